@@ -0,0 +1,52 @@
+use {
+    crate::PostgresBackend,
+    chrono::{DateTime, Utc},
+    std::borrow::Cow,
+    stry_models::{Job, JobKind, JobState},
+};
+
+/// Handles any and all queries that deal with Jobs.
+#[cfg_attr(feature = "boxed-futures", stry_macros::box_async)]
+impl PostgresBackend {
+    #[tracing::instrument(skip(self, payload), err)]
+    pub async fn add_job(
+        &self,
+        kind: JobKind,
+        payload: Cow<'static, str>,
+        priority: i32,
+        run_at: DateTime<Utc>,
+        max_attempts: i32,
+    ) -> anyhow::Result<String> {
+        anyhow::bail!("Jobs are not supported by the PostgreSQL backend")
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn claim_job(&self, kinds: Vec<JobKind>) -> anyhow::Result<Option<Job>> {
+        anyhow::bail!("Jobs are not supported by the PostgreSQL backend")
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn complete_job(&self, id: Cow<'static, str>) -> anyhow::Result<()> {
+        anyhow::bail!("Jobs are not supported by the PostgreSQL backend")
+    }
+
+    #[tracing::instrument(skip(self, error), err)]
+    pub async fn fail_job(
+        &self,
+        id: Cow<'static, str>,
+        error: Cow<'static, str>,
+        retry_at: DateTime<Utc>,
+    ) -> anyhow::Result<JobState> {
+        anyhow::bail!("Jobs are not supported by the PostgreSQL backend")
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn reset_jobs(&self) -> anyhow::Result<usize> {
+        anyhow::bail!("Jobs are not supported by the PostgreSQL backend")
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn dead_jobs(&self) -> anyhow::Result<Vec<Job>> {
+        anyhow::bail!("Jobs are not supported by the PostgreSQL backend")
+    }
+}
@@ -5,8 +5,11 @@ mod utils;
 mod author;
 mod chapter;
 mod character;
+mod job;
+mod maintenance;
 mod origin;
 mod pairing;
+mod setting;
 mod story;
 mod tag;
 mod warning;
@@ -0,0 +1,14 @@
+use {crate::PostgresBackend, std::borrow::Cow};
+
+#[cfg_attr(feature = "boxed-futures", stry_macros::box_async)]
+impl PostgresBackend {
+    #[tracing::instrument(skip(self), err)]
+    pub async fn vacuum(&self) -> anyhow::Result<()> {
+        anyhow::bail!("Maintenance is not supported by the PostgreSQL backend")
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn backup(&self, path: Cow<'static, str>) -> anyhow::Result<()> {
+        anyhow::bail!("Maintenance is not supported by the PostgreSQL backend")
+    }
+}
@@ -0,0 +1,37 @@
+use {crate::PostgresBackend, std::borrow::Cow, stry_models::Settings};
+
+/// Handles any and all queries that deal with Settings.
+#[cfg_attr(feature = "boxed-futures", stry_macros::box_async)]
+impl PostgresBackend {
+    #[tracing::instrument(skip(self), err)]
+    pub async fn get_setting(&self, key: Cow<'static, str>) -> anyhow::Result<Option<String>> {
+        anyhow::bail!("Settings are not supported by the PostgreSQL backend")
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn prefixed_settings(
+        &self,
+        prefix: Cow<'static, str>,
+    ) -> anyhow::Result<Vec<Settings>> {
+        anyhow::bail!("Settings are not supported by the PostgreSQL backend")
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn set_setting(
+        &self,
+        key: Cow<'static, str>,
+        value: Cow<'static, str>,
+    ) -> anyhow::Result<()> {
+        anyhow::bail!("Settings are not supported by the PostgreSQL backend")
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn swap_setting(
+        &self,
+        key: Cow<'static, str>,
+        current: Option<Cow<'static, str>>,
+        value: Cow<'static, str>,
+    ) -> anyhow::Result<bool> {
+        anyhow::bail!("Settings are not supported by the PostgreSQL backend")
+    }
+}
@@ -77,7 +77,7 @@ impl PostgresBackend {
             // pairing_stmt
             conn.prepare("SELECT C.Id, C.Platonic, C.Created, C.Updated FROM Pairing P LEFT JOIN PairingCharacter PC ON PC.PairingId = P.Id LEFT JOIN Character C ON PC.CharacterId = C.Id WHERE P.Id = $1 ORDER BY C.Name ASC;"),
             // story_row
-            conn.query_one("SELECT Id, Name, Summary, Rating, State, Created, Updated FROM Story WHERE Id = $1;", id_params),
+            conn.query_one("SELECT Id, Name, Summary, Rating, State, Created, Updated, Language FROM Story WHERE Id = $1;", id_params),
             // chapter_row
             conn.query_one(
                 "SELECT COUNT(StoryId) as Count FROM StoryChapter WHERE StoryId = $1;",
@@ -216,6 +216,8 @@ impl PostgresBackend {
             name: story_row.try_get(1)?,
             summary: story_row.try_get(2)?,
 
+            language: story_row.try_get::<_, String>(7)?.parse()?,
+
             square: Square {
                 rating: story_row.try_get(3)?,
                 warnings: !warnings.is_empty(),
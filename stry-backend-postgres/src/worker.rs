@@ -1,10 +1,60 @@
-use {crate::PostgresBackend, stry_models::WorkerTask};
+use {
+    crate::PostgresBackend,
+    chrono::{DateTime, Utc},
+    std::borrow::Cow,
+    stry_models::{WorkerSite, WorkerTask},
+};
 
 /// Handles any and all queries that deal with Workers.
 #[cfg_attr(feature = "boxed-futures", stry_macros::box_async)]
 impl PostgresBackend {
     #[tracing::instrument(skip(self), err)]
     pub async fn get_new_task(&self) -> anyhow::Result<Option<WorkerTask>> {
-        todo!()
+        anyhow::bail!("Worker tasks are not supported by the PostgreSQL backend")
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn all_tasks(&self) -> anyhow::Result<Vec<WorkerTask>> {
+        anyhow::bail!("Worker tasks are not supported by the PostgreSQL backend")
+    }
+
+    #[tracing::instrument(skip(self, error), err)]
+    pub async fn fail_task(
+        &self,
+        id: Cow<'static, str>,
+        error: Cow<'static, str>,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> anyhow::Result<()> {
+        anyhow::bail!("Worker tasks are not supported by the PostgreSQL backend")
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn update_task(
+        &self,
+        id: Cow<'static, str>,
+        chapter: i32,
+        chapters: i32,
+    ) -> anyhow::Result<()> {
+        anyhow::bail!("Worker tasks are not supported by the PostgreSQL backend")
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn complete_task(&self, id: Cow<'static, str>) -> anyhow::Result<()> {
+        anyhow::bail!("Worker tasks are not supported by the PostgreSQL backend")
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn requeue_tasks(&self) -> anyhow::Result<usize> {
+        anyhow::bail!("Worker tasks are not supported by the PostgreSQL backend")
+    }
+
+    #[tracing::instrument(skip(self), err)]
+    pub async fn add_task(
+        &self,
+        name: Cow<'static, str>,
+        site: WorkerSite,
+        url: Cow<'static, str>,
+    ) -> anyhow::Result<bool> {
+        anyhow::bail!("Worker tasks are not supported by the PostgreSQL backend")
     }
 }
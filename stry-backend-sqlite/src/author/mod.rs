@@ -2,12 +2,11 @@
 pub mod test;
 
 use {
-    crate::{utils::Total, SqliteBackend},
+    crate::{story, utils::Total, SqliteBackend},
     anyhow::Context,
     rewryte::sqlite::{ConnectionExt, StatementExt},
     std::borrow::Cow,
     stry_models::{Author, Entity, List, Story},
-    tracing_futures::Instrument,
 };
 
 #[cfg_attr(feature = "boxed-futures", stry_macros::box_async)]
@@ -135,20 +134,12 @@ impl SqliteBackend {
 
         let (total, entities) = ids.into_parts();
 
-        let mut items = Vec::with_capacity(limit as usize);
+        let ids: Vec<String> = entities.into_iter().map(|entity| entity.id).collect();
 
-        for Entity { id } in entities {
-            let story = match self
-                .get_story(id.into())
-                .instrument(tracing::trace_span!("get_story"))
-                .await?
-            {
-                Some(story) => story,
-                None => return Ok(None),
-            };
-
-            items.push(story);
-        }
+        let items = match story::loader::stories(self, &ids).await? {
+            Some(items) => items,
+            None => return Ok(None),
+        };
 
         Ok(Some(List { total, items }))
     }
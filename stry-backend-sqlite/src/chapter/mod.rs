@@ -64,6 +64,9 @@ impl SqliteBackend {
         })
         .await??;
 
+        // The word count of the story comes from its chapters
+        self.1.clear(&story_id);
+
         Ok(())
     }
 }
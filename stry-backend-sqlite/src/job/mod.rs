@@ -0,0 +1,224 @@
+#[cfg(test)]
+pub mod test;
+
+use {
+    crate::SqliteBackend,
+    chrono::{DateTime, Utc},
+    rewryte::sqlite::{ConnectionExt, StatementExt},
+    std::borrow::Cow,
+    stry_common::nanoid::nanoid,
+    stry_models::{Job, JobKind, JobState},
+};
+
+const COLUMNS: &str = "J.Id, J.Kind, J.Payload, J.Priority, J.RunAt, J.Attempts, J.MaxAttempts, J.State, J.Error, J.Created, J.Updated";
+
+#[cfg_attr(feature = "boxed-futures", stry_macros::box_async)]
+impl SqliteBackend {
+    /// Queues a job that is run once `run_at` has passed, returning the
+    /// job's ID.
+    #[tracing::instrument(level = "trace", skip(self, payload), err)]
+    pub async fn add_job(
+        &self,
+        kind: JobKind,
+        payload: Cow<'static, str>,
+        priority: i32,
+        run_at: DateTime<Utc>,
+        max_attempts: i32,
+    ) -> anyhow::Result<String> {
+        let id = tokio::task::spawn_blocking({
+            let inner = self.clone();
+
+            move || -> anyhow::Result<String> {
+                let conn = inner.0.get()?;
+
+                let id = nanoid();
+
+                conn.execute(
+                    "INSERT INTO Job(Id, Kind, Payload, Priority, RunAt, Attempts, MaxAttempts, State) VALUES (?, ?, ?, ?, ?, 0, ?, ?)",
+                    rusqlite::params![id, kind, payload, priority, run_at, max_attempts, JobState::Queued],
+                )?;
+
+                Ok(id)
+            }
+        })
+        .await??;
+
+        Ok(id)
+    }
+
+    /// Takes the queued job of one of the kinds with the highest priority
+    /// that is ready to run, marking it as running.
+    #[tracing::instrument(level = "trace", skip(self), err)]
+    pub async fn claim_job(&self, kinds: Vec<JobKind>) -> anyhow::Result<Option<Job>> {
+        if kinds.is_empty() {
+            return Ok(None);
+        }
+
+        let job = tokio::task::spawn_blocking({
+            let inner = self.clone();
+
+            move || -> anyhow::Result<Option<Job>> {
+                let mut conn = inner.0.get()?;
+
+                // Take the write lock up front, so two workers can't select
+                // the same job before either of them marks it as running
+                let trans =
+                    conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+
+                let queued = JobState::Queued;
+                let now = Utc::now();
+
+                let mut params: Vec<&dyn rusqlite::ToSql> = vec![&queued, &now];
+
+                params.extend(kinds.iter().map(|kind| kind as &dyn rusqlite::ToSql));
+
+                let query = format!(
+                    "SELECT {} FROM Job J WHERE J.State = ? AND J.RunAt <= ? AND J.Kind IN ({}) ORDER BY J.Priority DESC, J.RunAt ASC LIMIT 1",
+                    COLUMNS,
+                    vec!["?"; kinds.len()].join(", "),
+                );
+
+                let mut job: Job = match trans.type_query_one_opt(&query, &params[..])? {
+                    Some(job) => job,
+                    None => return Ok(None),
+                };
+
+                let claimed = trans.execute(
+                    "UPDATE Job SET State = ?, Attempts = Attempts + 1, Updated = CURRENT_TIMESTAMP WHERE Id = ? AND State = ?",
+                    rusqlite::params![JobState::Running, job.id, JobState::Queued],
+                )?;
+
+                if claimed == 0 {
+                    return Ok(None);
+                }
+
+                trans.commit()?;
+
+                job.state = JobState::Running;
+                job.attempts += 1;
+
+                Ok(Some(job))
+            }
+        })
+        .await??;
+
+        Ok(job)
+    }
+
+    #[tracing::instrument(level = "trace", skip(self), err)]
+    pub async fn complete_job(&self, id: Cow<'static, str>) -> anyhow::Result<()> {
+        tokio::task::spawn_blocking({
+            let inner = self.clone();
+
+            move || -> anyhow::Result<()> {
+                let conn = inner.0.get()?;
+
+                conn.execute(
+                    "UPDATE Job SET State = ?, Error = NULL, Updated = CURRENT_TIMESTAMP WHERE Id = ?",
+                    rusqlite::params![JobState::Completed, id],
+                )?;
+
+                Ok(())
+            }
+        })
+        .await??;
+
+        Ok(())
+    }
+
+    /// Records why a job failed, the job is queued again to run at `retry_at`
+    /// unless it has used all of its attempts, then it is dead.
+    #[tracing::instrument(level = "trace", skip(self, error), err)]
+    pub async fn fail_job(
+        &self,
+        id: Cow<'static, str>,
+        error: Cow<'static, str>,
+        retry_at: DateTime<Utc>,
+    ) -> anyhow::Result<JobState> {
+        let state = tokio::task::spawn_blocking({
+            let inner = self.clone();
+
+            move || -> anyhow::Result<JobState> {
+                let mut conn = inner.0.get()?;
+
+                let trans =
+                    conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+
+                let (attempts, max_attempts): (i32, i32) = trans.query_row(
+                    "SELECT Attempts, MaxAttempts FROM Job WHERE Id = ?",
+                    rusqlite::params![id],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )?;
+
+                let state = if attempts >= max_attempts {
+                    JobState::Dead
+                } else {
+                    JobState::Queued
+                };
+
+                trans.execute(
+                    "UPDATE Job SET State = ?, Error = ?, RunAt = ?, Updated = CURRENT_TIMESTAMP WHERE Id = ?",
+                    rusqlite::params![state, error, retry_at, id],
+                )?;
+
+                trans.commit()?;
+
+                Ok(state)
+            }
+        })
+        .await??;
+
+        Ok(state)
+    }
+
+    /// Queues the jobs that were left running, when stry stopped before they
+    /// finished.
+    #[tracing::instrument(level = "trace", skip(self), err)]
+    pub async fn reset_jobs(&self) -> anyhow::Result<usize> {
+        let reset = tokio::task::spawn_blocking({
+            let inner = self.clone();
+
+            move || -> anyhow::Result<usize> {
+                let conn = inner.0.get()?;
+
+                let reset = conn.execute(
+                    "UPDATE Job SET State = ?, Updated = CURRENT_TIMESTAMP WHERE State = ?",
+                    rusqlite::params![JobState::Queued, JobState::Running],
+                )?;
+
+                Ok(reset)
+            }
+        })
+        .await??;
+
+        Ok(reset)
+    }
+
+    /// Returns the jobs that have used all of their attempts, the most
+    /// recent first.
+    #[tracing::instrument(level = "trace", skip(self), err)]
+    pub async fn dead_jobs(&self) -> anyhow::Result<Vec<Job>> {
+        let jobs = tokio::task::spawn_blocking({
+            let inner = self.clone();
+
+            move || -> anyhow::Result<Vec<Job>> {
+                let conn = inner.0.get()?;
+
+                let mut stmt = conn.prepare(&format!(
+                    "SELECT {} FROM Job J WHERE J.State = ? ORDER BY J.Updated DESC",
+                    COLUMNS
+                ))?;
+
+                let jobs = match stmt.type_query_opt(rusqlite::params![JobState::Dead])? {
+                    Some(rows) => rows.collect::<Result<Vec<Job>, _>>()?,
+                    None => Vec::new(),
+                };
+
+                Ok(jobs)
+            }
+        })
+        .await??;
+
+        Ok(jobs)
+    }
+}
@@ -0,0 +1,113 @@
+use {
+    crate::test_utils::setup,
+    chrono::{Duration, Utc},
+    stry_models::{JobKind, JobState},
+    tokio::runtime::Runtime,
+};
+
+#[test]
+pub fn claim_by_priority() -> anyhow::Result<()> {
+    let mut rt = Runtime::new()?;
+
+    async fn run() -> anyhow::Result<Vec<Option<(String, i32)>>> {
+        let backend = setup()?;
+
+        let now = Utc::now() - Duration::seconds(1);
+
+        backend
+            .add_job(JobKind::Export, "{}".into(), 0, now, 5)
+            .await?;
+        backend
+            .add_job(JobKind::Export, "{\"low\":false}".into(), 10, now, 5)
+            .await?;
+        backend
+            .add_job(JobKind::Reindex, "{}".into(), 20, now, 5)
+            .await?;
+
+        let mut claimed = Vec::new();
+
+        for _ in 0..3 {
+            let job = backend.claim_job(vec![JobKind::Export]).await?;
+
+            claimed.push(job.map(|job| (job.payload, job.attempts)));
+        }
+
+        Ok(claimed)
+    }
+
+    assert_eq!(
+        vec![
+            Some(("{\"low\":false}".to_string(), 1)),
+            Some(("{}".to_string(), 1)),
+            None,
+        ],
+        rt.block_on(run())?,
+    );
+
+    Ok(())
+}
+
+#[test]
+pub fn claim_waits_for_run_at() -> anyhow::Result<()> {
+    let mut rt = Runtime::new()?;
+
+    async fn run() -> anyhow::Result<bool> {
+        let backend = setup()?;
+
+        backend
+            .add_job(
+                JobKind::Export,
+                "{}".into(),
+                0,
+                Utc::now() + Duration::hours(1),
+                5,
+            )
+            .await?;
+
+        let job = backend.claim_job(vec![JobKind::Export]).await?;
+
+        Ok(job.is_some())
+    }
+
+    assert!(!rt.block_on(run())?);
+
+    Ok(())
+}
+
+#[test]
+pub fn fail_until_dead() -> anyhow::Result<()> {
+    let mut rt = Runtime::new()?;
+
+    async fn run() -> anyhow::Result<(Vec<JobState>, usize)> {
+        let backend = setup()?;
+
+        let past = Utc::now() - Duration::seconds(1);
+
+        let id = backend
+            .add_job(JobKind::UpdateCheck, "{}".into(), 0, past, 2)
+            .await?;
+
+        let mut states = Vec::new();
+
+        while let Some(job) = backend.claim_job(vec![JobKind::UpdateCheck]).await? {
+            assert_eq!(id, job.id);
+
+            states.push(
+                backend
+                    .fail_job(job.id.into(), "failed".into(), past)
+                    .await?,
+            );
+        }
+
+        let dead = backend.dead_jobs().await?;
+
+        Ok((states, dead.len()))
+    }
+
+    assert_eq!(
+        (vec![JobState::Queued, JobState::Dead], 1),
+        rt.block_on(run())?,
+    );
+
+    Ok(())
+}
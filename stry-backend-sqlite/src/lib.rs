@@ -1,8 +1,12 @@
 mod author;
 mod chapter;
 mod character;
+mod job;
+mod maintenance;
+mod migration;
 mod origin;
 mod pairing;
+mod setting;
 mod story;
 mod tag;
 mod warning;
@@ -11,7 +15,7 @@ mod worker;
 mod utils;
 
 use {
-    crate::utils::SqliteConnectionManager,
+    crate::{story::loader::StoryLoaders, utils::SqliteConnectionManager},
     r2d2::Pool,
     std::sync::Arc,
     stry_common::LibraryDetails,
@@ -24,7 +28,7 @@ pub const SCHEMA: &str = rewryte::schema!("sqlite", "../schema.dal");
 pub const TEST_DATA: &str = include_str!("test-data.sql");
 
 #[derive(Clone, Debug)]
-pub struct SqliteBackend(Pool<SqliteConnectionManager>);
+pub struct SqliteBackend(Pool<SqliteConnectionManager>, StoryLoaders);
 
 #[cfg_attr(feature = "boxed-futures", stry_macros::box_async)]
 impl SqliteBackend {
@@ -51,12 +55,14 @@ impl SqliteBackend {
 
                     conn.execute_batch(SCHEMA)?;
 
+                    migration::migrate(&conn)?;
+
                     Ok(pool)
                 },
             )
             .await??;
 
-            Ok(Self(pool))
+            Ok(Self(pool.clone(), StoryLoaders::new(pool)))
         } else {
             anyhow::bail!("The `SQLite` backend can only use the `File` storage type");
         }
@@ -87,7 +93,7 @@ pub mod test_utils {
 
         let pool = Pool::new(manager)?;
 
-        Ok(SqliteBackend(pool))
+        Ok(SqliteBackend(pool.clone(), StoryLoaders::new(pool)))
     }
 }
 
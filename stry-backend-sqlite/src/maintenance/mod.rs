@@ -0,0 +1,43 @@
+use {crate::SqliteBackend, std::borrow::Cow};
+
+#[cfg_attr(feature = "boxed-futures", stry_macros::box_async)]
+impl SqliteBackend {
+    /// Rebuilds the database file to reclaim free space, and updates the
+    /// statistics used by the query planner.
+    #[tracing::instrument(level = "trace", skip(self), err)]
+    pub async fn vacuum(&self) -> anyhow::Result<()> {
+        tokio::task::spawn_blocking({
+            let inner = self.clone();
+
+            move || -> anyhow::Result<()> {
+                let conn = inner.0.get()?;
+
+                conn.execute_batch("VACUUM; ANALYZE;")?;
+
+                Ok(())
+            }
+        })
+        .await??;
+
+        Ok(())
+    }
+
+    /// Writes a copy of the database to the path, which must not exist.
+    #[tracing::instrument(level = "trace", skip(self), err)]
+    pub async fn backup(&self, path: Cow<'static, str>) -> anyhow::Result<()> {
+        tokio::task::spawn_blocking({
+            let inner = self.clone();
+
+            move || -> anyhow::Result<()> {
+                let conn = inner.0.get()?;
+
+                conn.execute("VACUUM INTO ?", rusqlite::params![path])?;
+
+                Ok(())
+            }
+        })
+        .await??;
+
+        Ok(())
+    }
+}
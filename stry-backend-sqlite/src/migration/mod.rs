@@ -0,0 +1,99 @@
+#[cfg(test)]
+pub mod test;
+
+use rusqlite::Connection;
+
+/// A column that was added to a table after it was first created.
+///
+/// `CREATE TABLE IF NOT EXISTS` leaves tables of older databases untouched,
+/// so the column has to be added by hand before anything queries it.
+struct Column {
+    table: &'static str,
+    name: &'static str,
+    definition: &'static str,
+}
+
+/// Columns added by each schema version, the index is the version minus one.
+const MIGRATIONS: &[&[Column]] = &[
+    &[
+        Column {
+            table: "WorkerTask",
+            name: "Failed",
+            definition: "BOOLEAN NOT NULL DEFAULT FALSE",
+        },
+        Column {
+            table: "WorkerTask",
+            name: "Error",
+            definition: "TEXT",
+        },
+        Column {
+            table: "WorkerTask",
+            name: "RetryAt",
+            definition: "TEXT",
+        },
+    ],
+    &[Column {
+        table: "Story",
+        name: "Language",
+        definition: "TEXT NOT NULL DEFAULT 'en'",
+    }],
+];
+
+/// Brings a database created by an older version up to the current schema.
+///
+/// Must be run after the schema has been loaded, the applied version is
+/// tracked with SQLite's `user_version` pragma.
+pub fn migrate(conn: &Connection) -> anyhow::Result<()> {
+    let version: u32 = conn.query_row("PRAGMA user_version;", rusqlite::params![], |row| {
+        row.get(0)
+    })?;
+
+    for (index, columns) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        conn.execute_batch("BEGIN;")?;
+
+        let applied = apply(conn, columns, index as u32 + 1);
+
+        match applied {
+            Ok(()) => conn.execute_batch("COMMIT;")?,
+            Err(err) => {
+                conn.execute_batch("ROLLBACK;")?;
+
+                return Err(err);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn apply(conn: &Connection, columns: &[Column], version: u32) -> anyhow::Result<()> {
+    for column in columns {
+        // new databases get the column from the schema itself
+        if has_column(conn, column.table, column.name)? {
+            continue;
+        }
+
+        conn.execute_batch(&format!(
+            "ALTER TABLE {} ADD COLUMN {} {};",
+            column.table, column.name, column.definition
+        ))?;
+    }
+
+    conn.execute_batch(&format!("PRAGMA user_version = {};", version))?;
+
+    Ok(())
+}
+
+fn has_column(conn: &Connection, table: &str, name: &str) -> anyhow::Result<bool> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({});", table))?;
+
+    let names = stmt.query_map(rusqlite::params![], |row| row.get::<_, String>(1))?;
+
+    for found in names {
+        if found? == name {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
@@ -0,0 +1,91 @@
+use {
+    crate::{migration::migrate, SCHEMA},
+    rusqlite::Connection,
+};
+
+#[test]
+pub fn adds_worker_task_columns_to_old_databases() -> anyhow::Result<()> {
+    let conn = Connection::open_in_memory()?;
+
+    conn.execute_batch(
+        "CREATE TABLE WorkerTask (
+            Id TEXT PRIMARY KEY,
+            Name TEXT NOT NULL,
+            Site TEXT NOT NULL,
+            Url TEXT NOT NULL,
+            Chapter INTEGER NOT NULL,
+            Chapters INTEGER NOT NULL,
+            Next INTEGER,
+            Completed BOOLEAN NOT NULL,
+            Created TEXT NOT NULL,
+            Updated TEXT NOT NULL
+        );
+        INSERT INTO WorkerTask(Id, Name, Site, Url, Chapter, Chapters, Completed, Created, Updated) VALUES
+            ('Vvm1rd', 'Story', 'archive-of-our-own', 'https://archiveofourown.org/works/1', 0, 0, FALSE, '2020-06-08 07:22:03', '2020-06-08 07:22:03');",
+    )?;
+
+    conn.execute_batch(SCHEMA)?;
+
+    migrate(&conn)?;
+    migrate(&conn)?;
+
+    let row: (bool, Option<String>, Option<String>) = conn.query_row(
+        "SELECT Failed, Error, RetryAt FROM WorkerTask WHERE Id = 'Vvm1rd';",
+        rusqlite::params![],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )?;
+
+    assert_eq!((false, None, None), row);
+
+    Ok(())
+}
+
+#[test]
+pub fn adds_story_language_to_old_databases() -> anyhow::Result<()> {
+    let conn = Connection::open_in_memory()?;
+
+    conn.execute_batch(
+        "CREATE TABLE Story (
+            Id TEXT PRIMARY KEY,
+            Name TEXT NOT NULL,
+            Summary TEXT NOT NULL,
+            Rating TEXT NOT NULL,
+            State TEXT NOT NULL,
+            Created TEXT NOT NULL,
+            Updated TEXT NOT NULL
+        );
+        INSERT INTO Story(Id, Name, Summary, Rating, State, Created, Updated) VALUES
+            ('uSPAbP', 'story 1', 'summary', 'teen', 'in-progress', '2020-06-08 07:22:03', '2020-06-08 07:22:03');",
+    )?;
+
+    conn.execute_batch(SCHEMA)?;
+
+    migrate(&conn)?;
+
+    let language: String = conn.query_row(
+        "SELECT Language FROM Story WHERE Id = 'uSPAbP';",
+        rusqlite::params![],
+        |row| row.get(0),
+    )?;
+
+    assert_eq!("en", language);
+
+    Ok(())
+}
+
+#[test]
+pub fn leaves_new_databases_alone() -> anyhow::Result<()> {
+    let conn = Connection::open_in_memory()?;
+
+    conn.execute_batch(SCHEMA)?;
+
+    migrate(&conn)?;
+
+    let version: u32 = conn.query_row("PRAGMA user_version;", rusqlite::params![], |row| {
+        row.get(0)
+    })?;
+
+    assert_eq!(2, version);
+
+    Ok(())
+}
@@ -0,0 +1,125 @@
+#[cfg(test)]
+pub mod test;
+
+use {
+    crate::SqliteBackend,
+    rewryte::sqlite::{ConnectionExt, StatementExt},
+    std::borrow::Cow,
+    stry_models::Settings,
+};
+
+#[cfg_attr(feature = "boxed-futures", stry_macros::box_async)]
+impl SqliteBackend {
+    #[tracing::instrument(level = "trace", skip(self), err)]
+    pub async fn get_setting(&self, key: Cow<'static, str>) -> anyhow::Result<Option<String>> {
+        let value = tokio::task::spawn_blocking({
+            let inner = self.clone();
+
+            move || -> anyhow::Result<Option<String>> {
+                let conn = inner.0.get()?;
+
+                let setting: Option<Settings> = conn.type_query_one_opt(
+                    "SELECT S.Key, S.Value, S.Created, S.Updated FROM Settings S WHERE S.Key = ?",
+                    rusqlite::params![key],
+                )?;
+
+                Ok(setting.map(|setting| setting.value))
+            }
+        })
+        .await??;
+
+        Ok(value)
+    }
+
+    /// Returns every setting with a key that starts with the prefix, ordered
+    /// by their key.
+    #[tracing::instrument(level = "trace", skip(self), err)]
+    pub async fn prefixed_settings(
+        &self,
+        prefix: Cow<'static, str>,
+    ) -> anyhow::Result<Vec<Settings>> {
+        let settings = tokio::task::spawn_blocking({
+            let inner = self.clone();
+
+            move || -> anyhow::Result<Vec<Settings>> {
+                let conn = inner.0.get()?;
+
+                let mut stmt = conn.prepare(
+                    "SELECT S.Key, S.Value, S.Created, S.Updated FROM Settings S WHERE substr(S.Key, 1, ?) = ? ORDER BY S.Key ASC",
+                )?;
+
+                let settings = match stmt
+                    .type_query_opt(rusqlite::params![prefix.chars().count() as i64, prefix])?
+                {
+                    Some(rows) => rows.collect::<Result<Vec<Settings>, _>>()?,
+                    None => Vec::new(),
+                };
+
+                Ok(settings)
+            }
+        })
+        .await??;
+
+        Ok(settings)
+    }
+
+    #[tracing::instrument(level = "trace", skip(self), err)]
+    pub async fn set_setting(
+        &self,
+        key: Cow<'static, str>,
+        value: Cow<'static, str>,
+    ) -> anyhow::Result<()> {
+        tokio::task::spawn_blocking({
+            let inner = self.clone();
+
+            move || -> anyhow::Result<()> {
+                let conn = inner.0.get()?;
+
+                conn.execute(
+                    "INSERT INTO Settings(Key, Value) VALUES (?, ?) ON CONFLICT(Key) DO UPDATE SET Value = excluded.Value, Updated = CURRENT_TIMESTAMP",
+                    rusqlite::params![key, value],
+                )?;
+
+                Ok(())
+            }
+        })
+        .await??;
+
+        Ok(())
+    }
+
+    /// Sets the value only if the setting still has the current value, or
+    /// does not exist when there is no current value. Returns if the value
+    /// was set.
+    #[tracing::instrument(level = "trace", skip(self), err)]
+    pub async fn swap_setting(
+        &self,
+        key: Cow<'static, str>,
+        current: Option<Cow<'static, str>>,
+        value: Cow<'static, str>,
+    ) -> anyhow::Result<bool> {
+        let swapped = tokio::task::spawn_blocking({
+            let inner = self.clone();
+
+            move || -> anyhow::Result<bool> {
+                let conn = inner.0.get()?;
+
+                let changed = match current {
+                    Some(current) => conn.execute(
+                        "UPDATE Settings SET Value = ?, Updated = CURRENT_TIMESTAMP WHERE Key = ? AND Value = ?",
+                        rusqlite::params![value, key, current],
+                    )?,
+                    None => conn.execute(
+                        "INSERT OR IGNORE INTO Settings(Key, Value) VALUES (?, ?)",
+                        rusqlite::params![key, value],
+                    )?,
+                };
+
+                Ok(changed != 0)
+            }
+        })
+        .await??;
+
+        Ok(swapped)
+    }
+}
@@ -0,0 +1,81 @@
+use {crate::test_utils::setup, tokio::runtime::Runtime};
+
+#[test]
+pub fn set_and_get() -> anyhow::Result<()> {
+    let mut rt = Runtime::new()?;
+
+    async fn run() -> anyhow::Result<(Option<String>, Option<String>)> {
+        let backend = setup()?;
+
+        let missing = backend
+            .get_setting("schedule.backup.last-run".into())
+            .await?;
+
+        backend
+            .set_setting("schedule.backup.last-run".into(), "first".into())
+            .await?;
+        backend
+            .set_setting("schedule.backup.last-run".into(), "second".into())
+            .await?;
+
+        let value = backend
+            .get_setting("schedule.backup.last-run".into())
+            .await?;
+
+        Ok((missing, value))
+    }
+
+    assert_eq!((None, Some("second".to_string())), rt.block_on(run())?);
+
+    Ok(())
+}
+
+#[test]
+pub fn swap() -> anyhow::Result<()> {
+    let mut rt = Runtime::new()?;
+
+    async fn run() -> anyhow::Result<Vec<bool>> {
+        let backend = setup()?;
+
+        let key = "schedule.vacuum.next-run";
+
+        Ok(vec![
+            backend.swap_setting(key.into(), None, "1".into()).await?,
+            backend.swap_setting(key.into(), None, "2".into()).await?,
+            backend
+                .swap_setting(key.into(), Some("2".into()), "3".into())
+                .await?,
+            backend
+                .swap_setting(key.into(), Some("1".into()), "3".into())
+                .await?,
+        ])
+    }
+
+    assert_eq!(vec![true, false, false, true], rt.block_on(run())?);
+
+    Ok(())
+}
+
+#[test]
+pub fn prefixed() -> anyhow::Result<()> {
+    let mut rt = Runtime::new()?;
+
+    async fn run() -> anyhow::Result<Vec<String>> {
+        let backend = setup()?;
+
+        for key in &["schedule.b", "schedule.a", "scheduler", "other"] {
+            backend.set_setting((*key).into(), "value".into()).await?;
+        }
+
+        let settings = backend.prefixed_settings("schedule.".into()).await?;
+
+        Ok(settings.into_iter().map(|setting| setting.key).collect())
+    }
+
+    assert_eq!(
+        vec!["schedule.a".to_string(), "schedule.b".to_string()],
+        rt.block_on(run())?
+    );
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+WITH Ids(Id) AS (VALUES {ids})
+SELECT A.Id, A.Name, A.Created, A.Updated, 'author' AS Type, SA.StoryId, SA.rowid AS Place
+FROM Author A
+LEFT JOIN StoryAuthor SA ON SA.AuthorId = A.Id
+WHERE SA.StoryId IN (SELECT Id FROM Ids)
+
+UNION ALL
+
+SELECT O.Id, O.Name, O.Created, O.Updated, 'origin' AS Type, SO.StoryId, SO.rowid AS Place
+FROM Origin O
+LEFT JOIN StoryOrigin SO ON SO.OriginId = O.Id
+WHERE SO.StoryId IN (SELECT Id FROM Ids)
+
+UNION ALL
+
+SELECT W.Id, W.Name, W.Created, W.Updated, 'warning' AS Type, SW.StoryId, SW.rowid AS Place
+FROM Warning W
+LEFT JOIN StoryWarning SW ON SW.WarningId = W.Id
+WHERE SW.StoryId IN (SELECT Id FROM Ids)
+
+UNION ALL
+
+SELECT C.Id, C.Name, C.Created, C.Updated, 'character' AS Type, SC.StoryId, SC.rowid AS Place
+FROM Character C
+LEFT JOIN StoryCharacter SC ON SC.CharacterId = C.Id
+WHERE SC.StoryId IN (SELECT Id FROM Ids)
+
+UNION ALL
+
+SELECT T.Id, T.Name, T.Created, T.Updated, 'tag' AS Type, ST.StoryId, ST.rowid AS Place
+FROM Tag T
+LEFT JOIN StoryTag ST ON ST.TagId = T.Id
+WHERE ST.StoryId IN (SELECT Id FROM Ids)
+
+ORDER BY 6, 7;
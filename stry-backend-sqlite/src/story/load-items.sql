@@ -0,0 +1,6 @@
+WITH Ids(Id) AS (VALUES {ids})
+SELECT
+    S.Id, S.Name, S.Created, S.Updated,
+    S.Summary, S.Rating, S.State, (SELECT COUNT(SC.StoryId) as Count FROM StoryChapter SC WHERE SC.StoryId = S.Id) AS Chapters, (SELECT SUM(C.Words) as Words FROM StoryChapter SC LEFT JOIN Chapter C ON C.Id = SC.ChapterId WHERE SC.StoryId = S.Id) AS Words, S.Language
+FROM Story S
+WHERE S.Id IN (SELECT Id FROM Ids);
@@ -0,0 +1,2 @@
+WITH Ids(Id) AS (VALUES {ids})
+SELECT C.Id, C.Name, C.Created, C.Updated, PC.PairingId FROM PairingCharacter PC LEFT JOIN Character C ON PC.CharacterId = C.Id WHERE PC.PairingId IN (SELECT Id FROM Ids) ORDER BY PC.PairingId, C.Name ASC;
@@ -0,0 +1,2 @@
+WITH Ids(Id) AS (VALUES {ids})
+SELECT P.Id, P.Hash, P.Platonic, P.Created, P.Updated, SP.StoryId FROM StoryPairing SP LEFT JOIN Pairing P ON P.Id = SP.PairingId WHERE SP.StoryId IN (SELECT Id FROM Ids) ORDER BY SP.StoryId, (SELECT GROUP_CONCAT(C.Name, '/') FROM PairingCharacter PC LEFT JOIN Character C ON C.Id = PC.CharacterId WHERE PC.PairingId = P.Id);
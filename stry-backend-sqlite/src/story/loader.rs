@@ -0,0 +1,425 @@
+//! Batched loading for stories, a page of stories is loaded with a query per
+//! part of a story instead of a query per story.
+//!
+//! The loaders are kept on the backend, so stories loaded at the same time by
+//! different requests share a query, and stay cached for a short while.
+
+use {
+    crate::{
+        utils::{self, SqliteConnectionManager},
+        SqliteBackend,
+    },
+    anyhow::Context,
+    r2d2::Pool,
+    rewryte::sqlite::{FromRow, StatementExt},
+    std::{
+        collections::HashMap,
+        fmt,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    },
+    stry_dataloader::{Fetcher, Loader},
+    stry_models::{
+        story::StoryRow, Author, Character, Origin, Pairing, PairingRow, Square, Story, Tag,
+        Warning,
+    },
+    tracing_futures::Instrument,
+};
+
+/// The most stories each loader keeps cached.
+const CAPACITY: usize = 1000;
+
+/// How long a loaded story is cached, stories can change when a task scrapes
+/// them.
+const TTL: Duration = Duration::from_secs(60);
+
+/// The loaders for each part of a story, shared by every clone of the
+/// backend.
+#[derive(Clone)]
+pub struct StoryLoaders {
+    rows: Loader<StoryFetcher, String, StoryRow>,
+    entities: Loader<EntityFetcher, String, StoryEntities>,
+    pairings: Loader<PairingFetcher, String, Vec<Pairing>>,
+
+    queries: Arc<AtomicUsize>,
+}
+
+impl StoryLoaders {
+    pub fn new(pool: Pool<SqliteConnectionManager>) -> Self {
+        let queries = Arc::new(AtomicUsize::new(0));
+
+        let fetcher = Queries {
+            pool,
+            count: queries.clone(),
+        };
+
+        Self {
+            rows: Loader::new(StoryFetcher(fetcher.clone()))
+                .capacity(CAPACITY)
+                .ttl(TTL),
+            entities: Loader::new(EntityFetcher(fetcher.clone()))
+                .capacity(CAPACITY)
+                .ttl(TTL),
+            pairings: Loader::new(PairingFetcher(fetcher))
+                .capacity(CAPACITY)
+                .ttl(TTL),
+
+            queries,
+        }
+    }
+
+    /// Forgets the cached parts of the story, so the next load sees any
+    /// changes to it.
+    pub fn clear(&self, id: &str) {
+        let id = id.to_string();
+
+        self.rows.clear(&id);
+        self.entities.clear(&id);
+        self.pairings.clear(&id);
+    }
+
+    /// The number of queries run by the loaders.
+    pub fn queries(&self) -> usize {
+        self.queries.load(Ordering::SeqCst)
+    }
+}
+
+impl fmt::Debug for StoryLoaders {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StoryLoaders")
+            .field("stories", &self.rows.cache().len())
+            .field("queries", &self.queries())
+            .finish()
+    }
+}
+
+/// The connection pool of the fetchers, counting the queries they run.
+#[derive(Clone)]
+struct Queries {
+    pool: Pool<SqliteConnectionManager>,
+    count: Arc<AtomicUsize>,
+}
+
+impl Queries {
+    fn ran(&self) {
+        self.count.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// The entities a story is tagged with.
+#[derive(Clone, Debug, Default)]
+pub struct StoryEntities {
+    pub authors: Vec<Author>,
+    pub origins: Vec<Origin>,
+
+    pub warnings: Vec<Warning>,
+    pub characters: Vec<Character>,
+    pub tags: Vec<Tag>,
+}
+
+enum Wrap {
+    Author(Author),
+    Origin(Origin),
+    Warning(Warning),
+    Character(Character),
+    Tag(Tag),
+}
+
+/// Fetches the rows of stories, keyed by story id.
+pub struct StoryFetcher(Queries);
+
+#[async_trait::async_trait]
+impl Fetcher<String, StoryRow> for StoryFetcher {
+    async fn fetch(&self, keys: &[String]) -> anyhow::Result<HashMap<String, StoryRow>> {
+        let span = tracing::debug_span!("fetch_stories", keys = keys.len());
+
+        tokio::task::spawn_blocking({
+            let queries = self.0.clone();
+            let keys = keys.to_vec();
+            let span = span.clone();
+
+            move || -> anyhow::Result<HashMap<String, StoryRow>> {
+                let _entered = span.enter();
+
+                let conn = queries.pool.get()?;
+
+                let mut stmt = tracing::trace_span!("prepare").in_scope(|| {
+                    conn.prepare(&format!(
+                        include_str!("load-items.sql"),
+                        ids = utils::values_list(keys.len())
+                    ))
+                })?;
+
+                let rows = tracing::trace_span!("get_rows").in_scope(|| stmt.type_query_opt(&keys));
+
+                queries.ran();
+
+                let rows: Vec<StoryRow> =
+                    match rows?.map(|items| items.collect::<Result<Vec<StoryRow>, _>>()) {
+                        Some(items) => items?,
+                        None => Vec::new(),
+                    };
+
+                Ok(rows.into_iter().map(|row| (row.id.clone(), row)).collect())
+            }
+        })
+        .instrument(span)
+        .await?
+    }
+}
+
+/// Fetches the authors, origins, warnings, characters, and tags of stories,
+/// keyed by story id.
+pub struct EntityFetcher(Queries);
+
+#[async_trait::async_trait]
+impl Fetcher<String, StoryEntities> for EntityFetcher {
+    async fn fetch(&self, keys: &[String]) -> anyhow::Result<HashMap<String, StoryEntities>> {
+        let span = tracing::debug_span!("fetch_entities", keys = keys.len());
+
+        tokio::task::spawn_blocking({
+            let queries = self.0.clone();
+            let keys = keys.to_vec();
+            let span = span.clone();
+
+            move || -> anyhow::Result<HashMap<String, StoryEntities>> {
+                let _entered = span.enter();
+
+                let conn = queries.pool.get()?;
+
+                let mut stmt = tracing::trace_span!("prepare").in_scope(|| {
+                    conn.prepare(&format!(
+                        include_str!("load-entities.sql"),
+                        ids = utils::values_list(keys.len())
+                    ))
+                })?;
+
+                queries.ran();
+
+                let rows = tracing::trace_span!("get_rows").in_scope(|| {
+                    stmt.query_opt(&keys, |row| {
+                        let typ: String = row
+                            .get(4)
+                            .context("Attempting to get row index 4 for story entity")?;
+                        let story: String = row
+                            .get(5)
+                            .context("Attempting to get row index 5 for story entity")?;
+
+                        let wrap = match typ.as_str() {
+                            "author" => Wrap::Author(
+                                Author::from_row(row)
+                                    .context("Attempting to get author for story (row)")?,
+                            ),
+                            "origin" => Wrap::Origin(
+                                Origin::from_row(row)
+                                    .context("Attempting to get origin for story (row)")?,
+                            ),
+                            "warning" => Wrap::Warning(
+                                Warning::from_row(row)
+                                    .context("Attempting to get warning for story (row)")?,
+                            ),
+                            "character" => Wrap::Character(
+                                Character::from_row(row)
+                                    .context("Attempting to get character for story (row)")?,
+                            ),
+                            "tag" => Wrap::Tag(
+                                Tag::from_row(row)
+                                    .context("Attempting to get tag for story (row)")?,
+                            ),
+                            other => anyhow::bail!("Unknown row group type `{}`", other),
+                        };
+
+                        Ok((story, wrap))
+                    })
+                })?;
+
+                let mut entities: HashMap<String, StoryEntities> = keys
+                    .iter()
+                    .map(|key| (key.clone(), StoryEntities::default()))
+                    .collect();
+
+                for row in rows.into_iter().flatten() {
+                    let (story, wrap) = row?;
+
+                    let entry = entities.entry(story).or_default();
+
+                    match wrap {
+                        Wrap::Author(item) => entry.authors.push(item),
+                        Wrap::Origin(item) => entry.origins.push(item),
+                        Wrap::Warning(item) => entry.warnings.push(item),
+                        Wrap::Character(item) => entry.characters.push(item),
+                        Wrap::Tag(item) => entry.tags.push(item),
+                    }
+                }
+
+                Ok(entities)
+            }
+        })
+        .instrument(span)
+        .await?
+    }
+}
+
+/// Fetches the pairings of stories, along with their characters, keyed by
+/// story id.
+pub struct PairingFetcher(Queries);
+
+#[async_trait::async_trait]
+impl Fetcher<String, Vec<Pairing>> for PairingFetcher {
+    async fn fetch(&self, keys: &[String]) -> anyhow::Result<HashMap<String, Vec<Pairing>>> {
+        let span = tracing::debug_span!("fetch_pairings", keys = keys.len());
+
+        tokio::task::spawn_blocking({
+            let queries = self.0.clone();
+            let keys = keys.to_vec();
+            let span = span.clone();
+
+            move || -> anyhow::Result<HashMap<String, Vec<Pairing>>> {
+                let _entered = span.enter();
+
+                let conn = queries.pool.get()?;
+
+                let mut pairing_stmt = tracing::trace_span!("prepare").in_scope(|| {
+                    conn.prepare(&format!(
+                        include_str!("load-pairings.sql"),
+                        ids = utils::values_list(keys.len())
+                    ))
+                })?;
+
+                queries.ran();
+
+                let rows = tracing::trace_span!("get_pairings").in_scope(|| {
+                    pairing_stmt.query_opt(&keys, |row| {
+                        let story: String = row
+                            .get(5)
+                            .context("Attempting to get row index 5 for story pairing")?;
+
+                        Ok((story, PairingRow::from_row(row)?))
+                    })
+                })?;
+
+                let parts = rows
+                    .into_iter()
+                    .flatten()
+                    .collect::<Result<Vec<(String, PairingRow)>, _>>()?;
+
+                let mut characters: HashMap<String, Vec<Character>> = HashMap::new();
+
+                if !parts.is_empty() {
+                    let ids: Vec<&str> = parts.iter().map(|(_, part)| part.id.as_str()).collect();
+
+                    let mut character_stmt = tracing::trace_span!("prepare").in_scope(|| {
+                        conn.prepare(&format!(
+                            include_str!("load-pairing-characters.sql"),
+                            ids = utils::values_list(ids.len())
+                        ))
+                    })?;
+
+                    queries.ran();
+
+                    let rows = tracing::trace_span!("get_characters").in_scope(|| {
+                        character_stmt.query_opt(&ids, |row| {
+                            let pairing: String = row
+                                .get(4)
+                                .context("Attempting to get row index 4 for pairing character")?;
+
+                            Ok((pairing, Character::from_row(row)?))
+                        })
+                    })?;
+
+                    for row in rows.into_iter().flatten() {
+                        let (pairing, character) = row?;
+
+                        characters.entry(pairing).or_default().push(character);
+                    }
+                }
+
+                let mut pairings: HashMap<String, Vec<Pairing>> =
+                    keys.into_iter().map(|key| (key, Vec::new())).collect();
+
+                for (story, part) in parts {
+                    pairings.entry(story).or_default().push(Pairing {
+                        characters: characters.get(&part.id).cloned().unwrap_or_default(),
+
+                        id: part.id,
+
+                        platonic: part.platonic,
+
+                        created: part.created,
+                        updated: part.updated,
+                    });
+                }
+
+                Ok(pairings)
+            }
+        })
+        .instrument(span)
+        .await?
+    }
+}
+
+/// Loads the stories in the order of their ids, returning `None` if any of
+/// them do not exist.
+#[tracing::instrument(level = "debug", skip(backend, ids), fields(stories = ids.len()), err)]
+pub async fn stories(
+    backend: &SqliteBackend,
+    ids: &[String],
+) -> anyhow::Result<Option<Vec<Story>>> {
+    if ids.is_empty() {
+        return Ok(Some(Vec::new()));
+    }
+
+    let loaders = &backend.1;
+
+    let (rows, entities, pairings) = tokio::try_join!(
+        loaders.rows.load_many(ids),
+        loaders.entities.load_many(ids),
+        loaders.pairings.load_many(ids),
+    )?;
+
+    if rows.len() != ids.len() {
+        return Ok(None);
+    }
+
+    let stories = rows
+        .into_iter()
+        .zip(entities)
+        .zip(pairings)
+        .map(|((story_row, entities), pairings)| Story {
+            id: story_row.id,
+
+            name: story_row.name,
+            summary: story_row.summary,
+
+            language: story_row.language,
+
+            square: Square {
+                rating: story_row.rating,
+                warnings: !entities.warnings.is_empty(),
+                state: story_row.state,
+            },
+
+            chapters: story_row.chapters,
+            words: story_row.words,
+
+            authors: entities.authors,
+            origins: entities.origins,
+
+            warnings: entities.warnings,
+            characters: entities.characters,
+            pairings,
+            tags: entities.tags,
+
+            // TODO
+            series: None,
+
+            created: story_row.created,
+            updated: story_row.updated,
+        })
+        .collect();
+
+    Ok(Some(stories))
+}
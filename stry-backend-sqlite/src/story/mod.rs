@@ -1,174 +1,20 @@
+#[cfg(test)]
+pub mod test;
+
+pub mod loader;
+
 use {
     crate::{
-        utils::{SqliteConnectionManager, Total, Wrapper},
+        utils::{Total, Wrapper},
         SqliteBackend,
     },
     anyhow::Context,
-    r2d2::PooledConnection,
-    rewryte::sqlite::{ConnectionExt, FromRow, StatementExt},
+    rewryte::sqlite::{ConnectionExt, StatementExt},
     std::borrow::Cow,
-    stry_models::{
-        story::StoryRow, Author, Character, Entity, List, Origin, Pairing, PairingRow, Square,
-        Story, Tag, Warning,
-    },
+    stry_models::{Entity, List, Story},
     stry_search::{SearchParser, Value},
 };
 
-enum Wrap {
-    Story(StoryRow),
-    Author(Author),
-    Origin(Origin),
-    Warning(Warning),
-    Character(Character),
-    Tag(Tag),
-}
-
-#[tracing::instrument(level = "trace", skip(conn), err)]
-pub fn get(
-    conn: &PooledConnection<SqliteConnectionManager>,
-    id: Cow<'static, str>,
-) -> anyhow::Result<Option<Story>> {
-    let (mut stmt, mut story_pairings_stmt, mut pairing_stmt) = tracing::trace_span!("prepare")
-        .in_scope(|| -> anyhow::Result<(_, _, _)> {
-            Ok((
-                conn.prepare(include_str!("get-item.sql"))?,
-                conn.prepare(include_str!("get-story-pairing.sql"))?,
-                conn.prepare(include_str!("get-pairing-character.sql"))?,
-            ))
-        })?;
-
-    let rows = tracing::trace_span!("get_rows").in_scope(|| {
-        stmt.query_opt(rusqlite::params![id, id, id, id, id, id], |row| {
-            let typ: String = row
-                .get(9)
-                .context("Attempting to get row index 8 for story")?;
-
-            match typ.as_str() {
-                "story" => Ok(Wrap::Story(
-                    StoryRow::from_row(row)
-                        .context("Attempting to get story row for story (row)")?,
-                )),
-                "author" => Ok(Wrap::Author(
-                    Author::from_row(row).context("Attempting to get author for story (row)")?,
-                )),
-                "origin" => Ok(Wrap::Origin(
-                    Origin::from_row(row).context("Attempting to get origin for story (row)")?,
-                )),
-                "warning" => Ok(Wrap::Warning(
-                    Warning::from_row(row).context("Attempting to get warning for story (row)")?,
-                )),
-                "character" => Ok(Wrap::Character(
-                    Character::from_row(row)
-                        .context("Attempting to get character for story (row)")?,
-                )),
-                "tag" => Ok(Wrap::Tag(
-                    Tag::from_row(row).context("Attempting to get tag for story (row)")?,
-                )),
-                other => anyhow::bail!("Unknown row group type `{}`", other),
-            }
-        })
-    })?;
-
-    let parts = match rows {
-        Some(parts) => parts,
-        None => return Ok(None),
-    };
-
-    let pairings =
-        match tracing::trace_span!("get_pairings").in_scope(|| -> anyhow::Result<_> {
-            let pairing_parts: Vec<PairingRow> = match story_pairings_stmt
-                .type_query_opt(rusqlite::params![id])?
-                .map(|items| items.collect::<Result<_, _>>())
-            {
-                Some(items) => items?,
-                None => return Ok(None),
-            };
-
-            let mut pairings = Vec::with_capacity(pairing_parts.len());
-
-            for part in pairing_parts {
-                let characters = match pairing_stmt
-                    .type_query_opt(rusqlite::params![part.id])?
-                    .map(|items| items.collect::<Result<_, _>>())
-                {
-                    Some(items) => items?,
-                    None => return Ok(None),
-                };
-
-                pairings.push(Pairing {
-                    id: part.id,
-
-                    characters,
-
-                    platonic: part.platonic,
-
-                    created: part.created,
-                    updated: part.updated,
-                });
-            }
-
-            Ok(Some(pairings))
-        })? {
-            Some(pairings) => pairings,
-            None => return Ok(None),
-        };
-
-    let mut story_row = None;
-
-    let mut authors = Vec::new();
-    let mut origins = Vec::new();
-
-    let mut warnings = Vec::new();
-    let mut characters = Vec::new();
-    let mut tags = Vec::new();
-
-    for row in parts {
-        match row? {
-            Wrap::Story(item) => {
-                story_row = Some(item);
-            }
-            Wrap::Author(item) => authors.push(item),
-            Wrap::Origin(item) => origins.push(item),
-            Wrap::Warning(item) => warnings.push(item),
-            Wrap::Character(item) => characters.push(item),
-            Wrap::Tag(item) => tags.push(item),
-        }
-    }
-
-    let story_row =
-        story_row.ok_or_else(|| anyhow::anyhow!("Story get did not return story group type"))?;
-
-    Ok(Some(Story {
-        id: story_row.id,
-
-        name: story_row.name,
-        summary: story_row.summary,
-
-        square: Square {
-            rating: story_row.rating,
-            warnings: !warnings.is_empty(),
-            state: story_row.state,
-        },
-
-        chapters: story_row.chapters,
-        words: story_row.words,
-
-        authors,
-        origins,
-
-        warnings,
-        characters,
-        pairings,
-        tags,
-
-        // TODO
-        series: None,
-
-        created: story_row.created,
-        updated: story_row.updated,
-    }))
-}
-
 #[cfg_attr(feature = "boxed-futures", stry_macros::box_async)]
 impl SqliteBackend {
     #[tracing::instrument(level = "trace", skip(self), err)]
@@ -177,10 +23,10 @@ impl SqliteBackend {
         offset: i32,
         limit: i32,
     ) -> anyhow::Result<Option<List<Story>>> {
-        let list = match tokio::task::spawn_blocking({
+        let ids = match tokio::task::spawn_blocking({
             let inner = self.clone();
 
-            move || -> anyhow::Result<Option<List<Story>>> {
+            move || -> anyhow::Result<Option<List<String>>> {
                 let conn = inner.0.get()?;
 
                 let mut stmt = tracing::trace_span!("prepare")
@@ -189,31 +35,18 @@ impl SqliteBackend {
                 let rows = tracing::trace_span!("get_rows")
                     .in_scope(|| {
                         stmt.query_opt(rusqlite::params![limit, offset * limit], |row| {
-                            Ok(Entity {
-                                id: row
-                                    .get(0)
-                                    .context("Attempting to get row index 0 for story id")?,
-                            })
+                            Ok(row
+                                .get(0)
+                                .context("Attempting to get row index 0 for story id")?)
                         })
                     })?
-                    .map(|items| items.collect::<Result<Vec<Entity>, _>>());
+                    .map(|items| items.collect::<Result<Vec<String>, _>>());
 
-                let entities = match rows {
+                let items = match rows {
                     Some(items) => items?,
                     None => return Ok(None),
                 };
 
-                let mut items = Vec::with_capacity(entities.len());
-
-                for entity in entities {
-                    let story = match get(&conn, entity.id.into())? {
-                        Some(items) => items,
-                        None => return Ok(None),
-                    };
-
-                    items.push(story);
-                }
-
                 let total = match tracing::trace_span!("get_count").in_scope(|| {
                     conn.query_one_opt(include_str!("all-count.sql"), rusqlite::params![], |row| {
                         Ok(row
@@ -235,30 +68,24 @@ impl SqliteBackend {
             None => return Ok(None),
         };
 
-        Ok(Some(list))
+        let (total, ids) = ids.into_parts();
+
+        let items = match loader::stories(self, &ids).await? {
+            Some(items) => items,
+            None => return Ok(None),
+        };
+
+        Ok(Some(List { total, items }))
     }
 
     #[tracing::instrument(level = "trace", skip(self), err)]
     pub async fn get_story(&self, id: Cow<'static, str>) -> anyhow::Result<Option<Story>> {
-        let story = match tokio::task::spawn_blocking({
-            let inner = self.clone();
-
-            move || -> anyhow::Result<Option<Story>> {
-                let conn = inner.0.get()?;
-
-                let story = get(&conn, id)?;
-
-                Ok(story)
-            }
-        })
-        .await?
-        .context("Unable to get story")?
-        {
-            Some(story_part) => story_part,
-            None => return Ok(None),
-        };
+        let story = loader::stories(self, &[id.into_owned()])
+            .await
+            .context("Unable to get story")?
+            .and_then(|mut stories| stories.pop());
 
-        Ok(Some(story))
+        Ok(story)
     }
 
     #[tracing::instrument(level = "trace", skip(self), err)]
@@ -268,11 +95,11 @@ impl SqliteBackend {
         offset: i32,
         limit: i32,
     ) -> anyhow::Result<Option<List<Story>>> {
-        let list = match tokio::task::spawn_blocking({
+        let ids = match tokio::task::spawn_blocking({
             let inner = self.clone();
             let input = input.to_owned();
 
-            move || -> anyhow::Result<Option<List<Story>>> {
+            move || -> anyhow::Result<Option<List<Entity>>> {
                 let search = SearchParser::parse_to_structure(&input)?;
 
                 let (and, not): (Vec<Value<'_>>, Vec<Value<'_>>) =
@@ -306,23 +133,12 @@ impl SqliteBackend {
                     })
                 })?;
 
-                let entities: Vec<Entity> =
+                let items: Vec<Entity> =
                     match rows.map(|items| items.collect::<Result<Vec<Entity>, _>>()) {
                         Some(items) => items?,
                         None => return Ok(None),
                     };
 
-                let mut items = Vec::with_capacity(entities.len());
-
-                for entity in entities {
-                    let story = match get(&conn, entity.id.into())? {
-                        Some(items) => items,
-                        None => return Ok(None),
-                    };
-
-                    items.push(story);
-                }
-
                 Ok(Some(List {
                     total: total.total,
                     items,
@@ -335,7 +151,16 @@ impl SqliteBackend {
             None => return Ok(None),
         };
 
-        Ok(Some(list))
+        let (total, entities) = ids.into_parts();
+
+        let ids: Vec<String> = entities.into_iter().map(|entity| entity.id).collect();
+
+        let items = match loader::stories(self, &ids).await? {
+            Some(items) => items,
+            None => return Ok(None),
+        };
+
+        Ok(Some(List { total, items }))
     }
 }
 
@@ -402,5 +227,9 @@ fn query_from_value<'p>(
             query_buff.push_str("SELECT Id, Updated AS StoryId FROM Story WHERE Rating = ?\n");
             param_buff.push(Wrapper::Rating(rating));
         }
+        Value::Language(_, language) => {
+            query_buff.push_str("SELECT Id, Updated AS StoryId FROM Story WHERE Language = ?\n");
+            param_buff.push(Wrapper::Cow(language.code().into()));
+        }
     }
 }
@@ -0,0 +1,91 @@
+use {
+    crate::{story::loader, test_utils::setup},
+    stry_models::{List, Story},
+    tokio::runtime::Runtime,
+};
+
+#[test]
+pub fn get_all_matches_get() -> anyhow::Result<()> {
+    let mut rt = Runtime::new()?;
+
+    async fn run() -> anyhow::Result<(Option<List<Story>>, Vec<Option<Story>>)> {
+        let backend = setup()?;
+
+        let stories = backend.all_stories(0, 10).await?;
+
+        let single = vec![
+            backend.get_story("pS8LfM".into()).await?,
+            backend.get_story("GQb4TP".into()).await?,
+        ];
+
+        Ok((stories, single))
+    }
+
+    let (stories, single) = rt.block_on(run())?;
+
+    let stories = stories.expect("No stories returned");
+
+    assert_eq!(2, stories.total);
+    assert_eq!(2, stories.items.len());
+
+    for story in &stories.items {
+        assert!(single.contains(&Some(story.clone())));
+    }
+
+    Ok(())
+}
+
+#[test]
+pub fn get_missing() -> anyhow::Result<()> {
+    let mut rt = Runtime::new()?;
+
+    async fn run() -> anyhow::Result<Option<Story>> {
+        let backend = setup()?;
+
+        let story = backend.get_story("missing".into()).await?;
+
+        Ok(story)
+    }
+
+    assert_eq!(None, rt.block_on(run())?);
+
+    Ok(())
+}
+
+#[test]
+pub fn loads_are_batched_and_cached() -> anyhow::Result<()> {
+    let mut rt = Runtime::new()?;
+
+    async fn run() -> anyhow::Result<(usize, usize, usize)> {
+        let backend = setup()?;
+
+        // The stories, entities, pairings and the pairing characters
+        loader::stories(&backend, &["pS8LfM".into(), "GQb4TP".into()]).await?;
+
+        let loaded = backend.1.queries();
+
+        loader::stories(&backend, &["pS8LfM".into(), "GQb4TP".into()]).await?;
+
+        let cached = backend.1.queries();
+
+        let backend = setup()?;
+
+        let (first, second) = tokio::join!(
+            loader::stories(&backend, &["pS8LfM".into()]),
+            loader::stories(&backend, &["GQb4TP".into()]),
+        );
+
+        assert!(first?.is_some());
+        assert!(second?.is_some());
+
+        Ok((loaded, cached, backend.1.queries()))
+    }
+
+    let (loaded, cached, concurrent) = rt.block_on(run())?;
+
+    assert_eq!(4, loaded);
+    assert_eq!(loaded, cached);
+    assert_eq!(4, concurrent);
+
+    Ok(())
+}
@@ -136,9 +136,9 @@ INSERT INTO Warning(Id, Name, Created, Updated) VALUES
 -- ============================================================================
 -- Stories
 -- ============================================================================
-INSERT INTO Story(Id, Name, Summary, Rating, State, Created, Updated) VALUES
-    ('pS8LfM', 'story 1', 'example story', 'teen', 'in-progress', '2020-06-08 07:22:03', '2020-06-08 07:22:03'),
-    ('GQb4TP', 'story 2', 'example story', 'mature', 'complete', '2020-06-08 07:22:03', '2020-06-08 07:22:03');
+INSERT INTO Story(Id, Name, Summary, Language, Rating, State, Created, Updated) VALUES
+    ('pS8LfM', 'story 1', 'example story', 'en', 'teen', 'in-progress', '2020-06-08 07:22:03', '2020-06-08 07:22:03'),
+    ('GQb4TP', 'story 2', 'example story', 'en', 'mature', 'complete', '2020-06-08 07:22:03', '2020-06-08 07:22:03');
 
 INSERT INTO StoryAuthor(StoryId, AuthorId, Created, Updated) VALUES
     ('pS8LfM', 'ZqYCf8', '2020-06-08 07:22:03', '2020-06-08 07:22:03');
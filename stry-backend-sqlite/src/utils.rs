@@ -173,3 +173,8 @@ impl FromRow for Total {
         })
     }
 }
+
+/// Creates the rows of a `VALUES` list with a placeholder for every key.
+pub fn values_list(len: usize) -> String {
+    vec!["(?)"; len].join(", ")
+}
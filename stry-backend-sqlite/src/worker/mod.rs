@@ -1,7 +1,19 @@
-use {crate::SqliteBackend, rewryte::sqlite::ConnectionExt, stry_models::WorkerTask};
+#[cfg(test)]
+pub mod test;
+
+use {
+    crate::SqliteBackend,
+    chrono::{DateTime, Utc},
+    rewryte::sqlite::{ConnectionExt, StatementExt},
+    std::borrow::Cow,
+    stry_common::nanoid::nanoid,
+    stry_models::{WorkerSite, WorkerTask},
+};
 
 #[cfg_attr(feature = "boxed-futures", stry_macros::box_async)]
 impl SqliteBackend {
+    /// Returns the task that has waited the longest, skipping tasks that are
+    /// waiting to be retried.
     #[tracing::instrument(level = "trace", skip(self), err)]
     pub async fn get_new_task(&self) -> anyhow::Result<Option<WorkerTask>> {
         let task = tokio::task::spawn_blocking({
@@ -11,8 +23,8 @@ impl SqliteBackend {
                 let conn = inner.0.get()?;
 
                 let task: WorkerTask = match conn.type_query_one_opt(
-                    "SELECT WT.Id, WT.Name, WT.Site, WT.Url, WT.Chapter, WT.Chapters, WT.Next, WT.Completed, WT.Created, WT.Updated FROM WorkerTask WT WHERE WT.Complete = TRUE AND WT.Id IS NOT IN (SELECT Task FROM Worker) LIMIT 1",
-                    rusqlite::params![],
+                    "SELECT WT.Id, WT.Name, WT.Site, WT.Url, WT.Chapter, WT.Chapters, WT.Next, WT.Completed, WT.Failed, WT.Error, WT.RetryAt, WT.Created, WT.Updated FROM WorkerTask WT WHERE WT.Completed = FALSE AND WT.Failed = FALSE AND (WT.RetryAt IS NULL OR WT.RetryAt <= ?) AND WT.Id NOT IN (SELECT Task FROM Worker WHERE Task IS NOT NULL) ORDER BY WT.Updated ASC LIMIT 1",
+                    rusqlite::params![Utc::now()],
                 )? {
                     Some(task) => task,
                     None => return Ok(None),
@@ -24,4 +36,160 @@ impl SqliteBackend {
 
         Ok(task)
     }
+
+    /// Returns every task that has not been completed, including failed
+    /// tasks, the oldest first.
+    #[tracing::instrument(level = "trace", skip(self), err)]
+    pub async fn all_tasks(&self) -> anyhow::Result<Vec<WorkerTask>> {
+        let tasks = tokio::task::spawn_blocking({
+            let inner = self.clone();
+
+            move || -> anyhow::Result<Vec<WorkerTask>> {
+                let conn = inner.0.get()?;
+
+                let mut stmt = conn.prepare(
+                    "SELECT WT.Id, WT.Name, WT.Site, WT.Url, WT.Chapter, WT.Chapters, WT.Next, WT.Completed, WT.Failed, WT.Error, WT.RetryAt, WT.Created, WT.Updated FROM WorkerTask WT WHERE WT.Completed = FALSE ORDER BY WT.Updated ASC",
+                )?;
+
+                let tasks = match stmt.type_query_opt(rusqlite::params![])? {
+                    Some(rows) => rows.collect::<Result<Vec<WorkerTask>, _>>()?,
+                    None => Vec::new(),
+                };
+
+                Ok(tasks)
+            }
+        })
+        .await??;
+
+        Ok(tasks)
+    }
+
+    /// Records why a task could not be finished, the task is given to workers
+    /// again once `retry_at` has passed, tasks without one have failed and are
+    /// not given to workers again.
+    #[tracing::instrument(level = "trace", skip(self, error), err)]
+    pub async fn fail_task(
+        &self,
+        id: Cow<'static, str>,
+        error: Cow<'static, str>,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> anyhow::Result<()> {
+        tokio::task::spawn_blocking({
+            let inner = self.clone();
+
+            move || -> anyhow::Result<()> {
+                let conn = inner.0.get()?;
+
+                conn.execute(
+                    "UPDATE WorkerTask SET Error = ?, Failed = ?, RetryAt = ?, Updated = CURRENT_TIMESTAMP WHERE Id = ?",
+                    rusqlite::params![error, retry_at.is_none(), retry_at, id],
+                )?;
+
+                Ok(())
+            }
+        })
+        .await??;
+
+        Ok(())
+    }
+
+    /// Records the last chapter that was fetched for the task, out of the
+    /// story's chapters.
+    #[tracing::instrument(level = "trace", skip(self), err)]
+    pub async fn update_task(
+        &self,
+        id: Cow<'static, str>,
+        chapter: i32,
+        chapters: i32,
+    ) -> anyhow::Result<()> {
+        tokio::task::spawn_blocking({
+            let inner = self.clone();
+
+            move || -> anyhow::Result<()> {
+                let conn = inner.0.get()?;
+
+                conn.execute(
+                    "UPDATE WorkerTask SET Chapter = ?, Chapters = ?, Updated = CURRENT_TIMESTAMP WHERE Id = ?",
+                    rusqlite::params![chapter, chapters, id],
+                )?;
+
+                Ok(())
+            }
+        })
+        .await??;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "trace", skip(self), err)]
+    pub async fn complete_task(&self, id: Cow<'static, str>) -> anyhow::Result<()> {
+        tokio::task::spawn_blocking({
+            let inner = self.clone();
+
+            move || -> anyhow::Result<()> {
+                let conn = inner.0.get()?;
+
+                conn.execute(
+                    "UPDATE WorkerTask SET Completed = TRUE, Error = NULL, RetryAt = NULL, Updated = CURRENT_TIMESTAMP WHERE Id = ?",
+                    rusqlite::params![id],
+                )?;
+
+                Ok(())
+            }
+        })
+        .await??;
+
+        Ok(())
+    }
+
+    /// Queues every completed task again, so their stories are checked for
+    /// new chapters. Returns the number of tasks queued.
+    #[tracing::instrument(level = "trace", skip(self), err)]
+    pub async fn requeue_tasks(&self) -> anyhow::Result<usize> {
+        let requeued = tokio::task::spawn_blocking({
+            let inner = self.clone();
+
+            move || -> anyhow::Result<usize> {
+                let conn = inner.0.get()?;
+
+                let requeued = conn.execute(
+                    "UPDATE WorkerTask SET Completed = FALSE, Updated = CURRENT_TIMESTAMP WHERE Completed = TRUE AND Failed = FALSE AND Url NOT IN (SELECT Url FROM WorkerTask WHERE Completed = FALSE)",
+                    rusqlite::params![],
+                )?;
+
+                Ok(requeued)
+            }
+        })
+        .await??;
+
+        Ok(requeued)
+    }
+
+    /// Queues a task for the story, unless the story already has a task that
+    /// has not been completed. Returns if the task was added.
+    #[tracing::instrument(level = "trace", skip(self), err)]
+    pub async fn add_task(
+        &self,
+        name: Cow<'static, str>,
+        site: WorkerSite,
+        url: Cow<'static, str>,
+    ) -> anyhow::Result<bool> {
+        let added = tokio::task::spawn_blocking({
+            let inner = self.clone();
+
+            move || -> anyhow::Result<bool> {
+                let conn = inner.0.get()?;
+
+                let changed = conn.execute(
+                    "INSERT INTO WorkerTask(Id, Name, Site, Url, Chapter, Chapters, Completed, Failed) SELECT ?, ?, ?, ?, 0, 0, FALSE, FALSE WHERE NOT EXISTS (SELECT 1 FROM WorkerTask WHERE Url = ? AND Completed = FALSE)",
+                    rusqlite::params![nanoid(), name, site, url, url],
+                )?;
+
+                Ok(changed != 0)
+            }
+        })
+        .await??;
+
+        Ok(added)
+    }
 }
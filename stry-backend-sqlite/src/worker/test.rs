@@ -0,0 +1,62 @@
+use {
+    crate::test_utils::setup,
+    chrono::{Duration, Utc},
+    stry_models::WorkerSite,
+    tokio::runtime::Runtime,
+};
+
+#[test]
+pub fn retry_waits_for_retry_at() -> anyhow::Result<()> {
+    let mut rt = Runtime::new()?;
+
+    async fn run() -> anyhow::Result<Vec<bool>> {
+        let backend = setup()?;
+
+        backend
+            .add_task(
+                "Story".into(),
+                WorkerSite::ArchiveOfOurOwn,
+                "https://archiveofourown.org/works/1".into(),
+            )
+            .await?;
+
+        let task = backend
+            .get_new_task()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Task was not added"))?;
+
+        let mut found = Vec::new();
+
+        backend
+            .fail_task(
+                task.id.clone().into(),
+                "Rate limited".into(),
+                Some(Utc::now() + Duration::hours(1)),
+            )
+            .await?;
+
+        found.push(backend.get_new_task().await?.is_some());
+
+        backend
+            .fail_task(
+                task.id.clone().into(),
+                "Rate limited".into(),
+                Some(Utc::now() - Duration::seconds(1)),
+            )
+            .await?;
+
+        found.push(backend.get_new_task().await?.is_some());
+
+        backend
+            .fail_task(task.id.clone().into(), "Not found".into(), None)
+            .await?;
+
+        found.push(backend.get_new_task().await?.is_some());
+
+        Ok(found)
+    }
+
+    assert_eq!(vec![false, true, false], rt.block_on(run())?);
+
+    Ok(())
+}
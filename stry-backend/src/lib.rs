@@ -1,9 +1,11 @@
 use {
+    chrono::{DateTime, Utc},
     std::{borrow::Cow, sync::Arc},
     stry_common::LibraryDetails,
     stry_config::{BackendType, StorageType},
     stry_models::{
-        Author, Chapter, Character, List, Origin, Pairing, Story, Tag, Warning, WorkerTask,
+        Author, Chapter, Character, Job, JobKind, JobState, List, Origin, Pairing, Payload,
+        Settings, Story, Tag, Warning, WorkerSite, WorkerTask,
     },
 };
 
@@ -72,6 +74,19 @@ impl DataBackend {
     }
 }
 
+impl DataBackend {
+    /// If the backend can store and run background jobs, the PostgreSQL
+    /// backend can't yet.
+    pub fn supports_jobs(&self) -> bool {
+        match &self.inner {
+            #[cfg(feature = "postgres")]
+            DataBackendInner::Postgres(_) => false,
+            #[cfg(feature = "sqlite")]
+            DataBackendInner::Sqlite(_) => true,
+        }
+    }
+}
+
 #[cfg_attr(feature = "boxed-futures", stry_macros::box_async)]
 impl DataBackend {
     pub async fn all_authors(
@@ -399,4 +414,236 @@ impl DataBackend {
             DataBackendInner::Sqlite(backend) => backend.get_new_task().await,
         }
     }
+
+    /// Returns every task that has not been completed, including failed
+    /// tasks.
+    pub async fn all_tasks(&self) -> anyhow::Result<Vec<WorkerTask>> {
+        match &self.inner {
+            #[cfg(feature = "postgres")]
+            DataBackendInner::Postgres(backend) => backend.all_tasks().await,
+            #[cfg(feature = "sqlite")]
+            DataBackendInner::Sqlite(backend) => backend.all_tasks().await,
+        }
+    }
+
+    /// Records why a task could not be finished, tasks without a `retry_at`
+    /// will not be retried.
+    pub async fn fail_task(
+        &self,
+        id: Cow<'static, str>,
+        error: Cow<'static, str>,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> anyhow::Result<()> {
+        match &self.inner {
+            #[cfg(feature = "postgres")]
+            DataBackendInner::Postgres(backend) => backend.fail_task(id, error, retry_at).await,
+            #[cfg(feature = "sqlite")]
+            DataBackendInner::Sqlite(backend) => backend.fail_task(id, error, retry_at).await,
+        }
+    }
+
+    /// Records the last chapter that was fetched for the task.
+    pub async fn update_task(
+        &self,
+        id: Cow<'static, str>,
+        chapter: i32,
+        chapters: i32,
+    ) -> anyhow::Result<()> {
+        match &self.inner {
+            #[cfg(feature = "postgres")]
+            DataBackendInner::Postgres(backend) => backend.update_task(id, chapter, chapters).await,
+            #[cfg(feature = "sqlite")]
+            DataBackendInner::Sqlite(backend) => backend.update_task(id, chapter, chapters).await,
+        }
+    }
+
+    pub async fn complete_task(&self, id: Cow<'static, str>) -> anyhow::Result<()> {
+        match &self.inner {
+            #[cfg(feature = "postgres")]
+            DataBackendInner::Postgres(backend) => backend.complete_task(id).await,
+            #[cfg(feature = "sqlite")]
+            DataBackendInner::Sqlite(backend) => backend.complete_task(id).await,
+        }
+    }
+
+    /// Queues every completed task again, to check their stories for new
+    /// chapters.
+    pub async fn requeue_tasks(&self) -> anyhow::Result<usize> {
+        match &self.inner {
+            #[cfg(feature = "postgres")]
+            DataBackendInner::Postgres(backend) => backend.requeue_tasks().await,
+            #[cfg(feature = "sqlite")]
+            DataBackendInner::Sqlite(backend) => backend.requeue_tasks().await,
+        }
+    }
+
+    /// Queues a task for the story, tasks are not added for stories that are
+    /// already waiting to be scraped.
+    pub async fn add_task(
+        &self,
+        name: Cow<'static, str>,
+        site: WorkerSite,
+        url: Cow<'static, str>,
+    ) -> anyhow::Result<bool> {
+        match &self.inner {
+            #[cfg(feature = "postgres")]
+            DataBackendInner::Postgres(backend) => backend.add_task(name, site, url).await,
+            #[cfg(feature = "sqlite")]
+            DataBackendInner::Sqlite(backend) => backend.add_task(name, site, url).await,
+        }
+    }
+    /// Queues a job with the payload, that is run once `run_at` has passed.
+    /// Jobs with a higher priority are run first.
+    pub async fn add_job<P>(
+        &self,
+        payload: &P,
+        priority: i32,
+        run_at: DateTime<Utc>,
+    ) -> anyhow::Result<String>
+    where
+        P: Payload + Sync,
+    {
+        let json = payload.to_json()?;
+
+        match &self.inner {
+            #[cfg(feature = "postgres")]
+            DataBackendInner::Postgres(backend) => {
+                backend
+                    .add_job(P::KIND, json.into(), priority, run_at, P::MAX_ATTEMPTS)
+                    .await
+            }
+            #[cfg(feature = "sqlite")]
+            DataBackendInner::Sqlite(backend) => {
+                backend
+                    .add_job(P::KIND, json.into(), priority, run_at, P::MAX_ATTEMPTS)
+                    .await
+            }
+        }
+    }
+
+    /// Takes the next job of one of the kinds that is ready to run.
+    pub async fn claim_job(&self, kinds: Vec<JobKind>) -> anyhow::Result<Option<Job>> {
+        match &self.inner {
+            #[cfg(feature = "postgres")]
+            DataBackendInner::Postgres(backend) => backend.claim_job(kinds).await,
+            #[cfg(feature = "sqlite")]
+            DataBackendInner::Sqlite(backend) => backend.claim_job(kinds).await,
+        }
+    }
+
+    pub async fn complete_job(&self, id: Cow<'static, str>) -> anyhow::Result<()> {
+        match &self.inner {
+            #[cfg(feature = "postgres")]
+            DataBackendInner::Postgres(backend) => backend.complete_job(id).await,
+            #[cfg(feature = "sqlite")]
+            DataBackendInner::Sqlite(backend) => backend.complete_job(id).await,
+        }
+    }
+
+    /// Records why a job failed, returning if the job is queued again or is
+    /// dead.
+    pub async fn fail_job(
+        &self,
+        id: Cow<'static, str>,
+        error: Cow<'static, str>,
+        retry_at: DateTime<Utc>,
+    ) -> anyhow::Result<JobState> {
+        match &self.inner {
+            #[cfg(feature = "postgres")]
+            DataBackendInner::Postgres(backend) => backend.fail_job(id, error, retry_at).await,
+            #[cfg(feature = "sqlite")]
+            DataBackendInner::Sqlite(backend) => backend.fail_job(id, error, retry_at).await,
+        }
+    }
+
+    /// Queues the jobs that were left running by the last run.
+    pub async fn reset_jobs(&self) -> anyhow::Result<usize> {
+        match &self.inner {
+            #[cfg(feature = "postgres")]
+            DataBackendInner::Postgres(backend) => backend.reset_jobs().await,
+            #[cfg(feature = "sqlite")]
+            DataBackendInner::Sqlite(backend) => backend.reset_jobs().await,
+        }
+    }
+
+    pub async fn dead_jobs(&self) -> anyhow::Result<Vec<Job>> {
+        match &self.inner {
+            #[cfg(feature = "postgres")]
+            DataBackendInner::Postgres(backend) => backend.dead_jobs().await,
+            #[cfg(feature = "sqlite")]
+            DataBackendInner::Sqlite(backend) => backend.dead_jobs().await,
+        }
+    }
+
+    pub async fn get_setting(&self, key: Cow<'static, str>) -> anyhow::Result<Option<String>> {
+        match &self.inner {
+            #[cfg(feature = "postgres")]
+            DataBackendInner::Postgres(backend) => backend.get_setting(key).await,
+            #[cfg(feature = "sqlite")]
+            DataBackendInner::Sqlite(backend) => backend.get_setting(key).await,
+        }
+    }
+
+    /// Returns every setting with a key that starts with the prefix.
+    pub async fn prefixed_settings(
+        &self,
+        prefix: Cow<'static, str>,
+    ) -> anyhow::Result<Vec<Settings>> {
+        match &self.inner {
+            #[cfg(feature = "postgres")]
+            DataBackendInner::Postgres(backend) => backend.prefixed_settings(prefix).await,
+            #[cfg(feature = "sqlite")]
+            DataBackendInner::Sqlite(backend) => backend.prefixed_settings(prefix).await,
+        }
+    }
+
+    pub async fn set_setting(
+        &self,
+        key: Cow<'static, str>,
+        value: Cow<'static, str>,
+    ) -> anyhow::Result<()> {
+        match &self.inner {
+            #[cfg(feature = "postgres")]
+            DataBackendInner::Postgres(backend) => backend.set_setting(key, value).await,
+            #[cfg(feature = "sqlite")]
+            DataBackendInner::Sqlite(backend) => backend.set_setting(key, value).await,
+        }
+    }
+
+    /// Sets the value only if the setting still has the current value, or
+    /// does not exist when there is no current value. Returns if the value
+    /// was set.
+    pub async fn swap_setting(
+        &self,
+        key: Cow<'static, str>,
+        current: Option<Cow<'static, str>>,
+        value: Cow<'static, str>,
+    ) -> anyhow::Result<bool> {
+        match &self.inner {
+            #[cfg(feature = "postgres")]
+            DataBackendInner::Postgres(backend) => backend.swap_setting(key, current, value).await,
+            #[cfg(feature = "sqlite")]
+            DataBackendInner::Sqlite(backend) => backend.swap_setting(key, current, value).await,
+        }
+    }
+
+    /// Rebuilds the database and updates its statistics.
+    pub async fn vacuum(&self) -> anyhow::Result<()> {
+        match &self.inner {
+            #[cfg(feature = "postgres")]
+            DataBackendInner::Postgres(backend) => backend.vacuum().await,
+            #[cfg(feature = "sqlite")]
+            DataBackendInner::Sqlite(backend) => backend.vacuum().await,
+        }
+    }
+
+    /// Writes a copy of the database to the path.
+    pub async fn backup(&self, path: Cow<'static, str>) -> anyhow::Result<()> {
+        match &self.inner {
+            #[cfg(feature = "postgres")]
+            DataBackendInner::Postgres(backend) => backend.backup(path).await,
+            #[cfg(feature = "sqlite")]
+            DataBackendInner::Sqlite(backend) => backend.backup(path).await,
+        }
+    }
 }
@@ -1,6 +1,6 @@
 use {
     anyhow::Context,
-    std::{collections::HashMap, str::FromStr},
+    std::{collections::HashMap, fmt, str::FromStr},
 };
 
 #[cfg(feature = "sources")]
@@ -16,7 +16,9 @@ pub enum Error {
     InvalidFrontendValue { value: String },
     #[error("Log level can only be `error`, `warn`, `info`, `debug` or `trace`, found '{value}'")]
     InvalidLogLevel { value: String },
-    #[error("Worker count can only be a multiple of 4 (up to 32), found '{value}'")]
+    #[error("Restart policy can only be `always`, `on-failure` or `never`, found '{value}'")]
+    InvalidRestartPolicy { value: String },
+    #[error("Worker count can only be a number from 1 to 255, found '{value}'")]
     InvalidWorkerCountValue { value: String },
 }
 
@@ -27,10 +29,19 @@ pub struct Config {
     pub port: u16,
     pub tls: Tls,
     pub frontend: Frontend,
-    pub workers: FourCount,
+    /// The number of workers started, more can be added while running.
+    #[serde(deserialize_with = "deserialize_worker_count")]
+    pub workers: u8,
+    pub restart: Restart,
+    /// Seconds the workers have to finish their tasks after the shutdown
+    /// signal, before the tasks are dropped.
+    pub drain_timeout: u64,
+    /// Jobs that are run whenever their cron expression matches.
+    pub schedule: Vec<Schedule>,
     pub database: Database,
     pub executor: Executor,
     pub logging: Logging,
+    pub scraper: Scraper,
 }
 
 impl Config {
@@ -47,9 +58,13 @@ impl Config {
             tls,
             frontend,
             workers,
+            restart,
+            drain_timeout,
+            schedule,
             database,
             executor,
             logging,
+            scraper,
         } = if cfg_path.exists() {
             let file = fs::OpenOptions::new().read(true).open(cfg_path)?;
             let mut reader = io::BufReader::new(file);
@@ -58,7 +73,15 @@ impl Config {
 
             reader.read_to_string(&mut contents)?;
 
-            ron::de::from_str(&contents)?
+            let mut cfg: Config = ron::de::from_str(&contents)?;
+
+            if cfg.workers == 0 {
+                cfg.workers = ron::de::from_str::<NamedWorkerCount>(&contents)?
+                    .workers
+                    .count();
+            }
+
+            cfg
         } else {
             Config::default()
         };
@@ -125,15 +148,22 @@ impl Config {
                 .or_else::<anyhow::Error, _>(|_| Ok(frontend))?,
             workers: env::var("STRY_WORKERS")
                 .context("Unable to get value of environmental variable `STRY_WORKERS`")
+                .and_then(|value| parse_worker_count(&value).map_err(Into::into))
+                .or_else::<anyhow::Error, _>(|_| Ok(workers))?,
+            restart: Restart::new_from_sources(restart, args.clone())?,
+            drain_timeout: env::var("STRY_DRAIN_TIMEOUT")
+                .context("Unable to get value of environmental variable `STRY_DRAIN_TIMEOUT`")
                 .and_then(|value| {
-                    let workers = FourCount::from_str(&value)?;
+                    let drain_timeout = value.parse()?;
 
-                    Ok(workers)
+                    Ok(drain_timeout)
                 })
-                .or_else::<anyhow::Error, _>(|_| Ok(workers))?,
+                .or_else::<anyhow::Error, _>(|_| Ok(drain_timeout))?,
+            schedule,
             database: Database::new_from_sources(database, args.clone())?,
             executor: Executor::new_from_sources(executor, args.clone())?,
             logging: Logging::new_from_sources(logging, args.clone())?,
+            scraper: Scraper::new_from_sources(scraper, args.clone())?,
         })
     }
 }
@@ -145,14 +175,164 @@ impl Default for Config {
             port: 8901,
             tls: Tls::None,
             frontend: Frontend::Both,
-            workers: FourCount::Four,
+            workers: 4,
+            restart: Restart::default(),
+            drain_timeout: 30,
+            schedule: Vec::new(),
             database: Database::default(),
             executor: Executor::default(),
             logging: Logging::default(),
+            scraper: Scraper::default(),
+        }
+    }
+}
+
+/// Checks the worker count is within 1 to 255, for both the config file and
+/// `STRY_WORKERS`.
+fn worker_count(value: u64) -> Result<u8, Error> {
+    match value {
+        1..=255 => Ok(value as u8),
+        _ => Err(Error::InvalidWorkerCountValue {
+            value: value.to_string(),
+        }),
+    }
+}
+
+/// Parses a worker count written as a number or as one of its old names.
+fn parse_worker_count(value: &str) -> Result<u8, Error> {
+    let value = value.trim();
+
+    if let Some(name) = FourCount::ALL
+        .iter()
+        .find(|name| format!("{:?}", name) == value)
+    {
+        return Ok(name.count());
+    }
+
+    value
+        .parse()
+        .map_err(|_| Error::InvalidWorkerCountValue {
+            value: value.to_string(),
+        })
+        .and_then(worker_count)
+}
+
+/// Reads the worker count from a number, or from one of the names it was
+/// written as before it became a number, such as `workers: Eight`.
+///
+/// RON reads a bare name as `()` unless it is asked for an enum, which would
+/// then refuse numbers, so a name is returned as `0` and read again as a
+/// [`FourCount`] by [`Config::new_from_sources`].
+fn deserialize_worker_count<'de, D>(deserializer: D) -> Result<u8, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    struct WorkerCountVisitor;
+
+    impl<'de> serde::de::Visitor<'de> for WorkerCountVisitor {
+        type Value = u8;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a worker count from 1 to 255")
+        }
+
+        fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            worker_count(value).map_err(E::custom)
+        }
+
+        fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            if value < 0 {
+                return Err(E::custom(Error::InvalidWorkerCountValue {
+                    value: value.to_string(),
+                }));
+            }
+
+            self.visit_u64(value as u64)
+        }
+
+        fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            parse_worker_count(value).map_err(E::custom)
+        }
+
+        fn visit_unit<E>(self) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(0)
+        }
+
+        fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+        where
+            A: serde::de::EnumAccess<'de>,
+        {
+            use serde::de::{Error as _, VariantAccess};
+
+            let (name, variant) = data.variant::<String>()?;
+
+            variant.unit_variant()?;
+
+            parse_worker_count(&name).map_err(A::Error::custom)
+        }
+    }
+
+    deserializer.deserialize_any(WorkerCountVisitor)
+}
+
+/// The names the worker count was written as before it became a number.
+#[derive(Clone, Copy, Debug, serde::Deserialize)]
+enum FourCount {
+    Four,
+    Eight,
+    Twelve,
+    Sixteen,
+    Twenty,
+    TwentyFour,
+    TwentyEight,
+    ThirtyTwo,
+}
+
+impl FourCount {
+    const ALL: &'static [FourCount] = &[
+        FourCount::Four,
+        FourCount::Eight,
+        FourCount::Twelve,
+        FourCount::Sixteen,
+        FourCount::Twenty,
+        FourCount::TwentyFour,
+        FourCount::TwentyEight,
+        FourCount::ThirtyTwo,
+    ];
+
+    fn count(self) -> u8 {
+        match self {
+            FourCount::Four => 4,
+            FourCount::Eight => 8,
+            FourCount::Twelve => 12,
+            FourCount::Sixteen => 16,
+            FourCount::Twenty => 20,
+            FourCount::TwentyFour => 24,
+            FourCount::TwentyEight => 28,
+            FourCount::ThirtyTwo => 32,
         }
     }
 }
 
+/// Only the worker count, for reading an old name as an enum.
+#[cfg(feature = "sources")]
+#[derive(serde::Deserialize)]
+struct NamedWorkerCount {
+    workers: FourCount,
+}
+
 #[derive(Clone, Debug, serde::Deserialize)]
 pub enum Tls {
     File { cert: String, key: String },
@@ -202,57 +382,124 @@ impl FromStr for Frontend {
     }
 }
 
-#[rustfmt::skip]
-#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
-#[derive(serde::Deserialize, serde::Serialize)]
-pub enum FourCount {
-    Four,
-    Eight,
-    Twelve,
-    Sixteen,
-    Twenty,
-    TwentyFour,
-    TwentyEight,
-    ThirtyTwo,
+/// How the workers' tasks are restarted once they stop.
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct Restart {
+    pub policy: RestartPolicy,
+    /// Milliseconds before a task is restarted, doubled for every failure in a row.
+    pub backoff: u64,
+    /// The most milliseconds before a task is restarted.
+    pub max_backoff: u64,
+    /// Failures in a row before a worker gives up, `None` to never give up.
+    pub max_failures: Option<usize>,
 }
 
-impl FourCount {
-    pub fn as_count(self) -> usize {
-        match self {
-            FourCount::Four => 4,
-            FourCount::Eight => 8,
-            FourCount::Twelve => 12,
-            FourCount::Sixteen => 16,
-            FourCount::Twenty => 20,
-            FourCount::TwentyFour => 24,
-            FourCount::TwentyEight => 28,
-            FourCount::ThirtyTwo => 32,
+impl Restart {
+    #[cfg(feature = "sources")]
+    pub fn new_from_sources(restart: Restart, _args: clap::ArgMatches<'_>) -> anyhow::Result<Self> {
+        let Restart {
+            policy,
+            backoff,
+            max_backoff,
+            max_failures,
+        } = restart;
+
+        Ok(Self {
+            policy: env::var("STRY_RESTART_POLICY")
+                .context("Unable to get value of environmental variable `STRY_RESTART_POLICY`")
+                .and_then(|value| RestartPolicy::from_str(&value).map_err(anyhow::Error::from))
+                .or_else::<anyhow::Error, _>(|_| Ok(policy))?,
+            backoff: env::var("STRY_RESTART_BACKOFF")
+                .context("Unable to get value of environmental variable `STRY_RESTART_BACKOFF`")
+                .and_then(|value| {
+                    let backoff = value.parse()?;
+
+                    Ok(backoff)
+                })
+                .or_else::<anyhow::Error, _>(|_| Ok(backoff))?,
+            max_backoff: env::var("STRY_RESTART_MAX_BACKOFF")
+                .context("Unable to get value of environmental variable `STRY_RESTART_MAX_BACKOFF`")
+                .and_then(|value| {
+                    let max_backoff = value.parse()?;
+
+                    Ok(max_backoff)
+                })
+                .or_else::<anyhow::Error, _>(|_| Ok(max_backoff))?,
+            max_failures: env::var("STRY_RESTART_MAX_FAILURES")
+                .context(
+                    "Unable to get value of environmental variable `STRY_RESTART_MAX_FAILURES`",
+                )
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .or_else(|| max_failures),
+        })
+    }
+}
+
+impl Default for Restart {
+    fn default() -> Self {
+        Self {
+            policy: RestartPolicy::Always,
+            backoff: 1000,
+            max_backoff: 5 * 60 * 1000,
+            max_failures: None,
         }
     }
 }
 
-impl FromStr for FourCount {
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, serde::Deserialize)]
+pub enum RestartPolicy {
+    /// Restart the task whenever it stops.
+    Always,
+    /// Only restart the task if it stopped with an error.
+    OnFailure,
+    /// Never restart the task, the worker stops with it.
+    Never,
+}
+
+impl FromStr for RestartPolicy {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let lower = s.to_lowercase();
 
         match lower.as_str() {
-            "4" => Ok(FourCount::Four),
-            "8" => Ok(FourCount::Eight),
-            "12" => Ok(FourCount::Twelve),
-            "16" => Ok(FourCount::Sixteen),
-            "20" => Ok(FourCount::Twenty),
-            "24" => Ok(FourCount::TwentyFour),
-            "28" => Ok(FourCount::TwentyEight),
-            "32" => Ok(FourCount::ThirtyTwo),
-            _ => Err(Error::InvalidWorkerCountValue {
+            "always" => Ok(RestartPolicy::Always),
+            "on-failure" => Ok(RestartPolicy::OnFailure),
+            "never" => Ok(RestartPolicy::Never),
+            _ => Err(Error::InvalidRestartPolicy {
                 value: s.to_string(),
             }),
         }
     }
 }
 
+/// A job that is queued for the workers whenever its cron expression
+/// matches.
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct Schedule {
+    /// The name the job's last and next run are stored under.
+    pub name: String,
+    /// A cron expression with seconds, `0 0 4 * * *` runs the job at 4am
+    /// every day.
+    pub cron: String,
+    pub job: ScheduledJob,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub enum ScheduledJob {
+    /// Queues every scraped story again, to check them for new chapters.
+    UpdateCheck,
+    /// Rebuilds the database and updates its statistics.
+    Vacuum,
+    /// Copies the database into the directory.
+    Backup { directory: String },
+    /// Removes cached pages that have not been fetched in the number of
+    /// days, or every page.
+    PruneCache { older_than: Option<i64> },
+}
+
 #[derive(Clone, Debug, serde::Deserialize)]
 #[serde(default)]
 pub struct Database {
@@ -624,3 +871,256 @@ pub enum LoggingOutput {
         json: bool,
     },
 }
+
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct Scraper {
+    /// Directory of site definitions that are loaded when the workers start.
+    pub sites: Option<String>,
+    /// Seconds before a request is given up on.
+    pub timeout: u64,
+    /// Times a request is retried after being rate limited or a server error.
+    pub retries: u32,
+    /// Milliseconds to wait before the first retry, doubled for every retry
+    /// after it. A `Retry-After` header is used instead when it is sent.
+    pub backoff: u64,
+    /// Rate limit for every host that is not listed in `hosts`.
+    pub rate_limit: RateLimit,
+    /// Rate limits for specific hosts, such as `archiveofourown.org`.
+    pub hosts: HashMap<String, RateLimit>,
+    pub cache: Cache,
+    pub images: Images,
+    /// Accounts used to view restricted stories, keyed by host.
+    pub accounts: HashMap<String, Account>,
+}
+
+impl Scraper {
+    #[cfg(feature = "sources")]
+    pub fn new_from_sources(scraper: Scraper, args: clap::ArgMatches<'_>) -> anyhow::Result<Self> {
+        let Scraper {
+            sites,
+            timeout,
+            retries,
+            backoff,
+            rate_limit,
+            hosts,
+            cache,
+            images,
+            accounts,
+        } = scraper;
+
+        Ok(Self {
+            sites: env::var("STRY_SCRAPER_SITES")
+                .context("Unable to get value of environmental variable `STRY_SCRAPER_SITES`")
+                .or_else(|_| {
+                    args.value_of("scraper-sites")
+                        .map(String::from)
+                        .ok_or_else(|| {
+                            anyhow::anyhow!(
+                                "No argument named 'scraper-sites' found in provided args"
+                            )
+                        })
+                })
+                .ok()
+                .or_else(|| sites),
+            timeout: env::var("STRY_SCRAPER_TIMEOUT")
+                .context("Unable to get value of environmental variable `STRY_SCRAPER_TIMEOUT`")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(timeout),
+            retries: env::var("STRY_SCRAPER_RETRIES")
+                .context("Unable to get value of environmental variable `STRY_SCRAPER_RETRIES`")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(retries),
+            backoff: env::var("STRY_SCRAPER_BACKOFF")
+                .context("Unable to get value of environmental variable `STRY_SCRAPER_BACKOFF`")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(backoff),
+            rate_limit: RateLimit::new_from_sources(rate_limit)?,
+            hosts,
+            cache: Cache::new_from_sources(cache)?,
+            images: Images::new_from_sources(images)?,
+            accounts,
+        })
+    }
+}
+
+impl Default for Scraper {
+    fn default() -> Self {
+        Self {
+            sites: None,
+            timeout: 30,
+            retries: 5,
+            backoff: 1000,
+            rate_limit: RateLimit::default(),
+            hosts: HashMap::new(),
+            cache: Cache::default(),
+            images: Images::default(),
+            accounts: HashMap::new(),
+        }
+    }
+}
+
+/// A token bucket, each request to a host takes a token from its bucket and
+/// waits for one to be added back if it is empty.
+#[derive(Clone, Copy, Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct RateLimit {
+    /// Tokens the bucket can hold, the number of requests that can be made
+    /// at once.
+    pub burst: u32,
+    /// Milliseconds between each token being added to the bucket.
+    pub interval: u64,
+}
+
+impl RateLimit {
+    #[cfg(feature = "sources")]
+    pub fn new_from_sources(rate_limit: RateLimit) -> anyhow::Result<Self> {
+        let RateLimit { burst, interval } = rate_limit;
+
+        Ok(Self {
+            burst: env::var("STRY_SCRAPER_RATE_BURST")
+                .context("Unable to get value of environmental variable `STRY_SCRAPER_RATE_BURST`")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(burst),
+            interval: env::var("STRY_SCRAPER_RATE_INTERVAL")
+                .context(
+                    "Unable to get value of environmental variable `STRY_SCRAPER_RATE_INTERVAL`",
+                )
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(interval),
+        })
+    }
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            burst: 1,
+            interval: 5000,
+        }
+    }
+}
+
+#[derive(Clone, serde::Deserialize)]
+pub enum Account {
+    /// Log in using the site's login form.
+    Login { username: String, password: String },
+    /// Use the session from a Netscape format `cookies.txt`, such as one
+    /// exported from a browser.
+    Cookies { file: String },
+}
+
+// Keep passwords out of the logs
+impl fmt::Debug for Account {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Account::Login { username, .. } => f
+                .debug_struct("Login")
+                .field("username", username)
+                .field("password", &"***")
+                .finish(),
+            Account::Cookies { file } => f.debug_struct("Cookies").field("file", file).finish(),
+        }
+    }
+}
+
+/// On-disk cache of the pages fetched by the scraper.
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct Cache {
+    pub enabled: bool,
+    pub directory: String,
+    /// Only use cached pages and never make a request, used to replay a
+    /// scrape when debugging a site.
+    pub offline: bool,
+}
+
+impl Cache {
+    #[cfg(feature = "sources")]
+    pub fn new_from_sources(cache: Cache) -> anyhow::Result<Self> {
+        let Cache {
+            enabled,
+            directory,
+            offline,
+        } = cache;
+
+        Ok(Self {
+            enabled: env::var("STRY_SCRAPER_CACHE")
+                .context("Unable to get value of environmental variable `STRY_SCRAPER_CACHE`")
+                .map(|value| match &*value {
+                    "0" => false,
+                    _ => true,
+                })
+                .or_else::<anyhow::Error, _>(|_| Ok(enabled))?,
+            directory: env::var("STRY_SCRAPER_CACHE_DIRECTORY")
+                .context(
+                    "Unable to get value of environmental variable `STRY_SCRAPER_CACHE_DIRECTORY`",
+                )
+                .or_else::<anyhow::Error, _>(|_| Ok(directory))?,
+            offline: env::var("STRY_SCRAPER_CACHE_OFFLINE")
+                .context(
+                    "Unable to get value of environmental variable `STRY_SCRAPER_CACHE_OFFLINE`",
+                )
+                .map(|value| match &*value {
+                    "0" => false,
+                    _ => true,
+                })
+                .or_else::<anyhow::Error, _>(|_| Ok(offline))?,
+        })
+    }
+}
+
+impl Default for Cache {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: String::from("cache"),
+            offline: false,
+        }
+    }
+}
+
+/// Images found in chapters, downloaded so stories can be read without
+/// requests to the site they came from.
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct Images {
+    pub enabled: bool,
+    pub directory: String,
+}
+
+impl Images {
+    #[cfg(feature = "sources")]
+    pub fn new_from_sources(images: Images) -> anyhow::Result<Self> {
+        let Images { enabled, directory } = images;
+
+        Ok(Self {
+            enabled: env::var("STRY_SCRAPER_IMAGES")
+                .context("Unable to get value of environmental variable `STRY_SCRAPER_IMAGES`")
+                .map(|value| match &*value {
+                    "0" => false,
+                    _ => true,
+                })
+                .or_else::<anyhow::Error, _>(|_| Ok(enabled))?,
+            directory: env::var("STRY_SCRAPER_IMAGES_DIRECTORY")
+                .context(
+                    "Unable to get value of environmental variable `STRY_SCRAPER_IMAGES_DIRECTORY`",
+                )
+                .or_else::<anyhow::Error, _>(|_| Ok(directory))?,
+        })
+    }
+}
+
+impl Default for Images {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            directory: String::from("images"),
+        }
+    }
+}
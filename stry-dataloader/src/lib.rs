@@ -1,42 +1,191 @@
+//! A loader that batches the keys requested at the same time into a single
+//! fetch, and caches the fetched values.
+//!
+//! Keys that are not cached are queued, once the batch window has passed, or
+//! the batch is full, every queued key is given to the [`Fetcher`] at once.
+//! Loading a key that is already queued or being fetched waits on that fetch
+//! instead of fetching it again.
+
+#[cfg(test)]
+mod test;
+
 use {
     std::{
-        borrow::Borrow, cmp::Eq, collections::HashMap, fmt::Debug, hash::Hash, slice, sync::Arc,
+        collections::{BTreeMap, HashMap},
+        fmt::Debug,
+        hash::Hash,
+        mem, slice,
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
     },
-    tokio::sync::RwLock,
+    tokio::sync::oneshot,
+    tracing_futures::Instrument,
 };
 
 #[async_trait::async_trait]
 pub trait Fetcher<Key, Value> {
-    async fn fetch(&self, keys: &[Key], values: Cache<Key, Value>) -> anyhow::Result<()>;
+    /// Fetches the value of every key, keys without a value are left out.
+    async fn fetch(&self, keys: &[Key]) -> anyhow::Result<HashMap<Key, Value>>;
 }
 
+/// The values loaded by a [`Loader`], which can be limited to a number of
+/// values and how long they are kept.
 #[derive(Debug)]
 pub struct Cache<Key, Value> {
-    inner: Arc<RwLock<HashMap<Key, Value>>>,
+    inner: Arc<Mutex<CacheInner<Key, Value>>>,
+}
+
+#[derive(Debug)]
+struct CacheInner<Key, Value> {
+    entries: HashMap<Key, Entry<Value>>,
+    /// The keys ordered by when they were last used, the oldest first.
+    order: BTreeMap<u64, Key>,
+    tick: u64,
+
+    capacity: Option<usize>,
+    ttl: Option<Duration>,
+}
+
+#[derive(Debug)]
+struct Entry<Value> {
+    value: Value,
+    inserted: Instant,
+    used: u64,
 }
 
-impl<Key, Value> Cache<Key, Value> {
+impl<Key, Value> Cache<Key, Value>
+where
+    Key: Clone + Eq + Hash,
+    Value: Clone,
+{
     pub fn new() -> Self {
         Self::default()
     }
 
-    pub async fn insert(&self, key: Key, value: Value) -> Option<Value>
-    where
-        Key: Eq + Hash,
-    {
-        let mut map = self.inner.write().await;
+    /// Keeps at most the number of values, removing the least recently used
+    /// values first.
+    pub fn set_capacity(&self, capacity: Option<usize>) {
+        let mut inner = self.lock();
+
+        inner.capacity = capacity;
+        inner.evict();
+    }
+
+    /// Removes values once they have been cached for longer than the `ttl`.
+    pub fn set_ttl(&self, ttl: Option<Duration>) {
+        self.lock().ttl = ttl;
+    }
 
-        map.insert(key, value)
+    pub fn get(&self, key: &Key) -> Option<Value> {
+        self.lock().get(key)
     }
 
-    async fn exists<Q>(&self, key: &Q) -> bool
-    where
-        Key: Borrow<Q> + Eq + Hash,
-        Q: Eq + Hash,
-    {
-        let map = self.inner.read().await;
+    pub fn insert(&self, key: Key, value: Value) -> Option<Value> {
+        self.lock().insert(key, value)
+    }
 
-        map.contains_key(key)
+    pub fn remove(&self, key: &Key) -> Option<Value> {
+        self.lock().remove(key)
+    }
+
+    pub fn clear(&self) {
+        let mut inner = self.lock();
+
+        inner.entries.clear();
+        inner.order.clear();
+    }
+
+    /// The number of values cached, including expired values that have not
+    /// been removed yet.
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheInner<Key, Value>> {
+        self.inner.lock().expect("Loader cache lock poisoned")
+    }
+}
+
+impl<Key, Value> CacheInner<Key, Value>
+where
+    Key: Clone + Eq + Hash,
+    Value: Clone,
+{
+    fn get(&mut self, key: &Key) -> Option<Value> {
+        let expired = match (self.entries.get(key), self.ttl) {
+            (None, _) => return None,
+            (Some(entry), Some(ttl)) => entry.inserted.elapsed() >= ttl,
+            (Some(_), None) => false,
+        };
+
+        if expired {
+            self.remove(key);
+
+            return None;
+        }
+
+        self.tick += 1;
+
+        let entry = self.entries.get_mut(key)?;
+
+        self.order.remove(&entry.used);
+        entry.used = self.tick;
+        self.order.insert(self.tick, key.clone());
+
+        Some(entry.value.clone())
+    }
+
+    fn insert(&mut self, key: Key, value: Value) -> Option<Value> {
+        self.tick += 1;
+
+        let old = self.entries.insert(
+            key.clone(),
+            Entry {
+                value,
+                inserted: Instant::now(),
+                used: self.tick,
+            },
+        );
+
+        if let Some(old) = &old {
+            self.order.remove(&old.used);
+        }
+
+        self.order.insert(self.tick, key);
+
+        self.evict();
+
+        old.map(|entry| entry.value)
+    }
+
+    fn remove(&mut self, key: &Key) -> Option<Value> {
+        let entry = self.entries.remove(key)?;
+
+        self.order.remove(&entry.used);
+
+        Some(entry.value)
+    }
+
+    fn evict(&mut self) {
+        let capacity = match self.capacity {
+            Some(capacity) => capacity,
+            None => return,
+        };
+
+        while self.entries.len() > capacity {
+            let used = match self.order.keys().next() {
+                Some(used) => *used,
+                None => break,
+            };
+
+            if let Some(key) = self.order.remove(&used) {
+                self.entries.remove(&key);
+            }
+        }
     }
 }
 
@@ -51,25 +200,114 @@ impl<Key, Value> Clone for Cache<Key, Value> {
 impl<Key, Value> Default for Cache<Key, Value> {
     fn default() -> Self {
         Self {
-            inner: Arc::new(RwLock::new(HashMap::new())),
+            inner: Arc::new(Mutex::new(CacheInner {
+                entries: HashMap::new(),
+                order: BTreeMap::new(),
+                tick: 0,
+                capacity: None,
+                ttl: None,
+            })),
         }
     }
 }
 
+type Waiter<Value> = oneshot::Sender<Result<Option<Value>, Arc<anyhow::Error>>>;
+
+struct Batch<Key, Value> {
+    /// The keys waiting for the next fetch.
+    keys: Vec<Key>,
+    /// Everything waiting on a key, either in the next batch or being
+    /// fetched.
+    waiting: HashMap<Key, Vec<Waiter<Value>>>,
+    /// Set while a task is waiting out the batch window.
+    scheduled: bool,
+}
+
 pub struct Loader<F, Key, Value>
 where
     F: Fetcher<Key, Value>,
 {
+    fetcher: Arc<F>,
     cache: Cache<Key, Value>,
-    fetcher: F,
+    batch: Arc<Mutex<Batch<Key, Value>>>,
+
+    max_batch_size: usize,
+    window: Duration,
 }
 
 impl<F, Key, Value> Loader<F, Key, Value>
 where
-    F: Fetcher<Key, Value>,
-    Key: Debug + Eq + Hash,
-    Value: Clone,
+    F: Fetcher<Key, Value> + Send + Sync + 'static,
+    Key: Clone + Debug + Eq + Hash + Send + Sync + 'static,
+    Value: Clone + Send + Sync + 'static,
 {
+    /// Creates a loader that fetches up to 100 keys at once, batching the
+    /// keys loaded before the runtime switches tasks.
+    pub fn new(fetcher: F) -> Self {
+        Self {
+            fetcher: Arc::new(fetcher),
+            cache: Cache::new(),
+            batch: Arc::new(Mutex::new(Batch {
+                keys: Vec::new(),
+                waiting: HashMap::new(),
+                scheduled: false,
+            })),
+            max_batch_size: 100,
+            window: Duration::from_millis(0),
+        }
+    }
+
+    /// Sets the most keys given to a single fetch, full batches are fetched
+    /// without waiting for the batch window.
+    pub fn max_batch_size(mut self, size: usize) -> Self {
+        debug_assert!(size >= 1, "Batch size must not be 0");
+
+        self.max_batch_size = size.max(1);
+
+        self
+    }
+
+    /// Sets how long keys are collected before they are fetched.
+    pub fn batch_window(mut self, window: Duration) -> Self {
+        self.window = window;
+
+        self
+    }
+
+    /// Keeps at most the number of values, see [`Cache::set_capacity`].
+    pub fn capacity(self, capacity: usize) -> Self {
+        self.cache.set_capacity(Some(capacity));
+
+        self
+    }
+
+    /// Fetches values again once they have been cached for the duration.
+    pub fn ttl(self, ttl: Duration) -> Self {
+        self.cache.set_ttl(Some(ttl));
+
+        self
+    }
+
+    pub fn cache(&self) -> &Cache<Key, Value> {
+        &self.cache
+    }
+
+    /// Caches the value, unless the key already has a value.
+    pub fn prime(&self, key: Key, value: Value) {
+        if self.cache.get(&key).is_none() {
+            self.cache.insert(key, value);
+        }
+    }
+
+    /// Removes the key's value, so the next load fetches it again.
+    pub fn clear(&self, key: &Key) {
+        self.cache.remove(key);
+    }
+
+    pub fn clear_all(&self) {
+        self.cache.clear();
+    }
+
     #[tracing::instrument(level = "debug", skip(self), err)]
     pub async fn load(&self, key: &Key) -> anyhow::Result<Value> {
         let mut values = self.load_many(slice::from_ref(key)).await?;
@@ -83,33 +321,129 @@ where
             .ok_or_else(|| anyhow::anyhow!("Loader did not return any value, either the cache broken, or it does not exist in the data base"))
     }
 
+    /// Loads the values of the keys, in the order of the keys. Keys without a
+    /// value are left out.
     #[tracing::instrument(level = "debug", skip(self), err)]
     pub async fn load_many(&self, keys: &[Key]) -> anyhow::Result<Vec<Value>> {
-        for key in keys {
-            let exists = self.cache.exists(key).await;
+        let mut values: Vec<Option<Value>> = keys.iter().map(|key| self.cache.get(key)).collect();
 
-            if !exists {
-                self.fetcher
-                    .fetch(slice::from_ref(key), self.cache.clone())
-                    .await?;
+        let mut receivers = Vec::new();
+
+        {
+            let mut batch = self.batch.lock().expect("Loader batch lock poisoned");
+
+            for (index, key) in keys.iter().enumerate() {
+                if values[index].is_some() {
+                    continue;
+                }
+
+                let (tx, rx) = oneshot::channel();
+
+                // Keys already queued or being fetched are only waited on
+                match batch.waiting.get_mut(key) {
+                    Some(waiters) => waiters.push(tx),
+                    None => {
+                        batch.waiting.insert(key.clone(), vec![tx]);
+                        batch.keys.push(key.clone());
+                    }
+                }
+
+                receivers.push((index, rx));
+
+                if batch.keys.len() >= self.max_batch_size {
+                    let keys = mem::take(&mut batch.keys);
+
+                    tokio::spawn(self.clone().fetch(keys));
+                }
+            }
+
+            if !batch.keys.is_empty() && !batch.scheduled {
+                batch.scheduled = true;
+
+                tokio::spawn(self.clone().dispatch());
             }
         }
 
-        let mut values = Vec::with_capacity(keys.len());
+        for (index, rx) in receivers {
+            let value = rx
+                .await
+                .map_err(|_| anyhow::anyhow!("Loader batch was dropped before it was fetched"))?
+                .map_err(|err| anyhow::anyhow!("Unable to fetch loader batch: {}", err))?;
 
-        let map = self.cache.inner.read().await;
+            values[index] = value;
+        }
 
-        for key in keys {
-            if let Some(value) = map.get(key) {
-                values.push(value.clone());
-            }
+        Ok(values.into_iter().flatten().collect())
+    }
+
+    /// Waits out the batch window, then fetches the keys that were queued.
+    async fn dispatch(self) {
+        if self.window > Duration::from_millis(0) {
+            tokio::time::delay_for(self.window).await;
+        } else {
+            tokio::task::yield_now().await;
         }
 
-        debug_assert!(
-            keys.len() == values.len(),
-            "Could not get every value, the cache is not working properly"
-        );
+        let keys = {
+            let mut batch = self.batch.lock().expect("Loader batch lock poisoned");
+
+            batch.scheduled = false;
+
+            mem::take(&mut batch.keys)
+        };
+
+        if !keys.is_empty() {
+            self.fetch(keys).await;
+        }
+    }
+
+    async fn fetch(self, keys: Vec<Key>) {
+        let span = tracing::debug_span!("fetch", keys = keys.len());
+
+        let result = self.fetcher.fetch(&keys).instrument(span).await;
+
+        let mut batch = self.batch.lock().expect("Loader batch lock poisoned");
 
-        Ok(values)
+        match result {
+            Ok(mut values) => {
+                for key in keys {
+                    let value = values.remove(&key);
+
+                    if let Some(value) = &value {
+                        self.cache.insert(key.clone(), value.clone());
+                    }
+
+                    for waiter in batch.waiting.remove(&key).unwrap_or_default() {
+                        let _ = waiter.send(Ok(value.clone()));
+                    }
+                }
+            }
+            Err(err) => {
+                tracing::error!("Unable to fetch loader batch: {}", err);
+
+                let err = Arc::new(err);
+
+                for key in keys {
+                    for waiter in batch.waiting.remove(&key).unwrap_or_default() {
+                        let _ = waiter.send(Err(err.clone()));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<F, Key, Value> Clone for Loader<F, Key, Value>
+where
+    F: Fetcher<Key, Value>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            fetcher: self.fetcher.clone(),
+            cache: self.cache.clone(),
+            batch: self.batch.clone(),
+            max_batch_size: self.max_batch_size,
+            window: self.window,
+        }
     }
 }
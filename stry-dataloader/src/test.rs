@@ -0,0 +1,112 @@
+use {
+    crate::{Fetcher, Loader},
+    std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+        time::Duration,
+    },
+};
+
+/// Doubles every key below 100, recording the keys of every fetch.
+#[derive(Clone, Default)]
+struct Doubler {
+    batches: Arc<Mutex<Vec<Vec<u32>>>>,
+}
+
+#[async_trait::async_trait]
+impl Fetcher<u32, u32> for Doubler {
+    async fn fetch(&self, keys: &[u32]) -> anyhow::Result<HashMap<u32, u32>> {
+        self.batches.lock().unwrap().push(keys.to_vec());
+
+        Ok(keys
+            .iter()
+            .filter(|key| **key < 100)
+            .map(|key| (*key, key * 2))
+            .collect())
+    }
+}
+
+#[tokio::test]
+async fn coalesces_concurrent_loads() {
+    let fetcher = Doubler::default();
+    let loader = Loader::new(fetcher.clone());
+
+    let (one, two, many) = tokio::join!(
+        loader.load(&1),
+        loader.load(&2),
+        loader.load_many(&[3, 100, 1])
+    );
+
+    assert_eq!(2, one.unwrap());
+    assert_eq!(4, two.unwrap());
+    assert_eq!(vec![6, 2], many.unwrap());
+
+    assert_eq!(vec![vec![1, 2, 3, 100]], *fetcher.batches.lock().unwrap());
+}
+
+#[tokio::test]
+async fn splits_full_batches() {
+    let fetcher = Doubler::default();
+    let loader = Loader::new(fetcher.clone()).max_batch_size(2);
+
+    let values = loader.load_many(&[1, 2, 3, 4, 5]).await.unwrap();
+
+    assert_eq!(vec![2, 4, 6, 8, 10], values);
+
+    let mut batches = fetcher.batches.lock().unwrap().clone();
+
+    batches.sort();
+
+    assert_eq!(vec![vec![1, 2], vec![3, 4], vec![5]], batches);
+}
+
+#[tokio::test]
+async fn caches_until_cleared() {
+    let fetcher = Doubler::default();
+    let loader = Loader::new(fetcher.clone());
+
+    loader.prime(2, 40);
+
+    assert_eq!(2, loader.load(&1).await.unwrap());
+    assert_eq!(2, loader.load(&1).await.unwrap());
+    assert_eq!(40, loader.load(&2).await.unwrap());
+
+    loader.clear(&2);
+
+    assert_eq!(4, loader.load(&2).await.unwrap());
+    assert!(loader.load(&100).await.is_err());
+
+    assert_eq!(
+        vec![vec![1], vec![2], vec![100]],
+        *fetcher.batches.lock().unwrap()
+    );
+}
+
+#[tokio::test]
+async fn evicts_least_recently_used() {
+    let fetcher = Doubler::default();
+    let loader = Loader::new(fetcher.clone()).capacity(2);
+
+    loader.load_many(&[1, 2]).await.unwrap();
+    loader.load(&1).await.unwrap();
+    loader.load(&3).await.unwrap();
+
+    assert_eq!(Some(2), loader.cache().get(&1));
+    assert_eq!(None, loader.cache().get(&2));
+    assert_eq!(Some(6), loader.cache().get(&3));
+}
+
+#[tokio::test]
+async fn expires_after_ttl() {
+    let fetcher = Doubler::default();
+    let loader = Loader::new(fetcher.clone()).ttl(Duration::from_millis(20));
+
+    loader.load(&1).await.unwrap();
+    loader.load(&1).await.unwrap();
+
+    tokio::time::delay_for(Duration::from_millis(40)).await;
+
+    loader.load(&1).await.unwrap();
+
+    assert_eq!(2, fetcher.batches.lock().unwrap().len());
+}
@@ -5,8 +5,8 @@
 //! # Examples
 //!
 //! The example below shows the normal usage of Evermore (with dummy
-//! tasks and data), with tokio's [`broadcast channel`] being used as a
-//! shutdown signal sent using [`ctrlc`].
+//! tasks and data), with tokio's [`ctrl_c`] being used as the shutdown
+//! signal.
 //!
 //! ```rust,no_run
 //! use stry_evermore::{Evermore, Worker};
@@ -16,21 +16,11 @@
 //!
 //! #[tokio::main]
 //! async fn main() {
-//!     tracing_subscriber::fmt()
-//!         .with_max_level(tracing::Level::TRACE)
-//!         .with_target(true)
-//!         .init();
-//!
-//!     let (tx, mut rx) = tokio::sync::broadcast::channel(1);
-//!
-//!     ctrlc::set_handler(move || {
-//!         if tx.send(()).is_err() {
-//!             tracing::error!("Unable to send shutdown signal");
+//!     let signal = async {
+//!         if let Err(err) = tokio::signal::ctrl_c().await {
+//!             tracing::error!("Unable to listen for shutdown signal: {}", err);
 //!         }
-//!     })
-//!     .expect("Unable to set CTRL-C handler");
-//!
-//!     let signal = async move { rx.recv().await.expect("Failed to listen for event") };
+//!     };
 //!
 //!     Evermore::new(signal, 4, Data::default(), |data: Worker<Data>| {
 //!         Box::pin(task(data))
@@ -38,15 +28,16 @@
 //!     .await;
 //! }
 //!
-//! #[tracing::instrument(skip(data))]
+//! #[tracing::instrument(skip(worker))]
 //! async fn task(worker: Worker<Data>) -> anyhow::Result<()> {
 //!     loop {
-//!         tokio::time::delay_for(tokio::time::Duration::from_millis(1)).await;
+//!         tokio::select! {
+//!             _ = tokio::time::delay_for(tokio::time::Duration::from_secs(30)) => {}
+//!             _ = worker.cancelled() => {
+//!                 tracing::info!("Received shutdown signal, shutting down");
 //!
-//!         if worker.should_stop() {
-//!             tracing::info!("Received shutdown signal, shutting down");
-//!
-//!             break;
+//!                 break;
+//!             }
 //!         }
 //!     }
 //!
@@ -54,24 +45,56 @@
 //! }
 //! ```
 //!
-//! [`broadcast channel`]: https://docs.rs/tokio/0.2.22/tokio/sync/broadcast/fn.channel.html
-//! [`ctrlc`]: https://crates.io/crates/ctrlc
+//! Once the shutdown signal is sent the workers are given until the
+//! [`Evermore::drain_timeout`] to stop, after which their tasks are dropped.
+//!
+//! How a worker's task is restarted after it stops is set with
+//! [`Evermore::restart`], failed tasks are restarted after a delay that
+//! doubles for every failure in a row.
+//!
+//! Workers can be added or removed while Evermore runs through its
+//! [`Handle`], which also gives a snapshot of what each worker is doing.
+//!
+//! [`ctrl_c`]: https://docs.rs/tokio/0.2.22/tokio/signal/fn.ctrl_c.html
+
+#[cfg(test)]
+mod test;
 
 use {
-    futures::TryFuture,
+    futures::{task::AtomicWaker, TryFuture},
     std::{
+        collections::BTreeMap,
+        fmt,
         future::Future,
         marker::Unpin,
+        mem,
         pin::Pin,
         sync::{
-            atomic::{AtomicBool, Ordering},
-            Arc,
+            atomic::{AtomicBool, AtomicU8, Ordering},
+            Arc, Mutex,
         },
-        task::{Context, Poll},
+        task::{Context, Poll, Waker},
+        time::Duration,
     },
 };
 
+pub use stry_config::{Restart, RestartPolicy};
+
+/// A worker that has given up on its task, see [`Evermore::on_failure`].
+#[derive(Debug)]
+pub struct Failure<'e> {
+    pub id: u8,
+    /// The failures in a row, including this one.
+    pub failures: usize,
+    pub error: &'e anyhow::Error,
+}
+
+type FailureHook = Arc<dyn Fn(Failure<'_>) + Send + Sync>;
+
 /// An graceful shutdown enabled repeating asynchronous task runner.
+///
+/// The workers are started on the first poll, and are added or removed
+/// whenever the count of its [`Handle`] changes.
 #[pin_project::pin_project]
 pub struct Evermore<S, D, F>
 where
@@ -80,8 +103,15 @@ where
     F: Unpin + factory::Factory<D>,
 {
     data: Worker<D>,
+    factory: F,
+    handle: Handle,
+    restart: Restart,
+    on_failure: Option<FailureHook>,
+    drain_timeout: Option<Duration>,
+    /// Started when the shutdown signal is received, if there is a drain timeout.
+    deadline: Option<Pin<Box<tokio::time::Delay>>>,
     span: tracing::Span,
-    workers: Vec<(bool, PinnedWorkerFactory<D, F>)>,
+    workers: Vec<Slot<D, F>>,
     #[pin]
     signal: S,
 }
@@ -96,41 +126,65 @@ where
     pub fn new(signal: S, worker_count: u8, data: D, factory: F) -> Self {
         debug_assert!(worker_count >= 1, "Worker count but not be 0");
 
+        Self::with_handle(signal, Handle::new(worker_count), data, factory)
+    }
+
+    /// Creates a runner that is scaled and watched through an existing
+    /// handle, starting with the handle's worker count.
+    pub fn with_handle(signal: S, handle: Handle, data: D, factory: F) -> Self {
         let worker_data = Worker {
             data,
-            stop: Arc::new(AtomicBool::new(false)),
+            cancellation: Cancellation::new(),
         };
 
-        let mut workers = Vec::with_capacity(worker_count as usize);
-
-        for i in 0..(worker_count - 1) {
-            workers.push((
-                true,
-                Box::pin(WorkerFactory::new(
-                    i + 1,
-                    worker_data.clone(),
-                    factory.clone(),
-                )),
-            ));
-        }
-
-        // Push the skipped worker, consuming the factory parameter
-        workers.push((
-            true,
-            Box::pin(WorkerFactory::new(
-                worker_count,
-                worker_data.clone(),
-                factory,
-            )),
-        ));
-
         Self {
             data: worker_data,
+            factory,
+            handle,
+            restart: Restart::default(),
+            on_failure: None,
+            drain_timeout: None,
+            deadline: None,
             span: tracing::info_span!("evermore"),
-            workers,
+            workers: Vec::new(),
             signal,
         }
     }
+
+    /// Returns a handle that can change the number of workers, and see what
+    /// each of them is doing.
+    pub fn handle(&self) -> Handle {
+        self.handle.clone()
+    }
+
+    /// Sets when and how quickly the workers restart their tasks, by default
+    /// tasks are always restarted.
+    pub fn restart(mut self, restart: Restart) -> Self {
+        self.restart = restart;
+
+        self
+    }
+
+    /// Sets a hook that is called when a worker gives up on its task, either
+    /// from reaching the restart's `max_failures` or from failing when its
+    /// policy does not restart failed tasks.
+    pub fn on_failure<H>(mut self, hook: H) -> Self
+    where
+        H: Fn(Failure<'_>) + Send + Sync + 'static,
+    {
+        self.on_failure = Some(Arc::new(hook));
+
+        self
+    }
+
+    /// Sets how long the workers have to stop after the shutdown signal,
+    /// once it passes any tasks still running are dropped. By default
+    /// Evermore waits for every task to stop.
+    pub fn drain_timeout(mut self, timeout: Duration) -> Self {
+        self.drain_timeout = Some(timeout);
+
+        self
+    }
 }
 
 impl<S, D, F> Future for Evermore<S, D, F>
@@ -148,71 +202,300 @@ where
         let _entered = this.span.enter();
 
         let data: &mut Worker<D> = this.data;
-        let workers: &mut Vec<(bool, PinnedWorkerFactory<D, F>)> = this.workers;
+        let handle: &mut Handle = this.handle;
+        let workers: &mut Vec<Slot<D, F>> = this.workers;
 
-        if !data.stop.load(Ordering::SeqCst) {
+        // Woken when the handle's worker count changes
+        handle.shared.waker.register(cx.waker());
+
+        if !data.should_stop() {
             tracing::trace!("Polling shutdown signal");
 
             if let Poll::Ready(()) = this.signal.poll(cx) {
-                tracing::debug!("Received shutdown signal, setting `stop` to `true`");
+                tracing::debug!("Received shutdown signal, cancelling workers");
+
+                data.cancellation.cancel();
 
-                data.stop.store(true, Ordering::SeqCst);
+                if let Some(timeout) = this.drain_timeout {
+                    *this.deadline = Some(Box::pin(tokio::time::delay_for(*timeout)));
+                }
             }
         }
 
-        if data.stop.load(Ordering::SeqCst) {
-            // Only runs once the shutdown signal has been sent
-            for entry in workers.iter_mut() {
-                let (running, worker): &mut (bool, PinnedWorkerFactory<D, F>) = entry;
+        let stopping = data.should_stop();
+
+        if !stopping {
+            scale(
+                workers,
+                data,
+                this.factory,
+                handle,
+                this.restart,
+                this.on_failure,
+            );
+        }
+
+        for slot in workers.iter_mut() {
+            let id = slot.worker.id;
 
-                tracing::trace!(id = worker.id, "Polling worker");
+            tracing::trace!(id = id, "Polling worker");
 
-                let worker: Pin<&mut WorkerFactory<D, F>> = worker.as_mut();
+            // Only poll the worker if its still running
+            // This is incase of the event of a worker returning early
+            if slot.running {
+                let worker: Pin<&mut WorkerFactory<D, F>> = slot.worker.as_mut();
 
-                let poll: Poll<<<F as factory::Factory<D>>::Future as TryFuture>::Ok> =
-                    worker.poll(cx);
+                if let Poll::Ready(()) = worker.poll(cx) {
+                    if !stopping && !slot.retired {
+                        // The worker's restart policy decided not to restart its task
+                        tracing::warn!(
+                            id = id,
+                            "Worker has stopped, without the shutdown signal, and will not restart"
+                        );
+                    }
 
-                if let Poll::Ready(_res) = poll {
-                    *running = false;
+                    slot.running = false;
                 }
             }
+        }
 
-            if workers.iter().any(|(running, _)| *running) {
-                Poll::Pending
+        // Removed workers are forgotten once their task has stopped
+        workers.retain(|slot| {
+            if slot.retired && !slot.running {
+                handle.forget(slot.worker.id);
+
+                false
             } else {
-                Poll::Ready(())
+                true
             }
-        } else {
-            // Poll over every worker until the shutdown signal is sent
-            for entry in workers.iter_mut() {
-                let (running, worker): &mut (bool, PinnedWorkerFactory<D, F>) = entry;
-
-                let id = worker.id;
+        });
 
-                tracing::trace!(id = id, "Polling worker");
+        if !stopping {
+            return Poll::Pending;
+        }
 
-                // Only poll the worker if its still running
-                // This is incase of the event of a worker returning early
-                if *running {
-                    let worker: Pin<&mut WorkerFactory<D, F>> = worker.as_mut();
+        let running = workers.iter().filter(|slot| slot.running).count();
 
-                    let poll: Poll<<<F as factory::Factory<D>>::Future as TryFuture>::Ok> =
-                        worker.poll(cx);
+        if running == 0 {
+            return Poll::Ready(());
+        }
 
-                    match poll {
-                        Poll::Pending => {}
-                        Poll::Ready(_res) => {
-                            // TODO: handle value of returned future
-                            // Maybe return the error and add it to a error chain
-                            tracing::error!(id = id, "Worker has stopped, without the shutdown signal, and has not restarted");
+        if let Some(deadline) = this.deadline {
+            if let Poll::Ready(()) = deadline.as_mut().poll(cx) {
+                tracing::warn!(
+                    running = running,
+                    "Workers did not stop before the drain timeout, dropping their tasks"
+                );
 
-                            *running = false;
-                        }
-                    }
+                for slot in workers.drain(..) {
+                    handle.forget(slot.worker.id);
                 }
+
+                return Poll::Ready(());
             }
+        }
 
-            Poll::Pending
+        Poll::Pending
+    }
+}
+
+/// Starts or removes workers until there are as many as the handle's count,
+/// the newest workers are removed first.
+fn scale<D, F>(
+    workers: &mut Vec<Slot<D, F>>,
+    data: &Worker<D>,
+    factory: &F,
+    handle: &Handle,
+    restart: &Restart,
+    on_failure: &Option<FailureHook>,
+) where
+    D: Clone,
+    F: Unpin + factory::Factory<D>,
+{
+    let target = handle.count() as usize;
+    let active = workers.iter().filter(|slot| !slot.retired).count();
+
+    if active < target {
+        for _ in active..target {
+            let id =
+                match (1..=u8::MAX).find(|id| !workers.iter().any(|slot| slot.worker.id == *id)) {
+                    Some(id) => id,
+                    None => break,
+                };
+
+            tracing::debug!(id = id, "Starting worker");
+
+            handle.update(id, |_| {});
+
+            workers.push(Slot {
+                running: true,
+                retired: false,
+                worker: Box::pin(WorkerFactory::new(
+                    id,
+                    data.for_worker(),
+                    factory.clone(),
+                    restart.clone(),
+                    on_failure.clone(),
+                    handle.clone(),
+                )),
+            });
+        }
+    } else if active > target {
+        for slot in workers
+            .iter_mut()
+            .rev()
+            .filter(|slot| !slot.retired)
+            .take(active - target)
+        {
+            tracing::debug!(id = slot.worker.id, "Stopping worker");
+
+            slot.retired = true;
+            slot.worker.data.cancellation.cancel();
+
+            handle.update(slot.worker.id, |status| {
+                status.state = WorkerState::Stopping
+            });
+        }
+    }
+}
+
+/// A worker started by [`Evermore`], removed workers are kept until their
+/// task stops.
+struct Slot<D, F>
+where
+    D: Clone,
+    F: Unpin + factory::Factory<D>,
+{
+    running: bool,
+    retired: bool,
+    worker: PinnedWorkerFactory<D, F>,
+}
+
+/// A handle to an [`Evermore`], used to add or remove workers while it runs
+/// and to get the status of each worker.
+#[derive(Clone, Debug)]
+pub struct Handle {
+    shared: Arc<Shared>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    count: AtomicU8,
+    waker: AtomicWaker,
+    statuses: Mutex<BTreeMap<u8, WorkerStatus>>,
+}
+
+impl Handle {
+    pub fn new(count: u8) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                count: AtomicU8::new(count),
+                waker: AtomicWaker::new(),
+                statuses: Mutex::new(BTreeMap::new()),
+            }),
+        }
+    }
+
+    /// The number of workers that should be running.
+    pub fn count(&self) -> u8 {
+        self.shared.count.load(Ordering::SeqCst)
+    }
+
+    /// Sets the number of workers, the newest workers are removed first.
+    ///
+    /// A removed worker's cancellation is cancelled, like on shutdown, so its
+    /// task should stop at the next point it checks the cancellation. The
+    /// worker is kept until its task has stopped.
+    pub fn scale(&self, count: u8) {
+        self.shared.count.store(count, Ordering::SeqCst);
+        self.shared.waker.wake();
+    }
+
+    pub fn add(&self, count: u8) {
+        self.change(|current| current.saturating_add(count));
+    }
+
+    pub fn remove(&self, count: u8) {
+        self.change(|current| current.saturating_sub(count));
+    }
+
+    fn change(&self, f: impl Fn(u8) -> u8) {
+        let _ = self
+            .shared
+            .count
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |current| {
+                Some(f(current))
+            });
+
+        self.shared.waker.wake();
+    }
+
+    /// Returns the status of every worker, ordered by their ID.
+    pub fn workers(&self) -> Vec<WorkerStatus> {
+        self.shared
+            .statuses
+            .lock()
+            .expect("Worker status lock poisoned")
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    fn update(&self, id: u8, f: impl FnOnce(&mut WorkerStatus)) {
+        let mut statuses = self
+            .shared
+            .statuses
+            .lock()
+            .expect("Worker status lock poisoned");
+
+        f(statuses.entry(id).or_insert_with(|| WorkerStatus {
+            id,
+            state: WorkerState::Idle,
+            generation: 1,
+            last_error: None,
+        }));
+    }
+
+    fn forget(&self, id: u8) {
+        self.shared
+            .statuses
+            .lock()
+            .expect("Worker status lock poisoned")
+            .remove(&id);
+    }
+}
+
+/// What a worker was doing when [`Handle::workers`] was called.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct WorkerStatus {
+    pub id: u8,
+    pub state: WorkerState,
+    /// The number of the task being run, counting the failures since the
+    /// last successful task.
+    pub generation: usize,
+    /// The error of the last task that failed.
+    pub last_error: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum WorkerState {
+    /// The worker has no task, either it has not started or its task
+    /// stopped and was not restarted.
+    Idle,
+    Running,
+    /// Waiting before the task is restarted.
+    BackingOff,
+    /// The worker was removed and is waiting for its task to stop.
+    Stopping,
+}
+
+impl fmt::Display for WorkerState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorkerState::Idle => write!(f, "idle"),
+            WorkerState::Running => write!(f, "running"),
+            WorkerState::BackingOff => write!(f, "backing off"),
+            WorkerState::Stopping => write!(f, "stopping"),
         }
     }
 }
@@ -221,13 +504,15 @@ where
 ///
 /// This does not allow you to send a shutdown signal or interact
 /// with the worker in anyway, it is only used to store user data
-/// and the shared stop signal.
+/// and the worker's cancellation.
 #[derive(Debug)]
 pub struct Worker<D>
 where
     D: Clone,
 {
-    stop: Arc<AtomicBool>,
+    /// Cancelled on shutdown, or when only this worker is removed, see
+    /// [`Handle::scale`].
+    cancellation: Cancellation,
 
     /// The users shared data.
     pub data: D,
@@ -239,7 +524,27 @@ where
 {
     /// Returns `true` if the running task should cleanup and shutdown.
     pub fn should_stop(&self) -> bool {
-        self.stop.load(Ordering::Acquire)
+        self.cancellation.is_cancelled()
+    }
+
+    /// Completes once the running task should cleanup and shutdown, for use
+    /// with `select!` around long running futures.
+    pub fn cancelled(&self) -> Cancelled<'_> {
+        self.cancellation.cancelled()
+    }
+
+    /// Returns the worker's cancellation, for futures that outlive the borrow
+    /// of the worker.
+    pub fn cancellation(&self) -> Cancellation {
+        self.cancellation.clone()
+    }
+
+    /// Copies the data for a new worker, which can be stopped on its own.
+    fn for_worker(&self) -> Self {
+        Self {
+            cancellation: self.cancellation.child(),
+            data: self.data.clone(),
+        }
     }
 }
 
@@ -249,12 +554,182 @@ where
 {
     fn clone(&self) -> Self {
         Self {
-            stop: self.stop.clone(),
+            cancellation: self.cancellation.clone(),
             data: self.data.clone(),
         }
     }
 }
 
+/// A shareable token that tasks can check or wait on to know when to stop.
+///
+/// A child token is cancelled along with its parent, but cancelling the child
+/// leaves the parent alone.
+#[derive(Clone, Debug, Default)]
+pub struct Cancellation {
+    inner: Arc<CancellationInner>,
+}
+
+#[derive(Debug, Default)]
+struct CancellationInner {
+    cancelled: AtomicBool,
+    wakers: Mutex<Wakers>,
+    parent: Option<Cancellation>,
+}
+
+/// The wakers of the futures waiting on a token, keyed so that a future can
+/// replace or remove its own waker.
+#[derive(Debug, Default)]
+struct Wakers {
+    next: usize,
+    registered: BTreeMap<usize, Waker>,
+}
+
+impl Cancellation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a token that is cancelled when this one is.
+    pub fn child(&self) -> Self {
+        Self {
+            inner: Arc::new(CancellationInner {
+                cancelled: AtomicBool::new(false),
+                wakers: Mutex::new(Wakers::default()),
+                parent: Some(self.clone()),
+            }),
+        }
+    }
+
+    /// Cancels the token and its children, waking any task waiting on them.
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+
+        let wakers = mem::take(
+            &mut self
+                .inner
+                .wakers
+                .lock()
+                .expect("Cancellation waker lock poisoned")
+                .registered,
+        );
+
+        for waker in wakers.values() {
+            waker.wake_by_ref();
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+            || self
+                .inner
+                .parent
+                .as_ref()
+                .map_or(false, Cancellation::is_cancelled)
+    }
+
+    /// Returns a future that completes once the token is cancelled.
+    pub fn cancelled(&self) -> Cancelled<'_> {
+        Cancelled {
+            token: self,
+            keys: Vec::new(),
+        }
+    }
+
+    /// Registers the waker with this token and its parents, `keys` holds the
+    /// key given by each token so the waker is replaced on later polls
+    /// instead of added again.
+    fn register(&self, keys: &mut Vec<usize>, depth: usize, waker: &Waker) {
+        {
+            let mut wakers = self
+                .inner
+                .wakers
+                .lock()
+                .expect("Cancellation waker lock poisoned");
+
+            match keys.get(depth) {
+                Some(key) => match wakers.registered.get_mut(key) {
+                    Some(registered) if registered.will_wake(waker) => {}
+                    Some(registered) => *registered = waker.clone(),
+                    None => {
+                        wakers.registered.insert(*key, waker.clone());
+                    }
+                },
+                None => {
+                    let key = wakers.next;
+
+                    wakers.next = wakers.next.wrapping_add(1);
+                    wakers.registered.insert(key, waker.clone());
+
+                    keys.push(key);
+                }
+            }
+        }
+
+        if let Some(parent) = &self.inner.parent {
+            parent.register(keys, depth + 1, waker);
+        }
+    }
+
+    /// Removes the wakers registered with the keys from [`register`].
+    ///
+    /// [`register`]: Cancellation::register
+    fn unregister(&self, keys: &[usize]) {
+        let mut token = Some(self);
+
+        for key in keys {
+            let current = match token {
+                Some(current) => current,
+                None => break,
+            };
+
+            current
+                .inner
+                .wakers
+                .lock()
+                .expect("Cancellation waker lock poisoned")
+                .registered
+                .remove(key);
+
+            token = current.inner.parent.as_ref();
+        }
+    }
+}
+
+/// The future returned by [`Cancellation::cancelled`].
+#[derive(Debug)]
+pub struct Cancelled<'c> {
+    token: &'c Cancellation,
+    /// The key of this future's waker in the token and each of its parents.
+    keys: Vec<usize>,
+}
+
+impl Future for Cancelled<'_> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        if this.token.is_cancelled() {
+            return Poll::Ready(());
+        }
+
+        this.token.register(&mut this.keys, 0, cx.waker());
+
+        // Checked again incase the token was cancelled while registering
+        if this.token.is_cancelled() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl Drop for Cancelled<'_> {
+    fn drop(&mut self) {
+        self.token.unregister(&self.keys);
+    }
+}
+
 type PinnedWorkerFactory<D, F> = Pin<Box<WorkerFactory<D, F>>>;
 
 #[pin_project::pin_project]
@@ -264,9 +739,15 @@ where
     F: Unpin + factory::Factory<D>,
 {
     id: u8,
+    /// The number of the task being run, counting the failures since the
+    /// last successful task.
     generation: usize,
     data: Worker<D>,
 
+    restart: Restart,
+    on_failure: Option<FailureHook>,
+    handle: Handle,
+
     #[pin]
     state: FactoryState<F::Future>,
     #[pin]
@@ -279,15 +760,50 @@ where
     F: Unpin + factory::Factory<D>,
 {
     #[inline]
-    fn new(id: u8, data: Worker<D>, factory: F) -> Self {
+    fn new(
+        id: u8,
+        data: Worker<D>,
+        factory: F,
+        restart: Restart,
+        on_failure: Option<FailureHook>,
+        handle: Handle,
+    ) -> Self {
         Self {
             id,
             data,
             factory,
             generation: 1,
+            restart,
+            on_failure,
+            handle,
             state: FactoryState::Idle,
         }
     }
+
+    /// The delay before the next task is created, doubled for every failure
+    /// in a row after the first.
+    fn backoff(restart: &Restart, generation: usize) -> Duration {
+        let doublings = generation.saturating_sub(2).min(32) as u32;
+
+        let delay = restart
+            .backoff
+            .saturating_mul(2u64.saturating_pow(doublings))
+            .min(restart.max_backoff);
+
+        Duration::from_millis(delay)
+    }
+
+    fn give_up(id: u8, failures: usize, error: &anyhow::Error, hook: Option<&FailureHook>) {
+        tracing::error!(failures = failures, "Worker has given up on its task");
+
+        if let Some(hook) = hook {
+            hook(Failure {
+                id,
+                failures,
+                error,
+            });
+        }
+    }
 }
 
 impl<D, F> Future for WorkerFactory<D, F>
@@ -296,7 +812,7 @@ where
     F: Unpin + factory::Factory<D>,
     <<F as factory::Factory<D>>::Future as TryFuture>::Error: Into<anyhow::Error> + Send,
 {
-    type Output = <<F as factory::Factory<D>>::Future as TryFuture>::Ok;
+    type Output = ();
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let span = tracing::info_span!("worker", id = self.id);
@@ -305,8 +821,12 @@ where
         loop {
             let this = self.as_mut().project();
 
+            let id: u8 = *this.id;
             let generation: &mut usize = this.generation;
             let data: &mut Worker<D> = this.data;
+            let restart: &mut Restart = this.restart;
+            let on_failure: &mut Option<FailureHook> = this.on_failure;
+            let handle: &mut Handle = this.handle;
 
             let mut factory: Pin<&mut F> = this.factory;
 
@@ -314,30 +834,92 @@ where
                 FactoryStateProject::Idle => {
                     tracing::trace!("No future task, creating from factory");
 
+                    let current = *generation;
+
+                    handle.update(id, |status| {
+                        status.state = WorkerState::Running;
+                        status.generation = current;
+                    });
+
                     FactoryState::Waiting {
                         task: factory.new(data.clone()),
                     }
                 }
+                FactoryStateProject::Backoff { delay } => {
+                    let delay: Pin<&mut tokio::time::Delay> = delay;
+
+                    // Don't wait out the delay if the worker is shutting down
+                    if let Poll::Ready(()) = Pin::new(&mut data.cancelled()).poll(cx) {
+                        return Poll::Ready(());
+                    }
+
+                    futures::ready!(delay.poll(cx));
+
+                    FactoryState::Idle
+                }
                 FactoryStateProject::Waiting { task } => {
                     let task: Pin<&mut <F as factory::Factory<D>>::Future> = task;
 
-                    match futures::ready!(task.try_poll(cx)) {
-                        Ok(x) => {
+                    let error = match futures::ready!(task.try_poll(cx)) {
+                        Ok(_) => {
                             *generation = 1;
 
-                            return Poll::Ready(x);
+                            None
                         }
                         Err(e) => {
                             *generation += 1;
 
                             let err: anyhow::Error = e.into();
 
-                            tracing::error!(error = ?err, "Task failed with error");
+                            tracing::error!(error = ?err, generation = *generation, "Task failed with error");
+
+                            handle.update(id, |status| status.last_error = Some(err.to_string()));
+
+                            Some(err)
+                        }
+                    };
+
+                    if data.should_stop() {
+                        return Poll::Ready(());
+                    }
+
+                    let failures = *generation - 1;
+
+                    match (error, restart.policy) {
+                        (None, RestartPolicy::OnFailure) | (None, RestartPolicy::Never) => {
+                            tracing::info!("Task has finished and will not be restarted");
+
+                            handle.update(id, |status| status.state = WorkerState::Idle);
 
-                            FactoryState::Waiting {
-                                task: factory.new(data.clone()),
-                            }
+                            return Poll::Ready(());
                         }
+                        (Some(err), RestartPolicy::Never) => {
+                            Self::give_up(id, failures, &err, on_failure.as_ref());
+
+                            handle.update(id, |status| status.state = WorkerState::Idle);
+
+                            return Poll::Ready(());
+                        }
+                        (Some(err), _)
+                            if restart.max_failures.map_or(false, |max| failures >= max) =>
+                        {
+                            Self::give_up(id, failures, &err, on_failure.as_ref());
+
+                            handle.update(id, |status| status.state = WorkerState::Idle);
+
+                            return Poll::Ready(());
+                        }
+                        _ => {}
+                    }
+
+                    let delay = Self::backoff(restart, *generation);
+
+                    tracing::debug!(delay = ?delay, "Restarting task after backoff");
+
+                    handle.update(id, |status| status.state = WorkerState::BackingOff);
+
+                    FactoryState::Backoff {
+                        delay: tokio::time::delay_for(delay),
                     }
                 }
             };
@@ -350,6 +932,10 @@ where
 #[pin_project::pin_project(project = FactoryStateProject)]
 enum FactoryState<F> {
     Idle,
+    Backoff {
+        #[pin]
+        delay: tokio::time::Delay,
+    },
     Waiting {
         #[pin]
         task: F,
@@ -0,0 +1,196 @@
+use {
+    crate::{Cancellation, Evermore, Restart, RestartPolicy, Worker},
+    std::{
+        future::Future,
+        pin::Pin,
+        sync::{Arc, Mutex},
+        time::Duration,
+    },
+    tokio::time::{self, Instant},
+};
+
+type Task = Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send>>;
+
+/// Records when each task was started, the tasks fail until `fail` have
+/// been started.
+#[derive(Clone)]
+struct Tasks {
+    fail: usize,
+    starts: Arc<Mutex<Vec<Instant>>>,
+}
+
+impl Tasks {
+    fn new(fail: usize) -> Self {
+        Self {
+            fail,
+            starts: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    fn start(worker: Worker<Tasks>) -> Task {
+        Box::pin(async move {
+            let started = {
+                let mut starts = worker.data.starts.lock().unwrap();
+
+                starts.push(Instant::now());
+
+                starts.len()
+            };
+
+            if started <= worker.data.fail {
+                anyhow::bail!("Task {} failed", started);
+            }
+
+            Ok(())
+        })
+    }
+
+    /// The whole seconds between each task starting, the timer can fire a
+    /// little late but never early.
+    fn gaps(&self) -> Vec<u64> {
+        self.starts
+            .lock()
+            .unwrap()
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).as_secs())
+            .collect()
+    }
+}
+
+fn restart(policy: RestartPolicy, max_failures: Option<usize>) -> Restart {
+    Restart {
+        policy,
+        backoff: 1000,
+        max_backoff: 60 * 1000,
+        max_failures,
+    }
+}
+
+#[tokio::test]
+async fn restarts_after_failure() {
+    time::pause();
+
+    let tasks = Tasks::new(2);
+
+    Evermore::new(
+        time::delay_for(Duration::from_secs(60)),
+        1,
+        tasks.clone(),
+        Tasks::start,
+    )
+    .restart(restart(RestartPolicy::OnFailure, None))
+    .await;
+
+    // Two failures and the success, which is not restarted
+    assert_eq!(3, tasks.starts.lock().unwrap().len());
+}
+
+#[tokio::test]
+async fn stops_at_max_failures() {
+    time::pause();
+
+    let tasks = Tasks::new(usize::MAX);
+    let failures = Arc::new(Mutex::new(Vec::new()));
+
+    Evermore::new(
+        time::delay_for(Duration::from_secs(60 * 60)),
+        1,
+        tasks.clone(),
+        Tasks::start,
+    )
+    .restart(restart(RestartPolicy::Always, Some(3)))
+    .on_failure({
+        let failures = failures.clone();
+
+        move |failure| failures.lock().unwrap().push(failure.failures)
+    })
+    .await;
+
+    assert_eq!(3, tasks.starts.lock().unwrap().len());
+    assert_eq!(vec![3], *failures.lock().unwrap());
+}
+
+#[tokio::test]
+async fn backoff_doubles() {
+    time::pause();
+
+    let tasks = Tasks::new(usize::MAX);
+
+    Evermore::new(
+        time::delay_for(Duration::from_secs(60 * 60)),
+        1,
+        tasks.clone(),
+        Tasks::start,
+    )
+    .restart(Restart {
+        max_backoff: 6000,
+        ..restart(RestartPolicy::Always, Some(5))
+    })
+    .await;
+
+    // Doubled after every failure, until it reaches the max backoff
+    assert_eq!(vec![1, 2, 4, 6], tasks.gaps());
+}
+
+#[tokio::test]
+async fn drain_timeout_drops_tasks() {
+    time::pause();
+
+    let start = Instant::now();
+
+    // The task ignores the shutdown signal, so only the drain timeout stops it
+    Evermore::new(
+        time::delay_for(Duration::from_secs(1)),
+        2,
+        (),
+        |_: Worker<()>| -> Task {
+            Box::pin(async {
+                time::delay_for(Duration::from_secs(60 * 60)).await;
+
+                Ok(())
+            })
+        },
+    )
+    .drain_timeout(Duration::from_secs(5))
+    .await;
+
+    let elapsed = Instant::now() - start;
+
+    assert!(
+        elapsed >= Duration::from_secs(6) && elapsed < Duration::from_secs(7),
+        "Stopped after {:?}",
+        elapsed
+    );
+}
+
+fn registered(token: &Cancellation) -> usize {
+    token.inner.wakers.lock().unwrap().registered.len()
+}
+
+#[tokio::test]
+async fn cancelled_removes_wakers_on_drop() {
+    let parent = Cancellation::new();
+    let child = parent.child();
+
+    // Like a task that waits on the token in a loop alongside other work
+    for _ in 0..10 {
+        let mut cancelled = child.cancelled();
+
+        assert!(futures::poll!(&mut cancelled).is_pending());
+        assert!(futures::poll!(&mut cancelled).is_pending());
+
+        assert_eq!(1, registered(&child));
+        assert_eq!(1, registered(&parent));
+    }
+
+    assert_eq!(0, registered(&child));
+    assert_eq!(0, registered(&parent));
+
+    let mut cancelled = child.cancelled();
+
+    assert!(futures::poll!(&mut cancelled).is_pending());
+
+    parent.cancel();
+
+    assert!(futures::poll!(&mut cancelled).is_ready());
+}
@@ -17,3 +17,51 @@ if (elmAddUrls) {
             }
         });
 }
+
+var elmTasks = document.getElementById("js__tbl--tasks");
+
+if (elmTasks && window.EventSource) {
+    var taskRow = function (event) {
+        var data = JSON.parse(event.data);
+
+        return {
+            data: data,
+            row: document.getElementById("js__task--" + data.task),
+        };
+    };
+
+    var taskEvents = new EventSource("/dashboard/tasks/events");
+
+    taskEvents.addEventListener("progress", function (event) {
+        var task = taskRow(event);
+
+        if (task.row) {
+            var elmProgress = task.row.querySelector(".js__task-progress");
+
+            elmProgress.max = task.data.chapters;
+            elmProgress.value = task.data.chapter;
+            elmProgress.textContent = task.data.chapter + " / " + task.data.chapters;
+        }
+    });
+
+    taskEvents.addEventListener("completed", function (event) {
+        var task = taskRow(event);
+
+        if (task.row) {
+            task.row.parentNode.removeChild(task.row);
+        }
+    });
+
+    taskEvents.addEventListener("failed", function (event) {
+        var task = taskRow(event);
+
+        if (task.row) {
+            if (task.data.retry) {
+                task.row.querySelector(".js__task-error").textContent = task.data.error;
+            } else {
+                // Failed tasks are listed under failed once the page is reloaded
+                task.row.parentNode.removeChild(task.row);
+            }
+        }
+    });
+}
@@ -21,6 +21,7 @@ story-info-chapters = { $chapters ->
         [one]       1 chapter
         *[other]    { $chapters } chapters
     }
+story-info-language = { $language }
 story-info-words = { $words ->
         [zero]      no words
         [one]       1 word
@@ -1,10 +1,13 @@
 use {
     crate::{
+        models::{QueueAction, QueueForm, WorkersForm},
         pages::dashboard,
         utils::{self, wrap},
     },
     chrono::Utc,
     stry_backend::DataBackend,
+    stry_evermore::Handle,
+    stry_worker_scraper::schedule,
     warp::{reply, Rejection, Reply},
 };
 
@@ -43,11 +46,142 @@ pub async fn downloads(
 }
 
 #[stry_macros::get("/queue")]
-pub async fn queue(
+pub async fn queue_get(
     #[data] _backend: DataBackend,
-    #[header("Accept-Language")] _languages: String,
+    #[header("Accept-Language")] languages: String,
+) -> Result<impl Reply, Rejection> {
+    wrap(move || async move {
+        let time = Utc::now();
+
+        let user_lang = utils::get_languages(&languages);
+
+        let rendered: String =
+            dashboard::Queue::new(time, String::new(), Vec::new(), None, None, user_lang)
+                .into_string()?;
+
+        Ok(rendered)
+    })
+    .await
+}
+
+/// Expands a listing into its stories, either showing them as a preview or
+/// adding a task for each one.
+#[stry_macros::post("/queue")]
+pub async fn queue_post(
+    #[data] backend: DataBackend,
+    #[header("Accept-Language")] languages: String,
+    #[form] body: QueueForm,
 ) -> Result<impl Reply, Rejection> {
-    Ok(reply::html("queue"))
+    wrap(move || async move {
+        let time = Utc::now();
+
+        let user_lang = utils::get_languages(&languages);
+
+        let url = body.url.trim().to_string();
+
+        let (stories, queued, error) = match stry_worker_scraper::expand(url.as_str()).await {
+            Ok(Some(stories)) => {
+                if body.action == QueueAction::Queue {
+                    let mut queued = 0;
+
+                    for story in &stories {
+                        let added = backend
+                            .add_task(
+                                story.name.clone().into(),
+                                story.site,
+                                story.url.clone().into(),
+                            )
+                            .await?;
+
+                        if added {
+                            queued += 1;
+                        }
+                    }
+
+                    (stories, Some(queued), None)
+                } else {
+                    (stories, None, None)
+                }
+            }
+            Ok(None) => (
+                Vec::new(),
+                None,
+                Some(String::from(
+                    "Not a listing, only Archive of Our Own works, bookmarks and collections, and FanFiction.net authors and communities can be imported",
+                )),
+            ),
+            Err(err) => (Vec::new(), None, Some(err.to_string())),
+        };
+
+        let rendered: String =
+            dashboard::Queue::new(time, url, stories, queued, error, user_lang).into_string()?;
+
+        Ok(rendered)
+    })
+    .await
+}
+
+#[stry_macros::get("/tasks")]
+pub async fn tasks_get(
+    #[data] backend: DataBackend,
+    #[data] workers: Handle,
+    #[header("Accept-Language")] languages: String,
+) -> Result<impl Reply, Rejection> {
+    wrap(move || async move {
+        let time = Utc::now();
+
+        let user_lang = utils::get_languages(&languages);
+
+        let tasks = backend.all_tasks().await?;
+        let settings = backend.prefixed_settings(schedule::PREFIX.into()).await?;
+
+        let rendered: String = dashboard::Tasks::new(
+            time,
+            workers.count(),
+            workers.workers(),
+            schedule::status(&settings),
+            &tasks,
+            user_lang,
+        )
+        .into_string()?;
+
+        Ok(rendered)
+    })
+    .await
+}
+
+/// Changes the number of workers, removed workers are told to stop and are
+/// kept until their task does.
+#[stry_macros::post("/tasks")]
+pub async fn tasks_post(
+    #[data] backend: DataBackend,
+    #[data] workers: Handle,
+    #[header("Accept-Language")] languages: String,
+    #[form] body: WorkersForm,
+) -> Result<impl Reply, Rejection> {
+    wrap(move || async move {
+        let time = Utc::now();
+
+        let user_lang = utils::get_languages(&languages);
+
+        workers.scale(body.count);
+
+        let tasks = backend.all_tasks().await?;
+        let settings = backend.prefixed_settings(schedule::PREFIX.into()).await?;
+
+        let rendered: String = dashboard::Tasks::new(
+            time,
+            workers.count(),
+            workers.workers(),
+            schedule::status(&settings),
+            &tasks,
+            user_lang,
+        )
+        .into_string()?;
+
+        Ok(rendered)
+    })
+    .await
 }
 
 #[stry_macros::get("/updates")]
@@ -10,6 +10,7 @@ pub mod readable;
 use {
     crate::controllers::{dashboard, edit, explore, item, search, story},
     stry_backend::DataBackend,
+    stry_evermore::Handle,
     warp::{
         filters::BoxedFilter,
         http::header::{HeaderMap, HeaderValue, CONTENT_SECURITY_POLICY, X_FRAME_OPTIONS},
@@ -20,7 +21,7 @@ use {
 
 // const BOM: &str = include_str!("../bom.txt");
 
-pub fn route(backend: DataBackend) -> BoxedFilter<(impl Reply,)> {
+pub fn route(backend: DataBackend, workers: Handle) -> BoxedFilter<(impl Reply,)> {
     utils::init_fluent().expect("Unable to initialize Fluent");
 
     let mut headers = HeaderMap::new();
@@ -37,7 +38,13 @@ pub fn route(backend: DataBackend) -> BoxedFilter<(impl Reply,)> {
             dashboard::about(backend.clone())
                 .or(dashboard::downloads(backend.clone()))
                 .boxed()
-                .or(dashboard::queue(backend.clone()))
+                .or(dashboard::queue_get(backend.clone()))
+                .boxed()
+                .or(dashboard::queue_post(backend.clone()))
+                .boxed()
+                .or(dashboard::tasks_get(backend.clone(), workers.clone()))
+                .boxed()
+                .or(dashboard::tasks_post(backend.clone(), workers))
                 .boxed()
                 .or(dashboard::updates(backend.clone()))
                 .boxed()
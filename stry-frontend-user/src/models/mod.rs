@@ -4,3 +4,23 @@ pub struct ChapterForm {
     pub main: String,
     pub post: String,
 }
+
+#[derive(Debug, serde::Deserialize)]
+pub struct QueueForm {
+    pub url: String,
+    pub action: QueueAction,
+}
+
+/// `Preview` only lists the stories that would be queued.
+#[derive(Clone, Copy, Debug, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueueAction {
+    Preview,
+    Queue,
+}
+
+/// Sets the number of workers from the tasks page.
+#[derive(Debug, serde::Deserialize)]
+pub struct WorkersForm {
+    pub count: u8,
+}
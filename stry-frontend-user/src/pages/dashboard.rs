@@ -3,8 +3,10 @@ use {
     askama::Template,
     chrono::{DateTime, Duration, Utc},
     stry_common::LibraryDetails,
+    stry_evermore::WorkerStatus,
     stry_generated_version::BOM,
-    stry_models::{Worker, WorkerTask},
+    stry_models::WorkerTask,
+    stry_worker_scraper::{models::ListedStory, schedule},
 };
 
 #[derive(Template)]
@@ -52,6 +54,48 @@ impl Database {
     }
 }
 
+#[derive(Template)]
+#[template(path = "dashboard/queue.html")]
+pub struct Queue {
+    meta: Meta,
+
+    title: &'static str,
+    duration: Duration,
+
+    url: String,
+    stories: Vec<ListedStory>,
+
+    /// Set once the stories have been queued, instead of only previewed.
+    queued: Option<usize>,
+    error: Option<String>,
+}
+
+impl Queue {
+    pub fn new(
+        time: DateTime<Utc>,
+        url: String,
+        stories: Vec<ListedStory>,
+        queued: Option<usize>,
+        error: Option<String>,
+        user_lang: Identifiers,
+    ) -> Self {
+        Self {
+            meta: Meta::new(user_lang),
+            title: "queue | dashboard",
+            duration: Utc::now().signed_duration_since(time),
+            url,
+            stories,
+            queued,
+            error,
+        }
+    }
+
+    #[tracing::instrument(level = "trace", name = "render", skip(self), err)]
+    pub fn into_string(self) -> anyhow::Result<String> {
+        Ok(self.render()?)
+    }
+}
+
 #[derive(Template)]
 #[template(path = "dashboard/settings.html")]
 pub struct Settings {
@@ -92,14 +136,20 @@ pub struct Tasks<'w> {
     title: &'static str,
     duration: Duration,
 
-    workers: &'w [Worker],
+    /// The number of workers that should be running, removed workers are
+    /// shown until they stop.
+    count: u8,
+    workers: Vec<WorkerStatus>,
+    schedules: Vec<schedule::Status>,
     tasks: &'w [WorkerTask],
 }
 
 impl<'w> Tasks<'w> {
     pub fn new(
         time: DateTime<Utc>,
-        workers: &'w [Worker],
+        count: u8,
+        workers: Vec<WorkerStatus>,
+        schedules: Vec<schedule::Status>,
         tasks: &'w [WorkerTask],
         user_lang: Identifiers,
     ) -> Self {
@@ -107,7 +157,9 @@ impl<'w> Tasks<'w> {
             meta: Meta::new(user_lang),
             title: "tasks | dashboard",
             duration: Utc::now().signed_duration_since(time),
+            count,
             workers,
+            schedules,
             tasks,
         }
     }
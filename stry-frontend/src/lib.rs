@@ -1,35 +1,55 @@
 use {
-    std::sync::Arc,
+    std::{convert::Infallible, sync::Arc},
     stry_backend::DataBackend,
     stry_config::{Config, Tls},
-    tokio::sync::broadcast::Receiver,
+    stry_evermore::{Cancellation, Handle},
+    stry_models::TaskEvent,
+    tokio::sync::broadcast::{Receiver, RecvError, Sender},
     warp::{Filter, Rejection, Reply},
 };
 
-pub async fn start(cfg: Arc<Config>, mut rx: Receiver<()>, backend: DataBackend) {
+pub async fn start(
+    cfg: Arc<Config>,
+    mut rx: Receiver<()>,
+    backend: DataBackend,
+    workers: Handle,
+    task_events: Sender<TaskEvent>,
+) {
     let (enable_api, enable_user) = cfg.frontend.as_bool();
 
+    // Event streams never finish on their own, so they are closed on shutdown
+    let closing = Cancellation::new();
+
     let routes = api_routes(enable_api, backend.clone())
-        .or(user_routes(enable_user, backend.clone()))
+        .or(user_routes(enable_user, backend.clone(), workers))
+        .or(image_routes(&cfg))
         .with(warp::trace::request());
 
+    // Compressing the event stream would hold back events until enough are buffered
+    let events =
+        task_event_routes(enable_user, task_events, closing.clone()).with(warp::trace::request());
+
+    let shutdown = async move {
+        rx.recv().await.expect("Failed to listen for event");
+
+        closing.cancel();
+    };
+
     if let Tls::None = cfg.tls {
         // I want to use brotli, but Firefpx isn't adding new features to HTTP (non HTTPS),
         // as such it only sends `Accept-Encoding: gzip, deflate`.
         // That means I'll either wrap the server in NGINX, or allow for TLS through the config.
         // See: https://bugzilla.mozilla.org/show_bug.cgi?id=1218924
-        let routes = routes.with(warp::compression::gzip());
+        let routes = events.or(routes.with(warp::compression::gzip()));
 
-        let (addr, server) = warp::serve(routes)
-            .bind_with_graceful_shutdown((cfg.ip, cfg.port), async move {
-                rx.recv().await.expect("Failed to listen for event")
-            });
+        let (addr, server) =
+            warp::serve(routes).bind_with_graceful_shutdown((cfg.ip, cfg.port), shutdown);
 
         tracing::info!("warp drive engaged: listening on http://{}", addr);
 
         server.await;
     } else {
-        let routes = routes.with(warp::compression::brotli());
+        let routes = events.or(routes.with(warp::compression::brotli()));
 
         let mut serve = warp::serve(routes).tls();
 
@@ -43,9 +63,7 @@ pub async fn start(cfg: Arc<Config>, mut rx: Receiver<()>, backend: DataBackend)
             Tls::None => unreachable!(),
         }
 
-        let (addr, server) = serve.bind_with_graceful_shutdown((cfg.ip, cfg.port), async move {
-            rx.recv().await.expect("Failed to listen for event")
-        });
+        let (addr, server) = serve.bind_with_graceful_shutdown((cfg.ip, cfg.port), shutdown);
 
         tracing::info!("warp drive engaged: listening on https://{}", addr);
 
@@ -77,9 +95,10 @@ fn api_routes(
 fn user_routes(
     enable_user: bool,
     backend: DataBackend,
+    workers: Handle,
 ) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
     enable(enable_user)
-        .and(stry_frontend_user::route(backend))
+        .and(stry_frontend_user::route(backend, workers))
         .boxed()
 }
 
@@ -87,12 +106,97 @@ fn user_routes(
 fn user_routes(
     _enable_user: bool,
     _backend: DataBackend,
+    _workers: Handle,
 ) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
     warp::any()
         .and_then(move || async move { Result::<String, _>::Err(warp::reject::not_found()) })
         .boxed()
 }
 
+/// Streams the progress of the workers' tasks to the dashboard, as
+/// server-sent events named after the [`TaskEvent`] with it as JSON data.
+fn task_event_routes(
+    enable_user: bool,
+    task_events: Sender<TaskEvent>,
+    closing: Cancellation,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    enable(enable_user)
+        .and(warp::get())
+        .and(warp::path!("dashboard" / "tasks" / "events"))
+        .map(move || {
+            let state = (task_events.subscribe(), closing.clone());
+
+            let stream = futures::stream::unfold(state, |(mut rx, closing)| async move {
+                loop {
+                    let event = tokio::select! {
+                        event = rx.recv() => event,
+                        _ = closing.cancelled() => return None,
+                    };
+
+                    match event {
+                        Ok(event) => {
+                            let sse = (warp::sse::event(event.name()), warp::sse::json(event));
+
+                            return Some((Ok::<_, Infallible>(sse), (rx, closing)));
+                        }
+                        // Missed events only leave the progress behind until the next one
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => return None,
+                    }
+                }
+            });
+
+            warp::sse::reply(warp::sse::keep_alive().stream(stream))
+        })
+        .boxed()
+}
+
+/// Serves the images downloaded from chapters by the scraper, only while the
+/// scraper downloads them.
+///
+/// Only the images themselves are served, not the files mapping URLs to them.
+fn image_routes(cfg: &Config) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    enable(cfg.scraper.images.enabled)
+        .and(warp::get())
+        .and(warp::path("images"))
+        .and(warp::path::peek())
+        .and_then(|peek: warp::path::Peek| async move {
+            if is_image_name(peek.as_str()) {
+                Ok(())
+            } else {
+                Err(warp::reject::not_found())
+            }
+        })
+        .untuple_one()
+        .and(warp::fs::dir(cfg.scraper.images.directory.clone()))
+        .with(warp::reply::with::header(
+            "X-Content-Type-Options",
+            "nosniff",
+        ))
+        .with(warp::reply::with::header(
+            "Content-Security-Policy",
+            "default-src 'none'; sandbox",
+        ))
+        .boxed()
+}
+
+/// If the path is an image stored by the scraper, a SHA-256 hash followed by
+/// one of the extensions it stores images with.
+fn is_image_name(path: &str) -> bool {
+    let mut parts = path.splitn(2, '.');
+
+    match (parts.next(), parts.next()) {
+        (Some(hash), Some(extension)) => {
+            hash.len() == 64
+                && hash
+                    .bytes()
+                    .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+                && ["bmp", "gif", "jpg", "png", "webp"].contains(&extension)
+        }
+        _ => false,
+    }
+}
+
 // https://github.com/seanmonstar/warp/issues/131
 fn enable(is_enabled: bool) -> impl Filter<Extract = (), Error = Rejection> + Clone {
     warp::any()
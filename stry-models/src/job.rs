@@ -0,0 +1,162 @@
+use {
+    crate::{Job, JobKind, JobState, WorkerSite},
+    serde::{de::DeserializeOwned, Serialize},
+    std::fmt,
+};
+
+/// The data a job of a kind is run with, stored as JSON in the job's
+/// payload.
+pub trait Payload: Serialize + DeserializeOwned {
+    const KIND: JobKind;
+
+    /// The number of times the job is run before it is dead.
+    const MAX_ATTEMPTS: i32 = 5;
+
+    fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+}
+
+impl Job {
+    /// Reads the job's payload, failing if the job is of another kind.
+    pub fn payload_as<P>(&self) -> anyhow::Result<P>
+    where
+        P: Payload,
+    {
+        if self.kind != P::KIND {
+            anyhow::bail!(
+                "Job {} is a {} job, not a {} job",
+                self.id,
+                self.kind,
+                P::KIND
+            );
+        }
+
+        Ok(serde_json::from_str(&self.payload)?)
+    }
+}
+
+/// Exports a story's chapters to a file.
+#[rustfmt::skip]
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct Export {
+    pub story: String,
+}
+
+impl Payload for Export {
+    const KIND: JobKind = JobKind::Export;
+}
+
+/// Downloads an image so it is served from stry.
+#[rustfmt::skip]
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct ImageDownload {
+    pub url: String,
+}
+
+impl Payload for ImageDownload {
+    const KIND: JobKind = JobKind::ImageDownload;
+}
+
+/// Rebuilds the search index of a story, or of every story.
+#[rustfmt::skip]
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct Reindex {
+    pub story: Option<String>,
+}
+
+impl Payload for Reindex {
+    const KIND: JobKind = JobKind::Reindex;
+    const MAX_ATTEMPTS: i32 = 1;
+}
+
+/// Checks a story for new chapters, by queuing a worker task for it.
+#[rustfmt::skip]
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct UpdateCheck {
+    pub name: String,
+    pub site: WorkerSite,
+    pub url: String,
+}
+
+impl Payload for UpdateCheck {
+    const KIND: JobKind = JobKind::UpdateCheck;
+}
+
+/// Copies the database into the directory.
+#[rustfmt::skip]
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct Backup {
+    pub directory: String,
+}
+
+impl Payload for Backup {
+    const KIND: JobKind = JobKind::Backup;
+    const MAX_ATTEMPTS: i32 = 1;
+}
+
+/// Removes cached pages that have not been fetched in the number of days, or
+/// every page.
+#[rustfmt::skip]
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct PruneCache {
+    pub older_than: Option<i64>,
+}
+
+impl Payload for PruneCache {
+    const KIND: JobKind = JobKind::PruneCache;
+    const MAX_ATTEMPTS: i32 = 1;
+}
+
+/// Queues every scraped story again, to check them for new chapters.
+#[rustfmt::skip]
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct UpdateAll {}
+
+impl Payload for UpdateAll {
+    const KIND: JobKind = JobKind::UpdateAll;
+}
+
+/// Rebuilds the database and updates its statistics.
+#[rustfmt::skip]
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct Vacuum {}
+
+impl Payload for Vacuum {
+    const KIND: JobKind = JobKind::Vacuum;
+    const MAX_ATTEMPTS: i32 = 1;
+}
+
+impl fmt::Display for JobKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JobKind::Export => write!(f, "export"),
+            JobKind::ImageDownload => write!(f, "image download"),
+            JobKind::Reindex => write!(f, "reindex"),
+            JobKind::UpdateCheck => write!(f, "update check"),
+            JobKind::Backup => write!(f, "backup"),
+            JobKind::PruneCache => write!(f, "prune cache"),
+            JobKind::UpdateAll => write!(f, "update all"),
+            JobKind::Vacuum => write!(f, "vacuum"),
+        }
+    }
+}
+
+impl fmt::Display for JobState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JobState::Queued => write!(f, "queued"),
+            JobState::Running => write!(f, "running"),
+            JobState::Completed => write!(f, "completed"),
+            JobState::Dead => write!(f, "dead"),
+        }
+    }
+}
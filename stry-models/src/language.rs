@@ -0,0 +1,385 @@
+//! ISO 639 languages, limited to those that have a two letter (639-1) code.
+//!
+//! Anything else is kept as [`Language::Undetermined`] so imports don't fail on
+//! languages the list doesn't cover.
+
+use {
+    serde::{de, Deserialize, Deserializer, Serialize, Serializer},
+    std::{error::Error, fmt, str::FromStr},
+};
+
+macro_rules! languages {
+    ($( $variant:ident => ($code:literal, $alpha3:literal, $bibliographic:expr, $name:literal), )*) => {
+        #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+        pub enum Language {
+            $( $variant, )*
+        }
+
+        impl Language {
+            pub const ALL: &'static [Language] = &[$( Language::$variant, )*];
+
+            /// The ISO 639-1 code, this is what gets stored in the database.
+            ///
+            /// [`Language::Undetermined`] uses the reserved 639-2 code `und` instead.
+            pub fn code(self) -> &'static str {
+                match self {
+                    $( Language::$variant => $code, )*
+                }
+            }
+
+            /// The ISO 639-2/T code.
+            pub fn alpha3(self) -> &'static str {
+                match self {
+                    $( Language::$variant => $alpha3, )*
+                }
+            }
+
+            /// The ISO 639-2/B code, only present when it differs from the terminology code.
+            pub fn bibliographic(self) -> Option<&'static str> {
+                match self {
+                    $( Language::$variant => $bibliographic, )*
+                }
+            }
+
+            /// The English name of the language.
+            pub fn name(self) -> &'static str {
+                match self {
+                    $( Language::$variant => $name, )*
+                }
+            }
+        }
+    };
+}
+
+languages! {
+    Abkhazian => ("ab", "abk", None, "Abkhazian"),
+    Afar => ("aa", "aar", None, "Afar"),
+    Afrikaans => ("af", "afr", None, "Afrikaans"),
+    Akan => ("ak", "aka", None, "Akan"),
+    Albanian => ("sq", "sqi", Some("alb"), "Albanian"),
+    Amharic => ("am", "amh", None, "Amharic"),
+    Arabic => ("ar", "ara", None, "Arabic"),
+    Aragonese => ("an", "arg", None, "Aragonese"),
+    Armenian => ("hy", "hye", Some("arm"), "Armenian"),
+    Assamese => ("as", "asm", None, "Assamese"),
+    Avaric => ("av", "ava", None, "Avaric"),
+    Avestan => ("ae", "ave", None, "Avestan"),
+    Aymara => ("ay", "aym", None, "Aymara"),
+    Azerbaijani => ("az", "aze", None, "Azerbaijani"),
+    Bambara => ("bm", "bam", None, "Bambara"),
+    Bashkir => ("ba", "bak", None, "Bashkir"),
+    Basque => ("eu", "eus", Some("baq"), "Basque"),
+    Belarusian => ("be", "bel", None, "Belarusian"),
+    Bengali => ("bn", "ben", None, "Bengali"),
+    BihariLanguages => ("bh", "bih", None, "Bihari languages"),
+    Bislama => ("bi", "bis", None, "Bislama"),
+    Bosnian => ("bs", "bos", None, "Bosnian"),
+    Breton => ("br", "bre", None, "Breton"),
+    Bulgarian => ("bg", "bul", None, "Bulgarian"),
+    Burmese => ("my", "mya", Some("bur"), "Burmese"),
+    Catalan => ("ca", "cat", None, "Catalan"),
+    CentralKhmer => ("km", "khm", None, "Central Khmer"),
+    Chamorro => ("ch", "cha", None, "Chamorro"),
+    Chechen => ("ce", "che", None, "Chechen"),
+    Chichewa => ("ny", "nya", None, "Chichewa"),
+    Chinese => ("zh", "zho", Some("chi"), "Chinese"),
+    ChurchSlavic => ("cu", "chu", None, "Church Slavic"),
+    Chuvash => ("cv", "chv", None, "Chuvash"),
+    Cornish => ("kw", "cor", None, "Cornish"),
+    Corsican => ("co", "cos", None, "Corsican"),
+    Cree => ("cr", "cre", None, "Cree"),
+    Croatian => ("hr", "hrv", None, "Croatian"),
+    Czech => ("cs", "ces", Some("cze"), "Czech"),
+    Danish => ("da", "dan", None, "Danish"),
+    Divehi => ("dv", "div", None, "Divehi"),
+    Dutch => ("nl", "nld", Some("dut"), "Dutch"),
+    Dzongkha => ("dz", "dzo", None, "Dzongkha"),
+    English => ("en", "eng", None, "English"),
+    Esperanto => ("eo", "epo", None, "Esperanto"),
+    Estonian => ("et", "est", None, "Estonian"),
+    Ewe => ("ee", "ewe", None, "Ewe"),
+    Faroese => ("fo", "fao", None, "Faroese"),
+    Fijian => ("fj", "fij", None, "Fijian"),
+    Finnish => ("fi", "fin", None, "Finnish"),
+    French => ("fr", "fra", Some("fre"), "French"),
+    Fulah => ("ff", "ful", None, "Fulah"),
+    Gaelic => ("gd", "gla", None, "Gaelic"),
+    Galician => ("gl", "glg", None, "Galician"),
+    Ganda => ("lg", "lug", None, "Ganda"),
+    Georgian => ("ka", "kat", Some("geo"), "Georgian"),
+    German => ("de", "deu", Some("ger"), "German"),
+    Greek => ("el", "ell", Some("gre"), "Greek"),
+    Guarani => ("gn", "grn", None, "Guarani"),
+    Gujarati => ("gu", "guj", None, "Gujarati"),
+    Haitian => ("ht", "hat", None, "Haitian"),
+    Hausa => ("ha", "hau", None, "Hausa"),
+    Hebrew => ("he", "heb", None, "Hebrew"),
+    Herero => ("hz", "her", None, "Herero"),
+    Hindi => ("hi", "hin", None, "Hindi"),
+    HiriMotu => ("ho", "hmo", None, "Hiri Motu"),
+    Hungarian => ("hu", "hun", None, "Hungarian"),
+    Icelandic => ("is", "isl", Some("ice"), "Icelandic"),
+    Ido => ("io", "ido", None, "Ido"),
+    Igbo => ("ig", "ibo", None, "Igbo"),
+    Indonesian => ("id", "ind", None, "Indonesian"),
+    Interlingua => ("ia", "ina", None, "Interlingua"),
+    Interlingue => ("ie", "ile", None, "Interlingue"),
+    Inuktitut => ("iu", "iku", None, "Inuktitut"),
+    Inupiaq => ("ik", "ipk", None, "Inupiaq"),
+    Irish => ("ga", "gle", None, "Irish"),
+    Italian => ("it", "ita", None, "Italian"),
+    Japanese => ("ja", "jpn", None, "Japanese"),
+    Javanese => ("jv", "jav", None, "Javanese"),
+    Kalaallisut => ("kl", "kal", None, "Kalaallisut"),
+    Kannada => ("kn", "kan", None, "Kannada"),
+    Kanuri => ("kr", "kau", None, "Kanuri"),
+    Kashmiri => ("ks", "kas", None, "Kashmiri"),
+    Kazakh => ("kk", "kaz", None, "Kazakh"),
+    Kikuyu => ("ki", "kik", None, "Kikuyu"),
+    Kinyarwanda => ("rw", "kin", None, "Kinyarwanda"),
+    Kirghiz => ("ky", "kir", None, "Kirghiz"),
+    Komi => ("kv", "kom", None, "Komi"),
+    Kongo => ("kg", "kon", None, "Kongo"),
+    Korean => ("ko", "kor", None, "Korean"),
+    Kuanyama => ("kj", "kua", None, "Kuanyama"),
+    Kurdish => ("ku", "kur", None, "Kurdish"),
+    Lao => ("lo", "lao", None, "Lao"),
+    Latin => ("la", "lat", None, "Latin"),
+    Latvian => ("lv", "lav", None, "Latvian"),
+    Limburgan => ("li", "lim", None, "Limburgan"),
+    Lingala => ("ln", "lin", None, "Lingala"),
+    Lithuanian => ("lt", "lit", None, "Lithuanian"),
+    LubaKatanga => ("lu", "lub", None, "Luba-Katanga"),
+    Luxembourgish => ("lb", "ltz", None, "Luxembourgish"),
+    Macedonian => ("mk", "mkd", Some("mac"), "Macedonian"),
+    Malagasy => ("mg", "mlg", None, "Malagasy"),
+    Malay => ("ms", "msa", Some("may"), "Malay"),
+    Malayalam => ("ml", "mal", None, "Malayalam"),
+    Maltese => ("mt", "mlt", None, "Maltese"),
+    Manx => ("gv", "glv", None, "Manx"),
+    Maori => ("mi", "mri", Some("mao"), "Maori"),
+    Marathi => ("mr", "mar", None, "Marathi"),
+    Marshallese => ("mh", "mah", None, "Marshallese"),
+    Mongolian => ("mn", "mon", None, "Mongolian"),
+    Nauru => ("na", "nau", None, "Nauru"),
+    Navajo => ("nv", "nav", None, "Navajo"),
+    Ndonga => ("ng", "ndo", None, "Ndonga"),
+    Nepali => ("ne", "nep", None, "Nepali"),
+    NorthNdebele => ("nd", "nde", None, "North Ndebele"),
+    NorthernSami => ("se", "sme", None, "Northern Sami"),
+    Norwegian => ("no", "nor", None, "Norwegian"),
+    NorwegianBokmal => ("nb", "nob", None, "Norwegian Bokmål"),
+    NorwegianNynorsk => ("nn", "nno", None, "Norwegian Nynorsk"),
+    Occitan => ("oc", "oci", None, "Occitan"),
+    Ojibwa => ("oj", "oji", None, "Ojibwa"),
+    Oriya => ("or", "ori", None, "Oriya"),
+    Oromo => ("om", "orm", None, "Oromo"),
+    Ossetian => ("os", "oss", None, "Ossetian"),
+    Pali => ("pi", "pli", None, "Pali"),
+    Panjabi => ("pa", "pan", None, "Panjabi"),
+    Persian => ("fa", "fas", Some("per"), "Persian"),
+    Polish => ("pl", "pol", None, "Polish"),
+    Portuguese => ("pt", "por", None, "Portuguese"),
+    Pushto => ("ps", "pus", None, "Pushto"),
+    Quechua => ("qu", "que", None, "Quechua"),
+    Romanian => ("ro", "ron", Some("rum"), "Romanian"),
+    Romansh => ("rm", "roh", None, "Romansh"),
+    Rundi => ("rn", "run", None, "Rundi"),
+    Russian => ("ru", "rus", None, "Russian"),
+    Samoan => ("sm", "smo", None, "Samoan"),
+    Sango => ("sg", "sag", None, "Sango"),
+    Sanskrit => ("sa", "san", None, "Sanskrit"),
+    Sardinian => ("sc", "srd", None, "Sardinian"),
+    Serbian => ("sr", "srp", None, "Serbian"),
+    Shona => ("sn", "sna", None, "Shona"),
+    SichuanYi => ("ii", "iii", None, "Sichuan Yi"),
+    Sindhi => ("sd", "snd", None, "Sindhi"),
+    Sinhala => ("si", "sin", None, "Sinhala"),
+    Slovak => ("sk", "slk", Some("slo"), "Slovak"),
+    Slovenian => ("sl", "slv", None, "Slovenian"),
+    Somali => ("so", "som", None, "Somali"),
+    SouthNdebele => ("nr", "nbl", None, "South Ndebele"),
+    SouthernSotho => ("st", "sot", None, "Southern Sotho"),
+    Spanish => ("es", "spa", None, "Spanish"),
+    Sundanese => ("su", "sun", None, "Sundanese"),
+    Swahili => ("sw", "swa", None, "Swahili"),
+    Swati => ("ss", "ssw", None, "Swati"),
+    Swedish => ("sv", "swe", None, "Swedish"),
+    Tagalog => ("tl", "tgl", None, "Tagalog"),
+    Tahitian => ("ty", "tah", None, "Tahitian"),
+    Tajik => ("tg", "tgk", None, "Tajik"),
+    Tamil => ("ta", "tam", None, "Tamil"),
+    Tatar => ("tt", "tat", None, "Tatar"),
+    Telugu => ("te", "tel", None, "Telugu"),
+    Thai => ("th", "tha", None, "Thai"),
+    Tibetan => ("bo", "bod", Some("tib"), "Tibetan"),
+    Tigrinya => ("ti", "tir", None, "Tigrinya"),
+    Tonga => ("to", "ton", None, "Tonga"),
+    Tsonga => ("ts", "tso", None, "Tsonga"),
+    Tswana => ("tn", "tsn", None, "Tswana"),
+    Turkish => ("tr", "tur", None, "Turkish"),
+    Turkmen => ("tk", "tuk", None, "Turkmen"),
+    Twi => ("tw", "twi", None, "Twi"),
+    Uighur => ("ug", "uig", None, "Uighur"),
+    Ukrainian => ("uk", "ukr", None, "Ukrainian"),
+    Undetermined => ("und", "und", None, "Undetermined"),
+    Urdu => ("ur", "urd", None, "Urdu"),
+    Uzbek => ("uz", "uzb", None, "Uzbek"),
+    Venda => ("ve", "ven", None, "Venda"),
+    Vietnamese => ("vi", "vie", None, "Vietnamese"),
+    Volapuk => ("vo", "vol", None, "Volapük"),
+    Walloon => ("wa", "wln", None, "Walloon"),
+    Welsh => ("cy", "cym", Some("wel"), "Welsh"),
+    WesternFrisian => ("fy", "fry", None, "Western Frisian"),
+    Wolof => ("wo", "wol", None, "Wolof"),
+    Xhosa => ("xh", "xho", None, "Xhosa"),
+    Yiddish => ("yi", "yid", None, "Yiddish"),
+    Yoruba => ("yo", "yor", None, "Yoruba"),
+    Zhuang => ("za", "zha", None, "Zhuang"),
+    Zulu => ("zu", "zul", None, "Zulu"),
+}
+
+impl Default for Language {
+    fn default() -> Self {
+        Language::English
+    }
+}
+
+impl Language {
+    /// Parses like [`FromStr`] but falls back to [`Language::Undetermined`],
+    /// for sites that list languages without a two letter code.
+    pub fn parse_lossy(s: &str) -> Language {
+        s.parse().unwrap_or(Language::Undetermined)
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Language {
+    type Err = UnknownLanguage;
+
+    /// Parses either a two or three letter code or the English name, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        Language::ALL
+            .iter()
+            .copied()
+            .find(|language| {
+                language.code().eq_ignore_ascii_case(s)
+                    || language.alpha3().eq_ignore_ascii_case(s)
+                    || language
+                        .bibliographic()
+                        .map(|code| code.eq_ignore_ascii_case(s))
+                        .unwrap_or(false)
+                    || language.name().to_lowercase() == s.to_lowercase()
+            })
+            .ok_or_else(|| UnknownLanguage(s.to_string()))
+    }
+}
+
+impl Serialize for Language {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.code())
+    }
+}
+
+impl<'de> Deserialize<'de> for Language {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+
+        value.parse().map_err(de::Error::custom)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownLanguage(pub String);
+
+impl fmt::Display for UnknownLanguage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown language: {}", self.0)
+    }
+}
+
+impl Error for UnknownLanguage {}
+
+#[cfg(test)]
+mod test_super {
+    use super::*;
+
+    #[test]
+    fn from_str_code() {
+        assert_eq!(Ok(Language::English), "en".parse::<Language>());
+        assert_eq!(Ok(Language::German), " DE ".parse::<Language>());
+    }
+
+    #[test]
+    fn from_str_alpha3() {
+        assert_eq!(Ok(Language::English), "eng".parse::<Language>());
+        assert_eq!(Ok(Language::German), "deu".parse::<Language>());
+    }
+
+    #[test]
+    fn from_str_bibliographic() {
+        assert_eq!(Ok(Language::German), "ger".parse::<Language>());
+        assert_eq!(Ok(Language::Chinese), "chi".parse::<Language>());
+    }
+
+    #[test]
+    fn from_str_name() {
+        assert_eq!(Ok(Language::English), "english".parse::<Language>());
+        assert_eq!(
+            Ok(Language::WesternFrisian),
+            "Western Frisian".parse::<Language>()
+        );
+    }
+
+    #[test]
+    fn from_str_unknown() {
+        assert_eq!(
+            Err(UnknownLanguage("fil".to_string())),
+            "fil".parse::<Language>()
+        );
+    }
+
+    #[test]
+    fn parse_lossy() {
+        assert_eq!(Language::French, Language::parse_lossy("fr"));
+        assert_eq!(Language::Undetermined, Language::parse_lossy("fil"));
+        assert_eq!(Language::Undetermined, Language::parse_lossy("Devanagari"));
+    }
+
+    #[test]
+    fn alpha3() {
+        assert_eq!("eng", Language::English.alpha3());
+        assert_eq!("deu", Language::German.alpha3());
+        assert_eq!("und", Language::Undetermined.alpha3());
+    }
+
+    #[test]
+    fn bibliographic() {
+        assert_eq!(None, Language::English.bibliographic());
+        assert_eq!(Some("ger"), Language::German.bibliographic());
+        assert_eq!(Some("wel"), Language::Welsh.bibliographic());
+    }
+
+    #[test]
+    fn codes_are_unique() {
+        for (i, language) in Language::ALL.iter().enumerate() {
+            for other in &Language::ALL[i + 1..] {
+                assert_ne!(language.code(), other.code());
+                assert_ne!(language.alpha3(), other.alpha3());
+            }
+        }
+    }
+}
@@ -4,6 +4,8 @@ pub mod api;
 pub mod author;
 pub mod chapter;
 pub mod character;
+pub mod job;
+pub mod language;
 pub mod notification;
 pub mod origin;
 pub mod pairing;
@@ -18,15 +20,18 @@ use std::fmt;
 
 pub use self::{
     generated::{
-        Author, Chapter, Character, Origin, Pairing as PairingRow, Rating, State,
-        Story as StoryRow, Tag, Warning, Worker as WorkerRow, WorkerSite, WorkerState, WorkerTask,
+        Author, Chapter, Character, Job, JobKind, JobState, Origin, Pairing as PairingRow, Rating,
+        Settings, State, Story as StoryRow, Tag, Warning, Worker as WorkerRow, WorkerSite,
+        WorkerState, WorkerTask,
     },
+    job::Payload,
+    language::Language,
     notification::{Level, Notification},
     pairing::Pairing,
     series::Series,
     site::Site,
     story::{Square, Story},
-    worker::Worker,
+    worker::{TaskEvent, Worker},
 };
 
 pub trait Node {
@@ -4,6 +4,12 @@
 pub enum Site {
     ArchiveOfOurOwn,
     FanFiction,
+    FicWad,
+    QuestionableQuesting,
+    RoyalRoad,
+    SpaceBattles,
+    SufficientVelocity,
+    Wattpad,
 }
 
 impl Site {
@@ -11,6 +17,12 @@ impl Site {
         match self {
             Site::ArchiveOfOurOwn => "archive-of-our-own",
             Site::FanFiction => "fanfiction",
+            Site::FicWad => "ficwad",
+            Site::QuestionableQuesting => "questionable-questing",
+            Site::RoyalRoad => "royal-road",
+            Site::SpaceBattles => "spacebattles",
+            Site::SufficientVelocity => "sufficient-velocity",
+            Site::Wattpad => "wattpad",
         }
     }
 }
@@ -1,7 +1,7 @@
 use {
     crate::{
-        pairing::PairingBuilder, Author, Character, List, Origin, Pairing, Rating, Series, State,
-        Tag, Warning,
+        pairing::PairingBuilder, Author, Character, Language, List, Origin, Pairing, Rating,
+        Series, State, Tag, Warning,
     },
     anyhow::Context,
     chrono::{DateTime, TimeZone as _, Utc},
@@ -20,6 +20,8 @@ pub struct Story {
     pub name: String,
     pub summary: String,
 
+    pub language: Language,
+
     pub square: Square,
 
     pub chapters: i32,
@@ -94,6 +96,7 @@ pub struct Story {
 //     }
 // }
 
+#[derive(Clone, Debug)]
 pub struct StoryRow {
     pub id: String,
 
@@ -109,6 +112,8 @@ pub struct StoryRow {
 
     pub chapters: i32,
     pub words: i32,
+
+    pub language: Language,
 }
 
 #[cfg(feature = "sqlite")]
@@ -150,6 +155,11 @@ impl FromRow for StoryRow {
             words: row
                 .get(8)
                 .context("Attempting to get row index 8 for story (row)")?,
+
+            language: row
+                .get::<_, String>(9)
+                .context("Attempting to get row index 9 for story (row)")?
+                .parse()?,
         })
     }
 }
@@ -160,6 +170,8 @@ pub struct StoryPart {
     pub name: String,
     pub summary: String,
 
+    pub language: Language,
+
     pub rating: Rating,
     pub state: State,
 
@@ -318,6 +330,8 @@ pub struct StoryBuilder {
     pub name: String,
     pub summary: String,
 
+    pub language: Language,
+
     pub rating: Rating,
     pub state: State,
 
@@ -349,6 +363,8 @@ impl StoryBuilder {
             name: name.into(),
             summary: summary.into(),
 
+            language: Language::English,
+
             rating,
             state,
 
@@ -372,6 +388,8 @@ impl StoryBuilder {
             name: self.name,
             summary: self.summary,
 
+            language: self.language,
+
             square: Square {
                 rating: self.rating,
                 state: self.state,
@@ -396,6 +414,12 @@ impl StoryBuilder {
         }
     }
 
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.language = language;
+
+        self
+    }
+
     pub fn with_author(mut self, id: impl Into<String>, name: impl Into<String>) -> Self {
         self.authors.push(Author::new_test(id, name));
 
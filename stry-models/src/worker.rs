@@ -16,11 +16,52 @@ pub struct Worker {
     pub updated: DateTime<Utc>,
 }
 
+/// Sent by the workers as they work on a task, to update the dashboard.
+#[rustfmt::skip]
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum TaskEvent {
+    /// A chapter of the task's story has been fetched.
+    Progress {
+        task: String,
+        chapter: u32,
+        chapters: u32,
+    },
+    /// Every chapter of the task's story has been fetched.
+    Completed {
+        task: String,
+    },
+    /// The task failed, tasks that are retried stay waiting.
+    Failed {
+        task: String,
+        error: String,
+        retry: bool,
+    },
+}
+
+impl TaskEvent {
+    /// The name of the event, for server-sent events.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TaskEvent::Progress { .. } => "progress",
+            TaskEvent::Completed { .. } => "completed",
+            TaskEvent::Failed { .. } => "failed",
+        }
+    }
+}
+
 impl WorkerSite {
     pub fn url(&self) -> &'static str {
         match self {
             WorkerSite::ArchiveOfOurOwn => "https://archiveofourown.org/",
             WorkerSite::FanFictionNet => "https://fanfiction.net/",
+            WorkerSite::FicWad => "https://ficwad.com/",
+            WorkerSite::QuestionableQuesting => "https://forum.questionablequesting.com/",
+            WorkerSite::RoyalRoad => "https://www.royalroad.com/",
+            WorkerSite::SpaceBattles => "https://forums.spacebattles.com/",
+            WorkerSite::SufficientVelocity => "https://forums.sufficientvelocity.com/",
+            WorkerSite::Wattpad => "https://www.wattpad.com/",
         }
     }
 }
@@ -30,6 +71,12 @@ impl fmt::Display for WorkerSite {
         match self {
             WorkerSite::ArchiveOfOurOwn => write!(f, "Archive of Our Own"),
             WorkerSite::FanFictionNet => write!(f, "FanFiction.net"),
+            WorkerSite::FicWad => write!(f, "FicWad"),
+            WorkerSite::QuestionableQuesting => write!(f, "Questionable Questing"),
+            WorkerSite::RoyalRoad => write!(f, "Royal Road"),
+            WorkerSite::SpaceBattles => write!(f, "SpaceBattles"),
+            WorkerSite::SufficientVelocity => write!(f, "Sufficient Velocity"),
+            WorkerSite::Wattpad => write!(f, "Wattpad"),
         }
     }
 }
@@ -0,0 +1,344 @@
+use {
+    crate::{render_limit, render_order, Error, Order, Parameter, Select, Value, Variant},
+    std::fmt::{self, Write as _},
+};
+
+#[derive(Debug)]
+enum Operator {
+    Union,
+    UnionAll,
+    Intersect,
+    Except,
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Operator::Union => "UNION",
+                Operator::UnionAll => "UNION ALL",
+                Operator::Intersect => "INTERSECT",
+                Operator::Except => "EXCEPT",
+            }
+        )
+    }
+}
+
+/// Selects joined with `UNION`, `INTERSECT`, or `EXCEPT`, the operators are
+/// applied from left to right.
+///
+/// PostgreSQL applies `INTERSECT` before the other operators, so the selects
+/// before each operator are wrapped in parentheses for it.
+///
+/// SQLite does not allow the joined selects to have their own `ORDER BY` or
+/// `LIMIT`, order and limit the compound instead.
+#[derive(Debug)]
+pub struct Compound<'q> {
+    first: Select<'q>,
+    rest: Vec<(Operator, Select<'q>)>,
+
+    order: Option<Vec<(&'q str, Order)>>,
+
+    limit: Option<Value<'q>>,
+    offset: Option<Value<'q>>,
+}
+
+impl<'q> Compound<'q> {
+    pub(crate) fn new(first: Select<'q>) -> Compound<'q> {
+        Compound {
+            first,
+            rest: Vec::with_capacity(1),
+
+            order: None,
+
+            limit: None,
+            offset: None,
+        }
+    }
+
+    /// `UNION`
+    pub fn union(mut self, other: Select<'q>) -> Compound<'q> {
+        self.rest.push((Operator::Union, other));
+
+        self
+    }
+
+    /// `UNION ALL`
+    pub fn union_all(mut self, other: Select<'q>) -> Compound<'q> {
+        self.rest.push((Operator::UnionAll, other));
+
+        self
+    }
+
+    /// `INTERSECT`
+    pub fn intersect(mut self, other: Select<'q>) -> Compound<'q> {
+        self.rest.push((Operator::Intersect, other));
+
+        self
+    }
+
+    /// `EXCEPT`
+    pub fn except(mut self, other: Select<'q>) -> Compound<'q> {
+        self.rest.push((Operator::Except, other));
+
+        self
+    }
+
+    /// `ORDER BY`
+    pub fn order_by(mut self, stmt: &'q str, order: Order) -> Compound<'q> {
+        self.order
+            .get_or_insert_with(|| Vec::with_capacity(1))
+            .push((stmt, order));
+
+        self
+    }
+
+    /// `LIMIT`
+    pub fn limit<V>(mut self, limit: V) -> Compound<'q>
+    where
+        V: Into<Value<'q>>,
+    {
+        self.limit = Some(limit.into());
+
+        self
+    }
+
+    /// `OFFSET`
+    pub fn offset<V>(mut self, offset: V) -> Compound<'q>
+    where
+        V: Into<Value<'q>>,
+    {
+        self.offset = Some(offset.into());
+
+        self
+    }
+
+    pub fn build(self, variant: Variant) -> Result<(String, Vec<Parameter>), Error> {
+        Query::from(self).build(variant)
+    }
+
+    pub(crate) fn render(
+        self,
+        variant: Variant,
+        buff: &mut String,
+        parameters: &mut Vec<Parameter>,
+    ) -> fmt::Result {
+        let nested = match variant {
+            Variant::PostgreSQL => self.rest.len().saturating_sub(1),
+            Variant::SQLite => 0,
+        };
+
+        for _ in 0..nested {
+            write!(buff, "(")?;
+        }
+
+        self.first.render(variant, buff, parameters)?;
+
+        for (i, (operator, select)) in self.rest.into_iter().enumerate() {
+            if i != 0 && nested != 0 {
+                write!(buff, ")")?;
+            }
+
+            write!(buff, " {} ", operator)?;
+
+            select.render(variant, buff, parameters)?;
+        }
+
+        if let Some(orders) = self.order {
+            render_order(orders, buff)?;
+        }
+
+        render_limit(self.limit, self.offset, variant, buff, parameters)
+    }
+}
+
+/// Either a select or a compound select, used where both are allowed.
+#[derive(Debug)]
+pub enum Query<'q> {
+    Select(Select<'q>),
+    Compound(Compound<'q>),
+}
+
+impl<'q> Query<'q> {
+    pub fn build(self, variant: Variant) -> Result<(String, Vec<Parameter>), Error> {
+        let mut buff = String::with_capacity(1024);
+        let mut parameters = Vec::new();
+
+        self.render(variant, &mut buff, &mut parameters)?;
+
+        write!(&mut buff, ";")?;
+
+        buff.shrink_to_fit();
+
+        Ok((buff, parameters))
+    }
+
+    pub(crate) fn render(
+        self,
+        variant: Variant,
+        buff: &mut String,
+        parameters: &mut Vec<Parameter>,
+    ) -> fmt::Result {
+        match self {
+            Query::Select(select) => select.render(variant, buff, parameters),
+            Query::Compound(compound) => compound.render(variant, buff, parameters),
+        }
+    }
+}
+
+impl<'q> From<Select<'q>> for Query<'q> {
+    fn from(value: Select<'q>) -> Self {
+        Query::Select(value)
+    }
+}
+
+impl<'q> From<Compound<'q>> for Query<'q> {
+    fn from(value: Compound<'q>) -> Self {
+        Query::Compound(value)
+    }
+}
+
+#[cfg(test)]
+mod test_super {
+    use crate::{prelude::*, Compound};
+
+    fn search() -> Compound<'static> {
+        select("Story".alias("S"))
+            .table("StoryCharacter".alias("SC"))
+            .fields(&["S.Id", "S.Updated"])
+            .filter("S.Id".is_eq("SC.StoryId"))
+            .filter(
+                "SC.CharacterId".is_eq(
+                    select("Character")
+                        .field("Id")
+                        .filter("LOWER(Name)".is_like("character".to_string())),
+                ),
+            )
+            .intersect(
+                select("Story")
+                    .fields(&["Id", "Updated"])
+                    .filter("Rating".is_eq("teen".to_string())),
+            )
+            .except(
+                select("Story")
+                    .fields(&["Id", "Updated"])
+                    .filter("Language".is_eq("de".to_string())),
+            )
+            .order_by("2", Desc)
+            .limit(10)
+            .offset(0)
+    }
+
+    #[test]
+    fn test_search_sqlite() {
+        let (query, parameters) = search().build(SQLite).unwrap();
+
+        assert_eq!(
+            "SELECT S.Id, S.Updated FROM Story S, StoryCharacter SC WHERE S.Id = SC.StoryId AND SC.CharacterId = (SELECT Id FROM Character WHERE LOWER(Name) LIKE ?) INTERSECT SELECT Id, Updated FROM Story WHERE Rating = ? EXCEPT SELECT Id, Updated FROM Story WHERE Language = ? ORDER BY 2 DESC LIMIT ? OFFSET ?;",
+            query,
+        );
+
+        assert_eq!(
+            vec![
+                Parameter::String("character".into()),
+                Parameter::String("teen".into()),
+                Parameter::String("de".into()),
+                Parameter::Signed32(10),
+                Parameter::Signed32(0),
+            ],
+            parameters,
+        );
+    }
+
+    #[test]
+    fn test_search_postgres() {
+        let (query, parameters) = search().build(PostgreSQL).unwrap();
+
+        assert_eq!(
+            "(SELECT S.Id, S.Updated FROM Story S, StoryCharacter SC WHERE S.Id = SC.StoryId AND SC.CharacterId = (SELECT Id FROM Character WHERE LOWER(Name) LIKE $1) INTERSECT SELECT Id, Updated FROM Story WHERE Rating = $2) EXCEPT SELECT Id, Updated FROM Story WHERE Language = $3 ORDER BY 2 DESC LIMIT $4 OFFSET $5;",
+            query,
+        );
+
+        assert_eq!(5, parameters.len());
+    }
+
+    fn auto() -> Compound<'static> {
+        select("Character")
+            .fields(&["Id", "Name", "'character' AS Type"])
+            .filter("Name".is_like("a%".to_string()))
+            .union_all(
+                select("Tag")
+                    .fields(&["Id", "Name", "'tag' AS Type"])
+                    .filter("Name".is_like("a%".to_string())),
+            )
+            .order_by("Name", Asc)
+            .limit(10)
+    }
+
+    #[test]
+    fn test_auto_sqlite() {
+        let (query, _) = auto().build(SQLite).unwrap();
+
+        assert_eq!(
+            "SELECT Id, Name, 'character' AS Type FROM Character WHERE Name LIKE ? UNION ALL SELECT Id, Name, 'tag' AS Type FROM Tag WHERE Name LIKE ? ORDER BY Name ASC LIMIT ?;",
+            query,
+        );
+    }
+
+    #[test]
+    fn test_auto_postgres() {
+        let (query, _) = auto().build(PostgreSQL).unwrap();
+
+        assert_eq!(
+            "SELECT Id, Name, 'character' AS Type FROM Character WHERE Name LIKE $1 UNION ALL SELECT Id, Name, 'tag' AS Type FROM Tag WHERE Name LIKE $2 ORDER BY Name ASC LIMIT $3;",
+            query,
+        );
+    }
+
+    fn tagged() -> Compound<'static> {
+        select("StoryTag")
+            .field("StoryId")
+            .filter("TagId".is_eq("a".to_string()))
+            .union(
+                select("StoryTag")
+                    .field("StoryId")
+                    .filter("TagId".is_eq("b".to_string())),
+            )
+            .intersect(
+                select("Story")
+                    .field("Id")
+                    .filter("Rating".is_eq("teen".to_string())),
+            )
+    }
+
+    #[test]
+    fn test_union_intersect_sqlite() {
+        let (query, _) = tagged().build(SQLite).unwrap();
+
+        assert_eq!(
+            "SELECT StoryId FROM StoryTag WHERE TagId = ? UNION SELECT StoryId FROM StoryTag WHERE TagId = ? INTERSECT SELECT Id FROM Story WHERE Rating = ?;",
+            query,
+        );
+    }
+
+    #[test]
+    fn test_union_intersect_postgres() {
+        let (query, parameters) = tagged().build(PostgreSQL).unwrap();
+
+        assert_eq!(
+            "(SELECT StoryId FROM StoryTag WHERE TagId = $1 UNION SELECT StoryId FROM StoryTag WHERE TagId = $2) INTERSECT SELECT Id FROM Story WHERE Rating = $3;",
+            query,
+        );
+
+        assert_eq!(
+            vec![
+                Parameter::String("a".into()),
+                Parameter::String("b".into()),
+                Parameter::String("teen".into()),
+            ],
+            parameters,
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use {
+    crate::{render_conditions, render_returning, Condition, Error, Parameter, Variant},
+    std::fmt::Write as _,
+};
+
+#[derive(Debug)]
+pub struct Delete<'q> {
+    table: &'q str,
+
+    conditions: Option<Vec<Condition<'q>>>,
+
+    returning: Option<Vec<&'q str>>,
+}
+
+impl<'q> Delete<'q> {
+    /// `WHERE`
+    pub fn filter<C>(mut self, condition: C) -> Delete<'q>
+    where
+        C: Into<Condition<'q>>,
+    {
+        self.conditions
+            .get_or_insert_with(|| Vec::with_capacity(1))
+            .push(condition.into());
+
+        self
+    }
+
+    /// `RETURNING`
+    pub fn returning(mut self, fields: &[&'q str]) -> Delete<'q> {
+        self.returning
+            .get_or_insert_with(|| Vec::with_capacity(fields.len()))
+            .extend(fields);
+
+        self
+    }
+
+    pub fn build(self, variant: Variant) -> Result<(String, Vec<Parameter>), Error> {
+        let mut buff = String::with_capacity(128);
+        let mut parameters = Vec::new();
+
+        write!(&mut buff, "DELETE FROM {}", self.table)?;
+
+        if let Some(conditions) = self.conditions {
+            render_conditions(conditions, variant, &mut buff, &mut parameters)?;
+        }
+
+        render_returning(self.returning, &mut buff)?;
+
+        write!(&mut buff, ";")?;
+
+        buff.shrink_to_fit();
+
+        Ok((buff, parameters))
+    }
+}
+
+#[allow(clippy::needless_lifetimes)]
+pub fn delete<'q>(table: &'q str) -> Delete<'q> {
+    Delete {
+        table,
+
+        conditions: None,
+
+        returning: None,
+    }
+}
+
+#[cfg(test)]
+mod test_super {
+    use crate::prelude::*;
+
+    #[test]
+    fn test() {
+        let query = delete("Job")
+            .filter("State".is_eq("dead".to_string()))
+            .filter("Updated".is_lt(30))
+            .returning(&["Id", "Kind"]);
+
+        let (sqlite, _) = query.build(SQLite).unwrap();
+
+        assert_eq!(
+            "DELETE FROM Job WHERE State = ? AND Updated < ? RETURNING Id, Kind;",
+            sqlite,
+        );
+
+        let query = delete("Job")
+            .filter("State".is_eq("dead".to_string()))
+            .filter("Updated".is_lt(30));
+
+        let (postgres, parameters) = query.build(PostgreSQL).unwrap();
+
+        assert_eq!(
+            "DELETE FROM Job WHERE State = $1 AND Updated < $2;",
+            postgres
+        );
+
+        assert_eq!(
+            vec![Parameter::String("dead".into()), Parameter::Signed32(30)],
+            parameters,
+        );
+    }
+}
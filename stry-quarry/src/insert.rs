@@ -0,0 +1,351 @@
+use {
+    crate::{render_returning, Error, Parameter, Value, Variant},
+    std::fmt::Write as _,
+};
+
+#[derive(Debug)]
+enum Conflict<'q> {
+    Nothing,
+    /// Sets every inserted column, other than the conflict target, to the
+    /// value it would have been inserted with.
+    Excluded,
+    /// Sets the columns to their value, or to the value it would have been
+    /// inserted with if there is none.
+    Update(Vec<(&'q str, Option<Value<'q>>)>),
+}
+
+#[derive(Debug)]
+pub struct Insert<'q> {
+    table: &'q str,
+
+    columns: Vec<&'q str>,
+    rows: Vec<Vec<Value<'q>>>,
+
+    target: Option<Vec<&'q str>>,
+    conflict: Option<Conflict<'q>>,
+
+    returning: Option<Vec<&'q str>>,
+}
+
+impl<'q> Insert<'q> {
+    pub fn column(mut self, column: &'q str) -> Insert<'q> {
+        self.columns.push(column);
+
+        self
+    }
+
+    pub fn columns(mut self, columns: &[&'q str]) -> Insert<'q> {
+        self.columns.extend(columns);
+
+        self
+    }
+
+    /// `VALUES`, a row of values in the same order as the columns.
+    pub fn values(mut self, values: Vec<Value<'q>>) -> Insert<'q> {
+        self.rows.push(values);
+
+        self
+    }
+
+    /// `ON CONFLICT`, nothing is done on a conflict unless an update is
+    /// given.
+    pub fn on_conflict(mut self, target: &[&'q str]) -> Insert<'q> {
+        self.target = Some(target.to_vec());
+        self.conflict.get_or_insert(Conflict::Nothing);
+
+        self
+    }
+
+    /// `DO NOTHING`
+    pub fn do_nothing(mut self) -> Insert<'q> {
+        self.conflict = Some(Conflict::Nothing);
+
+        self
+    }
+
+    /// `DO UPDATE SET`, setting the columns to the value they would have
+    /// been inserted with.
+    pub fn do_update(mut self, columns: &[&'q str]) -> Insert<'q> {
+        let sets = self.updates();
+
+        for column in columns {
+            sets.push((*column, None));
+        }
+
+        self
+    }
+
+    /// `DO UPDATE SET`, setting the column to the value.
+    pub fn do_update_set<V>(mut self, column: &'q str, value: V) -> Insert<'q>
+    where
+        V: Into<Value<'q>>,
+    {
+        self.updates().push((column, Some(value.into())));
+
+        self
+    }
+
+    /// `RETURNING`
+    pub fn returning(mut self, fields: &[&'q str]) -> Insert<'q> {
+        self.returning
+            .get_or_insert_with(|| Vec::with_capacity(fields.len()))
+            .extend(fields);
+
+        self
+    }
+
+    fn updates(&mut self) -> &mut Vec<(&'q str, Option<Value<'q>>)> {
+        if !matches!(self.conflict, Some(Conflict::Update(_))) {
+            self.conflict = Some(Conflict::Update(Vec::with_capacity(1)));
+        }
+
+        match &mut self.conflict {
+            Some(Conflict::Update(sets)) => sets,
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn build(self, variant: Variant) -> Result<(String, Vec<Parameter>), Error> {
+        if self.columns.is_empty() {
+            return Err(Error::Incomplete("insert does not have any columns"));
+        }
+
+        if self.rows.is_empty() {
+            return Err(Error::Incomplete("insert does not have any rows"));
+        }
+
+        if let Some(row) = self.rows.iter().find(|row| row.len() != self.columns.len()) {
+            return Err(Error::RowLength {
+                expected: self.columns.len(),
+                found: row.len(),
+            });
+        }
+
+        let mut buff = String::with_capacity(256);
+        let mut parameters = Vec::new();
+
+        write!(
+            &mut buff,
+            "INSERT INTO {} ({}) VALUES",
+            self.table,
+            self.columns.join(", ")
+        )?;
+
+        let len = self.rows.len() - 1;
+
+        for (i, row) in self.rows.into_iter().enumerate() {
+            write!(&mut buff, " (")?;
+
+            let row_len = row.len() - 1;
+
+            for (j, value) in row.into_iter().enumerate() {
+                value.render(variant, &mut buff, &mut parameters)?;
+
+                if j != row_len {
+                    write!(&mut buff, ", ")?;
+                }
+            }
+
+            write!(&mut buff, ")")?;
+
+            if i != len {
+                write!(&mut buff, ",")?;
+            }
+        }
+
+        if let Some(conflict) = self.conflict {
+            let target = self.target.unwrap_or_default();
+
+            write!(&mut buff, " ON CONFLICT")?;
+
+            if !target.is_empty() {
+                write!(&mut buff, " ({})", target.join(", "))?;
+            }
+
+            let sets = match conflict {
+                Conflict::Nothing => Vec::new(),
+                Conflict::Excluded => self
+                    .columns
+                    .iter()
+                    .filter(|column| !target.contains(*column))
+                    .map(|column| (*column, None))
+                    .collect(),
+                Conflict::Update(sets) => sets,
+            };
+
+            if sets.is_empty() {
+                write!(&mut buff, " DO NOTHING")?;
+            } else {
+                write!(&mut buff, " DO UPDATE SET")?;
+
+                let len = sets.len() - 1;
+
+                for (i, (column, value)) in sets.into_iter().enumerate() {
+                    write!(&mut buff, " {} = ", column)?;
+
+                    match value {
+                        Some(value) => value.render(variant, &mut buff, &mut parameters)?,
+                        None => write!(&mut buff, "excluded.{}", column)?,
+                    }
+
+                    if i != len {
+                        write!(&mut buff, ",")?;
+                    }
+                }
+            }
+        }
+
+        render_returning(self.returning, &mut buff)?;
+
+        write!(&mut buff, ";")?;
+
+        buff.shrink_to_fit();
+
+        Ok((buff, parameters))
+    }
+}
+
+#[allow(clippy::needless_lifetimes)]
+pub fn insert<'q>(table: &'q str) -> Insert<'q> {
+    Insert {
+        table,
+
+        columns: Vec::new(),
+        rows: Vec::with_capacity(1),
+
+        target: None,
+        conflict: None,
+
+        returning: None,
+    }
+}
+
+/// Inserts the rows, updating every other column of a row that conflicts on
+/// the `target` columns.
+#[allow(clippy::needless_lifetimes)]
+pub fn upsert<'q>(table: &'q str, target: &[&'q str]) -> Insert<'q> {
+    Insert {
+        target: Some(target.to_vec()),
+        conflict: Some(Conflict::Excluded),
+
+        ..insert(table)
+    }
+}
+
+#[cfg(test)]
+mod test_super {
+    use crate::{prelude::*, Error};
+
+    #[test]
+    fn test() {
+        let query = insert("Tag")
+            .columns(&["Id", "Name"])
+            .values(vec!["abc".to_string().into(), "tag".to_string().into()])
+            .values(vec!["def".to_string().into(), "CURRENT_TIMESTAMP".into()])
+            .returning(&["Id"]);
+
+        let (query, parameters) = query.build(PostgreSQL).unwrap();
+
+        assert_eq!(
+            "INSERT INTO Tag (Id, Name) VALUES ($1, $2), ($3, CURRENT_TIMESTAMP) RETURNING Id;",
+            query,
+        );
+
+        assert_eq!(
+            vec![
+                Parameter::String("abc".into()),
+                Parameter::String("tag".into()),
+                Parameter::String("def".into()),
+            ],
+            parameters,
+        );
+    }
+
+    #[test]
+    fn test_conflict() {
+        let query = insert("Settings")
+            .columns(&["Key", "Value"])
+            .values(vec!["key".to_string().into(), "value".to_string().into()])
+            .on_conflict(&["Key"])
+            .do_update(&["Value"])
+            .do_update_set("Updated", "CURRENT_TIMESTAMP");
+
+        let (query, _) = query.build(SQLite).unwrap();
+
+        assert_eq!(
+            "INSERT INTO Settings (Key, Value) VALUES (?, ?) ON CONFLICT (Key) DO UPDATE SET Value = excluded.Value, Updated = CURRENT_TIMESTAMP;",
+            query,
+        );
+
+        let query = insert("Tag")
+            .columns(&["Id"])
+            .values(vec![1.into()])
+            .on_conflict(&[]);
+
+        let (query, _) = query.build(PostgreSQL).unwrap();
+
+        assert_eq!(
+            "INSERT INTO Tag (Id) VALUES ($1) ON CONFLICT DO NOTHING;",
+            query,
+        );
+    }
+
+    #[test]
+    fn test_upsert() {
+        let query = upsert("Tag", &["Id"])
+            .columns(&["Id", "Name", "Updated"])
+            .values(vec![
+                "abc".to_string().into(),
+                "tag".to_string().into(),
+                "CURRENT_TIMESTAMP".into(),
+            ])
+            .returning(&["Id", "Name"]);
+
+        let (query, parameters) = query.build(PostgreSQL).unwrap();
+
+        assert_eq!(
+            "INSERT INTO Tag (Id, Name, Updated) VALUES ($1, $2, CURRENT_TIMESTAMP) ON CONFLICT (Id) DO UPDATE SET Name = excluded.Name, Updated = excluded.Updated RETURNING Id, Name;",
+            query,
+        );
+
+        assert_eq!(2, parameters.len());
+    }
+
+    #[test]
+    fn test_empty() {
+        assert!(matches!(
+            insert("Tag").values(vec![1.into()]).build(SQLite),
+            Err(Error::Incomplete(_)),
+        ));
+
+        assert!(matches!(
+            insert("Tag").columns(&["Id"]).build(SQLite),
+            Err(Error::Incomplete(_)),
+        ));
+
+        assert!(matches!(
+            insert("Tag")
+                .columns(&["Id", "Name"])
+                .values(vec![1.into()])
+                .build(SQLite),
+            Err(Error::RowLength {
+                expected: 2,
+                found: 1
+            }),
+        ));
+    }
+
+    #[test]
+    fn test_upsert_nothing_to_update() {
+        let query = upsert("StoryTag", &["StoryId", "TagId"])
+            .columns(&["StoryId", "TagId"])
+            .values(vec!["abc".to_string().into(), "def".to_string().into()]);
+
+        let (query, _) = query.build(SQLite).unwrap();
+
+        assert_eq!(
+            "INSERT INTO StoryTag (StoryId, TagId) VALUES (?, ?) ON CONFLICT (StoryId, TagId) DO NOTHING;",
+            query,
+        );
+    }
+}
@@ -1,27 +1,88 @@
 // TODO:
 //   - SQL functions for values
 
+mod compound;
+mod delete;
+mod insert;
 mod select;
-
-use std::fmt;
-
-pub use crate::select::{select, Select};
+mod update;
+mod window;
+mod with;
+
+use std::fmt::{self, Write as _};
+
+pub use crate::{
+    compound::{Compound, Query},
+    delete::{delete, Delete},
+    insert::{insert, upsert, Insert},
+    select::{select, Select},
+    update::{update, Update},
+    window::{over, Window},
+    with::{with, with_recursive, With},
+};
 
 pub mod prelude {
     pub use crate::{
-        select, Alias as _, ConditionPredicate as _,
+        delete, insert, over, select, update, upsert, with, with_recursive, Alias as _,
+        ConditionPredicate as _,
         Order::{Asc, Desc},
         Parameter, Value,
         Variant::{PostgreSQL, SQLite},
     };
 }
 
+/// Why a query could not be built.
+#[derive(Debug)]
+pub enum Error {
+    /// The query is missing a part it needs, such as the columns of an
+    /// insert.
+    Incomplete(&'static str),
+    /// An insert row does not have a value for every column.
+    RowLength {
+        expected: usize,
+        found: usize,
+    },
+    Fmt(fmt::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Incomplete(reason) => write!(f, "SQL query is incomplete: {}", reason),
+            Error::RowLength { expected, found } => write!(
+                f,
+                "SQL insert row has {} values but there are {} columns",
+                found, expected
+            ),
+            Error::Fmt(err) => write!(f, "Unable to write SQL query: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<fmt::Error> for Error {
+    fn from(err: fmt::Error) -> Self {
+        Error::Fmt(err)
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub enum Variant {
     PostgreSQL,
     SQLite,
 }
 
+impl Variant {
+    /// Writes the placeholder of the parameter at `index`, starting from 1.
+    fn placeholder(self, buff: &mut String, index: usize) -> fmt::Result {
+        match self {
+            Variant::PostgreSQL => write!(buff, "${}", index),
+            Variant::SQLite => write!(buff, "?"),
+        }
+    }
+}
+
 pub trait Alias<'q> {
     fn alias(self, alias: &'q str) -> (Self, &'q str)
     where
@@ -56,6 +117,7 @@ macro_rules! impl_predicates {
                     left: self,
                     predicate: $predicate,
                     right: Some(right.into()),
+                    high: None,
                 }
             }
         )*
@@ -71,10 +133,15 @@ pub trait ConditionPredicate<'q> {
         is_lt,
         is_lt_eq,
         is_in,
-        is_bt,
         is_like,
     ]);
 
+    /// `BETWEEN low AND high`
+    fn is_bt<L, H>(self, low: L, high: H) -> Condition<'q>
+    where
+        L: Into<Value<'q>>,
+        H: Into<Value<'q>>;
+
     fn is_null(self) -> Condition<'q>;
 
     fn is_not_null(self) -> Condition<'q>;
@@ -89,15 +156,28 @@ impl<'q> ConditionPredicate<'q> for &'q str {
         is_lt => Predicate::Lt,
         is_lt_eq => Predicate::LtEq,
         is_in => Predicate::In,
-        is_bt => Predicate::Bt,
         is_like => Predicate::Like,
     ]);
 
+    fn is_bt<L, H>(self, low: L, high: H) -> Condition<'q>
+    where
+        L: Into<Value<'q>>,
+        H: Into<Value<'q>>,
+    {
+        Condition {
+            left: self,
+            predicate: Predicate::Bt,
+            right: Some(low.into()),
+            high: Some(high.into()),
+        }
+    }
+
     fn is_null(self) -> Condition<'q> {
         Condition {
             left: self,
             predicate: Predicate::Null,
             right: None,
+            high: None,
         }
     }
 
@@ -106,6 +186,7 @@ impl<'q> ConditionPredicate<'q> for &'q str {
             left: self,
             predicate: Predicate::NotNull,
             right: None,
+            high: None,
         }
     }
 }
@@ -121,6 +202,135 @@ pub struct Condition<'q> {
     left: &'q str,
     predicate: Predicate,
     right: Option<Value<'q>>,
+    /// The upper bound of a `BETWEEN`.
+    high: Option<Value<'q>>,
+}
+
+impl<'q> Condition<'q> {
+    fn render(
+        self,
+        variant: Variant,
+        buff: &mut String,
+        params: &mut Vec<Parameter>,
+    ) -> fmt::Result {
+        write!(buff, "{}", self.left)?;
+
+        let symbol = match self.predicate {
+            Predicate::Eq => "=",
+            Predicate::NotEq => "<>",
+            Predicate::Gt => ">",
+            Predicate::GtEq => ">=",
+            Predicate::Lt => "<",
+            Predicate::LtEq => "<=",
+            Predicate::Like => "LIKE",
+            Predicate::In => "IN",
+            Predicate::Bt => "BETWEEN",
+            Predicate::Null => return write!(buff, " IS NULL"),
+            Predicate::NotNull => return write!(buff, " IS NOT NULL"),
+        };
+
+        let right = self
+            .right
+            .expect("BUG: SQL condition does not have right side value");
+
+        write!(buff, " {} ", symbol)?;
+
+        match (self.predicate, right) {
+            (Predicate::In, right @ Value::Select(_)) => right.render(variant, buff, params),
+            (Predicate::In, right) => {
+                write!(buff, "(")?;
+
+                right.render(variant, buff, params)?;
+
+                write!(buff, ")")
+            }
+            (Predicate::Bt, low) => {
+                let high = self
+                    .high
+                    .expect("BUG: SQL `BETWEEN` condition does not have an upper bound");
+
+                low.render(variant, buff, params)?;
+
+                write!(buff, " AND ")?;
+
+                high.render(variant, buff, params)
+            }
+            (_, right) => right.render(variant, buff, params),
+        }
+    }
+}
+
+/// Writes the `WHERE` clause, joining every condition with `AND`.
+fn render_conditions(
+    conditions: Vec<Condition<'_>>,
+    variant: Variant,
+    buff: &mut String,
+    params: &mut Vec<Parameter>,
+) -> fmt::Result {
+    write!(buff, " WHERE")?;
+
+    let len = conditions.len() - 1;
+
+    for (i, condition) in conditions.into_iter().enumerate() {
+        write!(buff, " ")?;
+
+        condition.render(variant, buff, params)?;
+
+        if i != len {
+            write!(buff, " AND")?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the `ORDER BY` clause.
+fn render_order(orders: Vec<(&str, Order)>, buff: &mut String) -> fmt::Result {
+    write!(buff, " ORDER BY")?;
+
+    let len = orders.len() - 1;
+
+    for (i, (stmt, order)) in orders.into_iter().enumerate() {
+        write!(buff, " {} {}", stmt, order)?;
+
+        if i != len {
+            write!(buff, ",")?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the `LIMIT` and `OFFSET` clauses.
+fn render_limit(
+    limit: Option<Value<'_>>,
+    offset: Option<Value<'_>>,
+    variant: Variant,
+    buff: &mut String,
+    params: &mut Vec<Parameter>,
+) -> fmt::Result {
+    if let Some(limit) = limit {
+        write!(buff, " LIMIT ")?;
+
+        limit.render(variant, buff, params)?;
+    }
+
+    if let Some(offset) = offset {
+        write!(buff, " OFFSET ")?;
+
+        offset.render(variant, buff, params)?;
+    }
+
+    Ok(())
+}
+
+/// Writes the `RETURNING` clause, SQLite only supports it from 3.35.
+fn render_returning(returning: Option<Vec<&str>>, buff: &mut String) -> fmt::Result {
+    if let Some(fields) = returning {
+        write!(buff, " RETURNING {}", fields.join(", "))?;
+    }
+
+    Ok(())
 }
 
 #[derive(Debug)]
@@ -173,6 +383,15 @@ pub enum Table<'q> {
     Name(&'q str),
 }
 
+impl<'q> fmt::Display for Table<'q> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Table::Alias { table, alias } => write!(f, "{} {}", table, alias),
+            Table::Name(table) => write!(f, "{}", table),
+        }
+    }
+}
+
 impl<'q> From<&'q str> for Table<'q> {
     fn from(value: &'q str) -> Self {
         Table::Name(value)
@@ -192,6 +411,31 @@ pub enum Value<'q> {
     Parameter(Parameter),
 }
 
+impl<'q> Value<'q> {
+    fn render(
+        self,
+        variant: Variant,
+        buff: &mut String,
+        params: &mut Vec<Parameter>,
+    ) -> fmt::Result {
+        match self {
+            Value::Select(select) => {
+                write!(buff, "(")?;
+
+                select.render(variant, buff, params)?;
+
+                write!(buff, ")")
+            }
+            Value::Parameter(param) => {
+                params.push(param);
+
+                variant.placeholder(buff, params.len())
+            }
+            Value::Value(value) => write!(buff, "{}", value),
+        }
+    }
+}
+
 impl<'q> From<Select<'q>> for Value<'q> {
     fn from(value: Select<'q>) -> Self {
         Value::Select(Box::new(value))
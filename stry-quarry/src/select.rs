@@ -1,36 +1,18 @@
 use {
-    crate::{Condition, Join, JoinClause, Order, Parameter, Predicate, Table, Value, Variant},
+    crate::{
+        render_conditions, render_limit, render_order, Compound, Condition, Error, Join,
+        JoinClause, Order, Parameter, Table, Value, Variant, Window,
+    },
     fnv::FnvHashMap,
     std::fmt::{self, Write as _},
 };
 
-macro_rules! right_condition {
-    ($con:expr, $buf:ident, $params:ident, $var:ident, $sym:expr) => {
-        match $con {
-            Some(Value::Select(selector)) => {
-                let (query, params) = selector.build($var)?;
-
-                $params.extend(params);
-
-                write!(&mut $buf, "({})", query)?;
-            }
-            Some(Value::Parameter(param)) => {
-                $params.push(param);
-
-                write!(&mut $buf, concat!(" ", $sym, " ?"))?;
-            }
-            Some(Value::Value(value)) => write!(&mut $buf, concat!(" ", $sym, " {}"), value)?,
-            None => panic!("BUG: SQL condition does not have right side value"),
-        }
-    };
-}
-
 #[derive(Debug)]
 pub struct Select<'q> {
     tables: Vec<Table<'q>>,
 
     aliases: Option<FnvHashMap<&'q str, &'q str>>,
-    fields: Option<Vec<&'q str>>,
+    fields: Option<Vec<Field<'q>>>,
 
     order: Option<Vec<(&'q str, Order)>>,
 
@@ -48,6 +30,21 @@ pub struct Select<'q> {
     parameters: Option<Vec<Parameter>>,
 }
 
+#[derive(Debug)]
+enum Field<'q> {
+    Name(&'q str),
+    Window(Window<'q>),
+}
+
+impl<'q> fmt::Display for Field<'q> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Field::Name(name) => write!(f, "{}", name),
+            Field::Window(window) => write!(f, "{}", window),
+        }
+    }
+}
+
 impl<'q> Select<'q> {
     pub fn table<T>(mut self, table: T) -> Select<'q>
     where
@@ -70,7 +67,7 @@ impl<'q> Select<'q> {
     pub fn field(mut self, field: &'q str) -> Select<'q> {
         self.fields
             .get_or_insert_with(|| Vec::with_capacity(1))
-            .push(field);
+            .push(Field::Name(field));
 
         self
     }
@@ -81,12 +78,21 @@ impl<'q> Select<'q> {
             .get_or_insert_with(|| Vec::with_capacity(fields.len()));
 
         for field in fields {
-            items.push(field);
+            items.push(Field::Name(field));
         }
 
         self
     }
 
+    /// A field of a window function, see [`over`](crate::over).
+    pub fn window(mut self, window: Window<'q>) -> Select<'q> {
+        self.fields
+            .get_or_insert_with(|| Vec::with_capacity(1))
+            .push(Field::Window(window));
+
+        self
+    }
+
     /// `ORDER BY`
     pub fn order_by(mut self, stmt: &'q str, order: Order) -> Select<'q> {
         self.order
@@ -178,168 +184,95 @@ impl<'q> Select<'q> {
         self
     }
 
-    pub fn build(self, variant: Variant) -> Result<(String, Vec<Parameter>), fmt::Error> {
+    /// `UNION`
+    pub fn union(self, other: Select<'q>) -> Compound<'q> {
+        Compound::new(self).union(other)
+    }
+
+    /// `UNION ALL`
+    pub fn union_all(self, other: Select<'q>) -> Compound<'q> {
+        Compound::new(self).union_all(other)
+    }
+
+    /// `INTERSECT`
+    pub fn intersect(self, other: Select<'q>) -> Compound<'q> {
+        Compound::new(self).intersect(other)
+    }
+
+    /// `EXCEPT`
+    pub fn except(self, other: Select<'q>) -> Compound<'q> {
+        Compound::new(self).except(other)
+    }
+
+    pub fn build(self, variant: Variant) -> Result<(String, Vec<Parameter>), Error> {
         let mut buff = String::with_capacity(512);
         let mut parameters = Vec::new();
 
-        write!(&mut buff, "SELECT")?;
+        self.render(variant, &mut buff, &mut parameters)?;
+
+        write!(&mut buff, ";")?;
+
+        buff.shrink_to_fit();
+
+        Ok((buff, parameters))
+    }
+
+    /// Writes the query without the closing `;`, so it can be used inside
+    /// other queries.
+    pub(crate) fn render(
+        self,
+        variant: Variant,
+        buff: &mut String,
+        parameters: &mut Vec<Parameter>,
+    ) -> fmt::Result {
+        write!(buff, "SELECT")?;
 
         if let Some(fields) = self.fields {
             let len = fields.len() - 1;
 
             for (i, field) in fields.into_iter().enumerate() {
-                write!(&mut buff, " {}", field)?;
+                write!(buff, " {}", field)?;
 
                 if i != len {
-                    write!(&mut buff, ",")?;
+                    write!(buff, ",")?;
                 }
             }
         }
 
-        for table in self.tables {
-            match table {
-                Table::Alias { table, alias } => {
-                    write!(&mut buff, " FROM {} {}", table, alias)?;
-                }
-                Table::Name(table) => {
-                    write!(&mut buff, " FROM {}", table)?;
-                }
+        for (i, table) in self.tables.into_iter().enumerate() {
+            if i == 0 {
+                write!(buff, " FROM {}", table)?;
+            } else {
+                write!(buff, ", {}", table)?;
             }
         }
 
         if let Some(joins) = self.joins {
             for join in joins {
                 match join.kind {
-                    Join::Inner => write!(&mut buff, " INNER JOIN")?,
-                    Join::Left => write!(&mut buff, " LEFT JOIN")?,
+                    Join::Inner => write!(buff, " INNER JOIN")?,
+                    Join::Left => write!(buff, " LEFT JOIN")?,
                 }
 
-                match join.table {
-                    Table::Alias { table, alias } => {
-                        write!(&mut buff, " {} {}", table, alias)?;
-                    }
-                    Table::Name(table) => {
-                        write!(&mut buff, " {}", table)?;
-                    }
-                }
-
-                write!(&mut buff, " ON {} = {}", join.left, join.right)?;
+                write!(buff, " {} ON {} = {}", join.table, join.left, join.right)?;
             }
         }
 
         if let Some(conditions) = self.conditions {
-            write!(&mut buff, " WHERE")?;
-
-            let len = conditions.len() - 1;
-
-            for (i, condition) in conditions.into_iter().enumerate() {
-                write!(&mut buff, " {}", condition.left)?;
-
-                match condition.predicate {
-                    Predicate::Eq => {
-                        right_condition!(condition.right, buff, parameters, variant, "=");
-                    }
-                    Predicate::NotEq => {
-                        right_condition!(condition.right, buff, parameters, variant, "<>");
-                    }
-                    Predicate::Gt => {
-                        right_condition!(condition.right, buff, parameters, variant, ">");
-                    }
-                    Predicate::GtEq => {
-                        right_condition!(condition.right, buff, parameters, variant, ">=");
-                    }
-                    Predicate::Lt => {
-                        right_condition!(condition.right, buff, parameters, variant, "<");
-                    }
-                    Predicate::LtEq => {
-                        right_condition!(condition.right, buff, parameters, variant, "<=");
-                    }
-                    Predicate::In => match condition.right {
-                        Some(Value::Select(_)) => todo!("TODO: Allow `IN` to be a sub-query"),
-                        Some(Value::Parameter(param)) => {
-                            parameters.push(param);
-
-                            write!(&mut buff, " IN (?)",)?;
-                        }
-                        Some(Value::Value(value)) => write!(&mut buff, " IN ({})", value)?,
-                        None => panic!("BUG: SQL condition does not have right side value"),
-                    },
-                    Predicate::Bt => match condition.right {
-                        Some(Value::Select(_)) => todo!("TODO: Allow `BETWEEN` to be a sub-query"),
-                        Some(Value::Parameter(param)) => {
-                            parameters.push(param);
-
-                            write!(&mut buff, " BETWEEN ? AND ?",)?;
-                        }
-                        Some(Value::Value(value)) => write!(&mut buff, " BETWEEN {}", value)?,
-                        None => panic!("BUG: SQL condition does not have right side value"),
-                    },
-                    Predicate::Like => {
-                        right_condition!(condition.right, buff, parameters, variant, "LIKE");
-                    }
-                    Predicate::Null => {
-                        write!(&mut buff, " IS NULL")?;
-                    }
-                    Predicate::NotNull => {
-                        write!(&mut buff, " IS NOT NULL")?;
-                    }
-                }
-
-                if i != len {
-                    write!(&mut buff, " AND")?;
-                }
-            }
+            render_conditions(conditions, variant, buff, parameters)?;
         }
 
         if let Some(groupings) = self.groupings {
             for group in groupings {
-                write!(&mut buff, " GROUP BY {}", group)?;
+                write!(buff, " GROUP BY {}", group)?;
             }
         }
 
         if let Some(orders) = self.order {
-            write!(&mut buff, " ORDER BY")?;
-
-            let len = orders.len() - 1;
-
-            for (i, (stmt, order)) in orders.into_iter().enumerate() {
-                write!(&mut buff, " {} {}", stmt, order)?;
-
-                if i != len {
-                    write!(&mut buff, ",")?;
-                }
-            }
+            render_order(orders, buff)?;
         }
 
-        if let Some(limit) = self.limit {
-            match limit {
-                Value::Select(_) => todo!("TODO: Allow `LIMIT` to be a sub-query"),
-                Value::Parameter(param) => {
-                    parameters.push(param);
-
-                    write!(&mut buff, " LIMIT ?")?;
-                }
-                Value::Value(value) => write!(&mut buff, " LIMIT {}", value)?,
-            }
-        }
-
-        if let Some(offset) = self.offset {
-            match offset {
-                Value::Select(_) => todo!("TODO: Allow `OFFSET` to be a sub-query"),
-                Value::Parameter(param) => {
-                    parameters.push(param);
-
-                    write!(&mut buff, " OFFSET ?")?;
-                }
-                Value::Value(value) => write!(&mut buff, " OFFSET {}", value)?,
-            }
-        }
-
-        write!(&mut buff, ";")?;
-
-        buff.shrink_to_fit();
-
-        Ok((buff, parameters))
+        render_limit(self.limit, self.offset, variant, buff, parameters)
     }
 }
 
@@ -396,4 +329,35 @@ mod test_super {
             parameters,
         );
     }
+
+    #[test]
+    fn test_between() {
+        let query = select("Story")
+            .field("Id")
+            .filter("Words".is_bt(1000, 5000))
+            .filter("Updated".is_bt(select("Story").field("MIN(Updated)"), "CURRENT_TIMESTAMP"));
+
+        let (sqlite, parameters) = query.build(SQLite).unwrap();
+
+        assert_eq!(
+            "SELECT Id FROM Story WHERE Words BETWEEN ? AND ? AND Updated BETWEEN (SELECT MIN(Updated) FROM Story) AND CURRENT_TIMESTAMP;",
+            sqlite,
+        );
+
+        assert_eq!(
+            vec![Parameter::Signed32(1000), Parameter::Signed32(5000)],
+            parameters,
+        );
+
+        let query = select("Story")
+            .field("Id")
+            .filter("Words".is_bt(1000, 5000));
+
+        let (postgres, _) = query.build(PostgreSQL).unwrap();
+
+        assert_eq!(
+            "SELECT Id FROM Story WHERE Words BETWEEN $1 AND $2;",
+            postgres,
+        );
+    }
 }
@@ -0,0 +1,158 @@
+use {
+    crate::{render_conditions, render_returning, Condition, Error, Parameter, Value, Variant},
+    std::fmt::Write as _,
+};
+
+#[derive(Debug)]
+pub struct Update<'q> {
+    table: &'q str,
+
+    sets: Vec<(&'q str, Value<'q>)>,
+
+    conditions: Option<Vec<Condition<'q>>>,
+
+    returning: Option<Vec<&'q str>>,
+}
+
+impl<'q> Update<'q> {
+    /// `SET`
+    pub fn set<V>(mut self, column: &'q str, value: V) -> Update<'q>
+    where
+        V: Into<Value<'q>>,
+    {
+        self.sets.push((column, value.into()));
+
+        self
+    }
+
+    /// `WHERE`
+    pub fn filter<C>(mut self, condition: C) -> Update<'q>
+    where
+        C: Into<Condition<'q>>,
+    {
+        self.conditions
+            .get_or_insert_with(|| Vec::with_capacity(1))
+            .push(condition.into());
+
+        self
+    }
+
+    /// `RETURNING`
+    pub fn returning(mut self, fields: &[&'q str]) -> Update<'q> {
+        self.returning
+            .get_or_insert_with(|| Vec::with_capacity(fields.len()))
+            .extend(fields);
+
+        self
+    }
+
+    pub fn build(self, variant: Variant) -> Result<(String, Vec<Parameter>), Error> {
+        if self.sets.is_empty() {
+            return Err(Error::Incomplete("update does not set any columns"));
+        }
+
+        let mut buff = String::with_capacity(256);
+        let mut parameters = Vec::new();
+
+        write!(&mut buff, "UPDATE {} SET", self.table)?;
+
+        let len = self.sets.len() - 1;
+
+        for (i, (column, value)) in self.sets.into_iter().enumerate() {
+            write!(&mut buff, " {} = ", column)?;
+
+            value.render(variant, &mut buff, &mut parameters)?;
+
+            if i != len {
+                write!(&mut buff, ",")?;
+            }
+        }
+
+        if let Some(conditions) = self.conditions {
+            render_conditions(conditions, variant, &mut buff, &mut parameters)?;
+        }
+
+        render_returning(self.returning, &mut buff)?;
+
+        write!(&mut buff, ";")?;
+
+        buff.shrink_to_fit();
+
+        Ok((buff, parameters))
+    }
+}
+
+#[allow(clippy::needless_lifetimes)]
+pub fn update<'q>(table: &'q str) -> Update<'q> {
+    Update {
+        table,
+
+        sets: Vec::with_capacity(1),
+
+        conditions: None,
+
+        returning: None,
+    }
+}
+
+#[cfg(test)]
+mod test_super {
+    use crate::{prelude::*, Error};
+
+    #[test]
+    fn test() {
+        let query = update("Worker")
+            .set("TaskId", "NULL")
+            .set("Updated", "CURRENT_TIMESTAMP")
+            .set("Name", "worker".to_string())
+            .filter("Id".is_eq("abc".to_string()))
+            .filter("TaskId".is_not_null())
+            .returning(&["Id"]);
+
+        let (sqlite, parameters) = query.build(SQLite).unwrap();
+
+        assert_eq!(
+            "UPDATE Worker SET TaskId = NULL, Updated = CURRENT_TIMESTAMP, Name = ? WHERE Id = ? AND TaskId IS NOT NULL RETURNING Id;",
+            sqlite,
+        );
+
+        assert_eq!(
+            vec![
+                Parameter::String("worker".into()),
+                Parameter::String("abc".into()),
+            ],
+            parameters,
+        );
+    }
+
+    #[test]
+    fn test_sub_query() {
+        let query = update("Story")
+            .set("Updated", "CURRENT_TIMESTAMP")
+            .filter(
+                "Id".is_in(
+                    select("StoryTag")
+                        .field("StoryId")
+                        .filter("TagId".is_eq("abc".to_string())),
+                ),
+            )
+            .filter("Rating".is_eq("teen".to_string()));
+
+        let (postgres, parameters) = query.build(PostgreSQL).unwrap();
+
+        assert_eq!(
+            "UPDATE Story SET Updated = CURRENT_TIMESTAMP WHERE Id IN (SELECT StoryId FROM StoryTag WHERE TagId = $1) AND Rating = $2;",
+            postgres,
+        );
+
+        assert_eq!(2, parameters.len());
+    }
+
+    #[test]
+    fn test_empty() {
+        assert!(matches!(
+            update("Story").filter("Id".is_eq(1)).build(SQLite),
+            Err(Error::Incomplete(_)),
+        ));
+    }
+}
@@ -0,0 +1,142 @@
+use {crate::Order, std::fmt};
+
+/// A window function field, `function OVER (...)`.
+#[derive(Debug)]
+pub struct Window<'q> {
+    function: &'q str,
+
+    partitions: Option<Vec<&'q str>>,
+    order: Option<Vec<(&'q str, Order)>>,
+    frame: Option<&'q str>,
+
+    alias: Option<&'q str>,
+}
+
+impl<'q> Window<'q> {
+    /// `PARTITION BY`
+    pub fn partition_by(mut self, stmt: &'q str) -> Window<'q> {
+        self.partitions
+            .get_or_insert_with(|| Vec::with_capacity(1))
+            .push(stmt);
+
+        self
+    }
+
+    /// `ORDER BY`
+    pub fn order_by(mut self, stmt: &'q str, order: Order) -> Window<'q> {
+        self.order
+            .get_or_insert_with(|| Vec::with_capacity(1))
+            .push((stmt, order));
+
+        self
+    }
+
+    /// The frame of the window, such as
+    /// `ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW`.
+    pub fn frame(mut self, frame: &'q str) -> Window<'q> {
+        self.frame = Some(frame);
+
+        self
+    }
+
+    /// `AS`
+    pub fn alias(mut self, alias: &'q str) -> Window<'q> {
+        self.alias = Some(alias);
+
+        self
+    }
+}
+
+impl<'q> fmt::Display for Window<'q> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} OVER (", self.function)?;
+
+        let mut clauses = Vec::with_capacity(3);
+
+        if let Some(partitions) = &self.partitions {
+            clauses.push(format!("PARTITION BY {}", partitions.join(", ")));
+        }
+
+        if let Some(orders) = &self.order {
+            let orders: Vec<String> = orders
+                .iter()
+                .map(|(stmt, order)| format!("{} {}", stmt, order))
+                .collect();
+
+            clauses.push(format!("ORDER BY {}", orders.join(", ")));
+        }
+
+        if let Some(frame) = self.frame {
+            clauses.push(frame.to_string());
+        }
+
+        write!(f, "{})", clauses.join(" "))?;
+
+        if let Some(alias) = self.alias {
+            write!(f, " AS {}", alias)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Creates a window function field, such as `ROW_NUMBER()` or
+/// `SUM(Words)`, to be added with [`Select::window`](crate::Select::window).
+#[allow(clippy::needless_lifetimes)]
+pub fn over<'q>(function: &'q str) -> Window<'q> {
+    Window {
+        function,
+
+        partitions: None,
+        order: None,
+        frame: None,
+
+        alias: None,
+    }
+}
+
+#[cfg(test)]
+mod test_super {
+    use crate::{prelude::*, Select};
+
+    fn chapters() -> Select<'static> {
+        select("StoryChapter".alias("SC"))
+            .field("SC.StoryId")
+            .window(
+                over("ROW_NUMBER()")
+                    .partition_by("SC.StoryId")
+                    .order_by("SC.Place", Asc)
+                    .alias("Number"),
+            )
+            .window(
+                over("SUM(C.Words)")
+                    .partition_by("SC.StoryId")
+                    .order_by("SC.Place", Asc)
+                    .frame("ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW")
+                    .alias("Total"),
+            )
+            .window(over("COUNT(*)").alias("Chapters"))
+            .left_join("Chapter".alias("C"), "C.Id", "SC.ChapterId")
+            .filter("SC.StoryId".is_eq("abc".to_string()))
+    }
+
+    #[test]
+    fn test_sqlite() {
+        let (query, _) = chapters().build(SQLite).unwrap();
+
+        assert_eq!(
+            "SELECT SC.StoryId, ROW_NUMBER() OVER (PARTITION BY SC.StoryId ORDER BY SC.Place ASC) AS Number, SUM(C.Words) OVER (PARTITION BY SC.StoryId ORDER BY SC.Place ASC ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW) AS Total, COUNT(*) OVER () AS Chapters FROM StoryChapter SC LEFT JOIN Chapter C ON C.Id = SC.ChapterId WHERE SC.StoryId = ?;",
+            query,
+        );
+    }
+
+    #[test]
+    fn test_postgres() {
+        let (query, _) = chapters().build(PostgreSQL).unwrap();
+
+        assert_eq!(
+            "SELECT SC.StoryId, ROW_NUMBER() OVER (PARTITION BY SC.StoryId ORDER BY SC.Place ASC) AS Number, SUM(C.Words) OVER (PARTITION BY SC.StoryId ORDER BY SC.Place ASC ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW) AS Total, COUNT(*) OVER () AS Chapters FROM StoryChapter SC LEFT JOIN Chapter C ON C.Id = SC.ChapterId WHERE SC.StoryId = $1;",
+            query,
+        );
+    }
+}
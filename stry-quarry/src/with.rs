@@ -0,0 +1,239 @@
+use {
+    crate::{Error, Parameter, Query, Variant},
+    std::fmt::Write as _,
+};
+
+#[derive(Debug)]
+struct Cte<'q> {
+    name: &'q str,
+    columns: Option<Vec<&'q str>>,
+    query: Query<'q>,
+}
+
+/// A query that uses common table expressions, `WITH name AS (...)`.
+#[derive(Debug)]
+pub struct With<'q> {
+    recursive: bool,
+
+    ctes: Vec<Cte<'q>>,
+
+    query: Option<Query<'q>>,
+}
+
+impl<'q> With<'q> {
+    /// Adds another table expression, which can use the ones before it.
+    pub fn with<Q>(mut self, name: &'q str, query: Q) -> With<'q>
+    where
+        Q: Into<Query<'q>>,
+    {
+        self.ctes.push(Cte {
+            name,
+            columns: None,
+            query: query.into(),
+        });
+
+        self
+    }
+
+    /// Names the columns of the last table expression added.
+    pub fn columns(mut self, columns: &[&'q str]) -> With<'q> {
+        if let Some(cte) = self.ctes.last_mut() {
+            cte.columns = Some(columns.to_vec());
+        }
+
+        self
+    }
+
+    /// The query that uses the table expressions.
+    pub fn query<Q>(mut self, query: Q) -> With<'q>
+    where
+        Q: Into<Query<'q>>,
+    {
+        self.query = Some(query.into());
+
+        self
+    }
+
+    pub fn build(self, variant: Variant) -> Result<(String, Vec<Parameter>), Error> {
+        let query = self.query.ok_or(Error::Incomplete(
+            "common table expression does not have a query",
+        ))?;
+
+        let mut buff = String::with_capacity(1024);
+        let mut parameters = Vec::new();
+
+        write!(&mut buff, "WITH")?;
+
+        if self.recursive {
+            write!(&mut buff, " RECURSIVE")?;
+        }
+
+        let len = self.ctes.len() - 1;
+
+        for (i, cte) in self.ctes.into_iter().enumerate() {
+            write!(&mut buff, " {}", cte.name)?;
+
+            if let Some(columns) = cte.columns {
+                write!(&mut buff, "({})", columns.join(", "))?;
+            }
+
+            write!(&mut buff, " AS (")?;
+
+            cte.query.render(variant, &mut buff, &mut parameters)?;
+
+            write!(&mut buff, ")")?;
+
+            if i != len {
+                write!(&mut buff, ",")?;
+            }
+        }
+
+        write!(&mut buff, " ")?;
+
+        query.render(variant, &mut buff, &mut parameters)?;
+
+        write!(&mut buff, ";")?;
+
+        buff.shrink_to_fit();
+
+        Ok((buff, parameters))
+    }
+}
+
+pub fn with<'q, Q>(name: &'q str, query: Q) -> With<'q>
+where
+    Q: Into<Query<'q>>,
+{
+    With {
+        recursive: false,
+
+        ctes: vec![Cte {
+            name,
+            columns: None,
+            query: query.into(),
+        }],
+
+        query: None,
+    }
+}
+
+/// Creates a `WITH RECURSIVE` query, where the table expressions can use
+/// themselves, usually as a `UNION ALL` of a starting select and a select
+/// that joins the table expression.
+pub fn with_recursive<'q, Q>(name: &'q str, query: Q) -> With<'q>
+where
+    Q: Into<Query<'q>>,
+{
+    With {
+        recursive: true,
+
+        ..with(name, query)
+    }
+}
+
+#[cfg(test)]
+mod test_super {
+    use crate::{prelude::*, Error, With};
+
+    fn stories() -> With<'static> {
+        with(
+            "Ids",
+            select("StoryTag")
+                .field("StoryId")
+                .filter("TagId".is_eq("abc".to_string())),
+        )
+        .query(
+            select("Story")
+                .fields(&["Id", "Name"])
+                .filter("Id".is_in(select("Ids").field("StoryId")))
+                .limit(10),
+        )
+    }
+
+    #[test]
+    fn test_sqlite() {
+        let (query, parameters) = stories().build(SQLite).unwrap();
+
+        assert_eq!(
+            "WITH Ids AS (SELECT StoryId FROM StoryTag WHERE TagId = ?) SELECT Id, Name FROM Story WHERE Id IN (SELECT StoryId FROM Ids) LIMIT ?;",
+            query,
+        );
+
+        assert_eq!(
+            vec![Parameter::String("abc".into()), Parameter::Signed32(10)],
+            parameters,
+        );
+    }
+
+    #[test]
+    fn test_postgres() {
+        let (query, _) = stories().build(PostgreSQL).unwrap();
+
+        assert_eq!(
+            "WITH Ids AS (SELECT StoryId FROM StoryTag WHERE TagId = $1) SELECT Id, Name FROM Story WHERE Id IN (SELECT StoryId FROM Ids) LIMIT $2;",
+            query,
+        );
+    }
+
+    fn tree() -> With<'static> {
+        with_recursive(
+            "Tree",
+            select("Tag")
+                .fields(&["Id", "ParentId", "0"])
+                .filter("Id".is_eq("abc".to_string()))
+                .union_all(
+                    select("Tag".alias("T"))
+                        .table("Tree")
+                        .fields(&["T.Id", "T.ParentId", "Tree.Depth + 1"])
+                        .filter("T.ParentId".is_eq("Tree.Id"))
+                        .filter("Tree.Depth".is_lt(5)),
+                ),
+        )
+        .columns(&["Id", "ParentId", "Depth"])
+        .with(
+            "Counts",
+            select("StoryTag")
+                .fields(&["TagId", "COUNT(StoryId) AS Count"])
+                .group_by("TagId"),
+        )
+        .query(
+            select("Tree".alias("TR"))
+                .fields(&["TR.Id", "TR.Depth", "C.Count"])
+                .left_join("Counts".alias("C"), "C.TagId", "TR.Id")
+                .order_by("TR.Depth", Asc),
+        )
+    }
+
+    #[test]
+    fn test_recursive_sqlite() {
+        let (query, parameters) = tree().build(SQLite).unwrap();
+
+        assert_eq!(
+            "WITH RECURSIVE Tree(Id, ParentId, Depth) AS (SELECT Id, ParentId, 0 FROM Tag WHERE Id = ? UNION ALL SELECT T.Id, T.ParentId, Tree.Depth + 1 FROM Tag T, Tree WHERE T.ParentId = Tree.Id AND Tree.Depth < ?), Counts AS (SELECT TagId, COUNT(StoryId) AS Count FROM StoryTag GROUP BY TagId) SELECT TR.Id, TR.Depth, C.Count FROM Tree TR LEFT JOIN Counts C ON C.TagId = TR.Id ORDER BY TR.Depth ASC;",
+            query,
+        );
+
+        assert_eq!(
+            vec![Parameter::String("abc".into()), Parameter::Signed32(5)],
+            parameters,
+        );
+    }
+
+    #[test]
+    fn test_recursive_postgres() {
+        let (query, _) = tree().build(PostgreSQL).unwrap();
+
+        assert_eq!(
+            "WITH RECURSIVE Tree(Id, ParentId, Depth) AS (SELECT Id, ParentId, 0 FROM Tag WHERE Id = $1 UNION ALL SELECT T.Id, T.ParentId, Tree.Depth + 1 FROM Tag T, Tree WHERE T.ParentId = Tree.Id AND Tree.Depth < $2), Counts AS (SELECT TagId, COUNT(StoryId) AS Count FROM StoryTag GROUP BY TagId) SELECT TR.Id, TR.Depth, C.Count FROM Tree TR LEFT JOIN Counts C ON C.TagId = TR.Id ORDER BY TR.Depth ASC;",
+            query,
+        );
+    }
+
+    #[test]
+    fn test_missing_query() {
+        assert!(matches!(
+            with("Ids", select("Story").field("Id")).build(SQLite),
+            Err(Error::Incomplete(_)),
+        ));
+    }
+}
@@ -10,38 +10,76 @@ use {
     std::{cell::Ref, collections::HashMap, sync::Arc},
 };
 
+/// Why a story or one of its pages could not be scraped.
+///
+/// Workers use [`ScrapeError::is_retryable`] to decide if a task should be
+/// tried again later or marked as failed.
+#[derive(Debug, thiserror::Error)]
+pub enum ScrapeError {
+    #[error("Story not found: {url}")]
+    NotFound { url: String },
+    #[error("Story is locked or restricted: {url}")]
+    Locked { url: String },
+    #[error("Story has been deleted: {url}")]
+    Deleted { url: String },
+    #[error("Rate limited by {host}")]
+    RateLimited { host: String },
+    #[error("Sector element for site {site} not found: {selector}")]
+    LayoutChanged { site: String, selector: String },
+    #[error("Request for {url} failed: {reason}")]
+    Network { url: String, reason: String },
+}
+
+impl ScrapeError {
+    pub fn layout_changed(site: impl Into<String>, selector: impl Into<String>) -> Self {
+        ScrapeError::LayoutChanged {
+            site: site.into(),
+            selector: selector.into(),
+        }
+    }
+
+    /// If trying again later could succeed, only rate limits and network
+    /// errors are temporary.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            ScrapeError::RateLimited { .. } | ScrapeError::Network { .. }
+        )
+    }
+}
+
 pub fn inner_html(
     html: &Document,
     selector: &'static str,
     name: &'static str,
-) -> anyhow::Result<String> {
+) -> Result<String, ScrapeError> {
     html.select(selector)
         .first()
         .and_then(|sd| sd.inner_html())
-        .ok_or_else(|| anyhow::anyhow!("Sector element for site {} not found: {}", name, selector))
+        .ok_or_else(|| ScrapeError::layout_changed(name, selector))
 }
 
 pub fn string(
     html: &Document,
     selector: &'static str,
     name: &'static str,
-) -> anyhow::Result<String> {
+) -> Result<String, ScrapeError> {
     html.select(selector)
         .first()
         .and_then(|sd| sd.text())
-        .ok_or_else(|| anyhow::anyhow!("Sector element for site {} not found: {}", name, selector))
+        .ok_or_else(|| ScrapeError::layout_changed(name, selector))
 }
 
 pub fn string_vec(
     html: &Document,
     selector: &'static str,
     name: &'static str,
-) -> anyhow::Result<Vec<String>> {
+) -> Result<Vec<String>, ScrapeError> {
     html.select(selector)
         .into_iter()
         .map(|ele| ele.text())
         .collect::<Option<Vec<_>>>()
-        .ok_or_else(|| anyhow::anyhow!("Sector element for site {} not found: {}", name, selector))
+        .ok_or_else(|| ScrapeError::layout_changed(name, selector))
 }
 
 pub struct Document {
@@ -343,6 +381,13 @@ pub struct Element {
     handle: Handle,
 }
 
+/// Elements are equal if they are the same node, not if they look the same.
+impl PartialEq for Element {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.handle, &other.handle)
+    }
+}
+
 impl From<Handle> for Element {
     fn from(e: Handle) -> Self {
         Self::from(&e)
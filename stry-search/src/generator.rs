@@ -23,6 +23,9 @@ impl<'p> Value<'p> {
             Value::Rating(_, rating) => select("Story")
                 .fields(&["Id", "Updated"])
                 .filter("Rating".is_eq(rating)),
+            Value::Language(_, language) => select("Story")
+                .fields(&["Id", "Updated"])
+                .filter("Language".is_eq(language.code().to_string())),
         }
     }
 }
@@ -1,7 +1,7 @@
 use {
     pest::{iterators::Pair, Parser},
     std::{borrow::Cow, iter::IntoIterator, ops::Deref},
-    stry_models::Rating,
+    stry_models::{Language, Rating},
 };
 
 macro_rules! simple {
@@ -80,6 +80,7 @@ impl SearchParser {
             Rule::tag => Self::handle_tag(include, inner_pair)?,
             Rule::fandom => Self::handle_fandom(include, inner_pair)?,
             Rule::rating => Self::handle_rating(include, inner_pair)?,
+            Rule::language => Self::handle_language(include, inner_pair)?,
             p => anyhow::bail!("TODO: {:?}", p),
         };
 
@@ -147,6 +148,25 @@ impl SearchParser {
         }
     }
 
+    fn handle_language<'p>(include: bool, pair: Pair<'p, Rule>) -> anyhow::Result<Value<'p>> {
+        let mut inner_pairs = pair.into_inner();
+
+        match inner_pairs.next() {
+            Some(inner) => {
+                if inner.as_rule() == Rule::value {
+                    let language = inner.as_str().trim().parse::<Language>()?;
+
+                    Ok(Value::Language(include, language))
+                } else {
+                    anyhow::bail!("Not a valid language, language inner is not a value");
+                }
+            }
+            None => {
+                anyhow::bail!("Not a valid language, language inner has no pairs");
+            }
+        }
+    }
+
     fn handle_tag<'p>(include: bool, pair: Pair<'p, Rule>) -> anyhow::Result<Value<'p>> {
         simple!([include, pair], "tag", Rule::value, Value::General)
     }
@@ -182,6 +202,7 @@ pub enum Value<'p> {
     Fandom(bool, Cow<'p, str>),
     General(bool, Cow<'p, str>),
     Rating(bool, Rating),
+    Language(bool, Language),
 }
 
 impl<'p> Value<'p> {
@@ -193,6 +214,65 @@ impl<'p> Value<'p> {
             Value::Fandom(included, _) => *included,
             Value::General(included, _) => *included,
             Value::Rating(included, _) => *included,
+            Value::Language(included, _) => *included,
         }
     }
 }
+
+#[cfg(test)]
+mod test_super {
+    use {
+        crate::{SearchParser, Value},
+        stry_models::Language,
+    };
+
+    fn language(input: &str) -> anyhow::Result<(bool, Language)> {
+        let values = SearchParser::parse_to_structure(input)?;
+
+        match values.first() {
+            Some(Value::Language(include, language)) => Ok((*include, *language)),
+            value => anyhow::bail!("expected a language, found {:?}", value),
+        }
+    }
+
+    #[test]
+    fn language_long() -> anyhow::Result<()> {
+        assert_eq!((true, Language::English), language("language:en")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn language_short() -> anyhow::Result<()> {
+        assert_eq!((true, Language::German), language("lang:deu")?);
+        assert_eq!((true, Language::French), language("l:french")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn language_excluded() -> anyhow::Result<()> {
+        assert_eq!((false, Language::Japanese), language("-lang:ja")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn language_unknown() {
+        assert!(SearchParser::parse_to_structure("lang:xx").is_err());
+    }
+
+    #[test]
+    fn language_among_others() -> anyhow::Result<()> {
+        let values = SearchParser::parse_to_structure("r:teen, lang:en, love")?;
+
+        assert_eq!(3, values.len());
+        assert!(matches!(
+            values[1],
+            Value::Language(true, Language::English)
+        ));
+        assert!(matches!(values[2], Value::General(true, _)));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,204 @@
+//! On-disk cache of fetched pages.
+//!
+//! Pages are stored by the SHA-256 hash of their body, so pages with the same
+//! content are only stored once, with an entry for each URL pointing to its
+//! body and keeping the `ETag` and `Last-Modified` headers for revalidation.
+//!
+//! ```text
+//! <directory>/bodies/<body hash>
+//! <directory>/entries/<url hash>.ron
+//! ```
+
+use {
+    crate::Uri,
+    chrono::{DateTime, Duration, Utc},
+    serde::{Deserialize, Serialize},
+    sha2::{Digest, Sha256},
+    std::{
+        collections::HashSet,
+        fs, io,
+        path::{Path, PathBuf},
+    },
+};
+
+/// A cached response for a URL.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Entry {
+    pub url: String,
+    /// Hash of the page's body.
+    pub body: String,
+
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+
+    /// When the page was last requested or revalidated.
+    pub fetched: DateTime<Utc>,
+}
+
+/// The number of entries and bodies removed by [`Cache::prune`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Pruned {
+    pub entries: usize,
+    pub bodies: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct Cache {
+    directory: PathBuf,
+}
+
+impl Cache {
+    pub fn new<P>(directory: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self {
+            directory: directory.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Returns the URL's entry and its body, if the page has been cached.
+    pub fn get(&self, url: &Uri) -> anyhow::Result<Option<(Entry, String)>> {
+        let entry = match self.entry(&self.entry_path(url))? {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+
+        match fs::read_to_string(self.body_path(&entry.body)) {
+            Ok(body) => Ok(Some((entry, body))),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Stores the page, replacing any previous entry for the URL.
+    pub fn insert(
+        &self,
+        url: &Uri,
+        etag: Option<String>,
+        last_modified: Option<String>,
+        body: &str,
+    ) -> anyhow::Result<Entry> {
+        let hash = hash(body.as_bytes());
+
+        let body_path = self.body_path(&hash);
+
+        if !body_path.exists() {
+            fs::create_dir_all(self.directory.join("bodies"))?;
+            fs::write(&body_path, body)?;
+        }
+
+        let entry = Entry {
+            url: url.to_string(),
+            body: hash,
+            etag,
+            last_modified,
+            fetched: Utc::now(),
+        };
+
+        self.write(url, &entry)?;
+
+        Ok(entry)
+    }
+
+    /// Marks the URL's entry as fetched now, after the server has said the
+    /// page has not changed.
+    pub fn touch(&self, url: &Uri, mut entry: Entry) -> anyhow::Result<()> {
+        entry.fetched = Utc::now();
+
+        self.write(url, &entry)
+    }
+
+    /// Removes entries that have not been fetched within `older_than`, or every
+    /// entry if `None`, then removes any bodies no longer used by an entry.
+    pub fn prune(&self, older_than: Option<Duration>) -> anyhow::Result<Pruned> {
+        let mut pruned = Pruned::default();
+        let mut used = HashSet::new();
+
+        for path in list(&self.directory.join("entries"))? {
+            let entry = self.entry(&path)?;
+
+            match (entry, older_than) {
+                (Some(entry), Some(older_than)) if entry.fetched > Utc::now() - older_than => {
+                    used.insert(entry.body);
+                }
+                _ => {
+                    fs::remove_file(&path)?;
+
+                    pruned.entries += 1;
+                }
+            }
+        }
+
+        for path in list(&self.directory.join("bodies"))? {
+            let name = path.file_name().and_then(|name| name.to_str());
+
+            if !name.map(|name| used.contains(name)).unwrap_or(false) {
+                fs::remove_file(&path)?;
+
+                pruned.bodies += 1;
+            }
+        }
+
+        Ok(pruned)
+    }
+
+    fn entry(&self, path: &Path) -> anyhow::Result<Option<Entry>> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        match ron::de::from_str(&text) {
+            Ok(entry) => Ok(Some(entry)),
+            Err(err) => {
+                tracing::warn!(
+                    "Ignoring unreadable cache entry {}: {}",
+                    path.display(),
+                    err
+                );
+
+                Ok(None)
+            }
+        }
+    }
+
+    fn write(&self, url: &Uri, entry: &Entry) -> anyhow::Result<()> {
+        fs::create_dir_all(self.directory.join("entries"))?;
+        fs::write(self.entry_path(url), ron::ser::to_string(entry)?)?;
+
+        Ok(())
+    }
+
+    fn entry_path(&self, url: &Uri) -> PathBuf {
+        self.directory
+            .join("entries")
+            .join(format!("{}.ron", hash(url.to_string().as_bytes())))
+    }
+
+    fn body_path(&self, hash: &str) -> PathBuf {
+        self.directory.join("bodies").join(hash)
+    }
+}
+
+fn hash(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Lists the files in the directory, which may not have been created yet.
+fn list(directory: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let entries = match fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+
+    let mut paths = Vec::new();
+
+    for entry in entries {
+        paths.push(entry?.path());
+    }
+
+    Ok(paths)
+}
@@ -0,0 +1,149 @@
+//! Cookies kept by the HTTP client, either set by sites or imported from a
+//! browser's `cookies.txt`.
+
+use {
+    isahc::http::{header::SET_COOKIE, HeaderMap},
+    std::sync::Mutex,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cookie {
+    /// The domain without a leading `.`, the cookie is also sent to its
+    /// subdomains.
+    pub domain: String,
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Default)]
+pub struct Cookies {
+    cookies: Mutex<Vec<Cookie>>,
+}
+
+impl Cookies {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets a cookie, replacing one with the same domain and name. Empty
+    /// values remove the cookie.
+    pub fn set(&self, domain: &str, name: &str, value: &str) {
+        let domain = domain.trim_start_matches('.').to_lowercase();
+
+        let mut cookies = self.cookies.lock().expect("Cookie lock poisoned");
+
+        cookies.retain(|cookie| !(cookie.domain == domain && cookie.name == name));
+
+        if !value.is_empty() {
+            cookies.push(Cookie {
+                domain,
+                name: name.to_string(),
+                value: value.to_string(),
+            });
+        }
+    }
+
+    /// Returns the value of a cookie that would be sent to the host.
+    pub fn get(&self, host: &str, name: &str) -> Option<String> {
+        self.cookies
+            .lock()
+            .expect("Cookie lock poisoned")
+            .iter()
+            .find(|cookie| cookie.name == name && matches(host, &cookie.domain))
+            .map(|cookie| cookie.value.clone())
+    }
+
+    /// The `Cookie` header for a request to the host.
+    pub fn header(&self, host: &str) -> Option<String> {
+        let cookies = self.cookies.lock().expect("Cookie lock poisoned");
+
+        let pairs = cookies
+            .iter()
+            .filter(|cookie| matches(host, &cookie.domain))
+            .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+            .collect::<Vec<_>>();
+
+        if pairs.is_empty() {
+            None
+        } else {
+            Some(pairs.join("; "))
+        }
+    }
+
+    /// Keeps the cookies from a response's `Set-Cookie` headers, using the
+    /// cookie's `Domain` attribute if it has one.
+    pub fn store(&self, host: &str, headers: &HeaderMap) {
+        for header in headers.get_all(SET_COOKIE) {
+            let header = match header.to_str() {
+                Ok(header) => header,
+                Err(_) => continue,
+            };
+
+            let mut parts = header.split(';').map(str::trim);
+
+            let (name, value) = match parts.next().and_then(split_pair) {
+                Some(pair) => pair,
+                None => continue,
+            };
+
+            let mut domain = host;
+            let mut expired = false;
+
+            for attribute in parts {
+                match split_pair(attribute) {
+                    Some((key, value)) if key.eq_ignore_ascii_case("domain") => domain = value,
+                    Some((key, value)) if key.eq_ignore_ascii_case("max-age") => {
+                        expired = value.parse::<i64>().map(|age| age <= 0).unwrap_or(false)
+                    }
+                    _ => {}
+                }
+            }
+
+            if expired {
+                self.set(domain, name, "");
+            } else {
+                self.set(domain, name, value);
+            }
+        }
+    }
+
+    /// Imports cookies from a Netscape format `cookies.txt`, returning the
+    /// number of cookies imported.
+    pub fn import(&self, text: &str) -> usize {
+        let mut count = 0;
+
+        for line in text.lines() {
+            // HTTP only cookies are prefixed, any other `#` is a comment
+            let line = line.trim_start_matches("#HttpOnly_");
+
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields = line.split('\t').collect::<Vec<_>>();
+
+            if let [domain, _, _, _, _, name, value] = fields.as_slice() {
+                self.set(domain, name, value.trim_end());
+
+                count += 1;
+            }
+        }
+
+        count
+    }
+}
+
+fn split_pair(pair: &str) -> Option<(&str, &str)> {
+    let mut split = pair.splitn(2, '=');
+
+    match (split.next(), split.next()) {
+        (Some(key), Some(value)) if !key.trim().is_empty() => Some((key.trim(), value.trim())),
+        _ => None,
+    }
+}
+
+fn matches(host: &str, domain: &str) -> bool {
+    let host = host.to_lowercase();
+
+    host == domain || host.ends_with(&format!(".{}", domain))
+}
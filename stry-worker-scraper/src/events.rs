@@ -0,0 +1,20 @@
+//! The channel task progress is sent on, for the dashboard to show live.
+
+use {std::sync::RwLock, stry_models::TaskEvent, tokio::sync::broadcast::Sender};
+
+lazy_static::lazy_static! {
+    static ref EVENTS: RwLock<Option<Sender<TaskEvent>>> = RwLock::new(None);
+}
+
+/// Sets the channel events are sent on, events are dropped until it is set.
+pub fn configure(sender: Sender<TaskEvent>) {
+    *EVENTS.write().expect("Task event lock poisoned") = Some(sender);
+}
+
+/// Sends the event to every listener, if there are any.
+pub fn send(event: TaskEvent) {
+    if let Some(sender) = &*EVENTS.read().expect("Task event lock poisoned") {
+        // Only fails when nothing is listening
+        let _ = sender.send(event);
+    }
+}
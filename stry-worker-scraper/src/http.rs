@@ -0,0 +1,671 @@
+//! The HTTP client shared by every site.
+//!
+//! Requests are limited per host using a token bucket, with rate limited and
+//! failed requests retried after an exponential backoff, or after the time
+//! given by the server's `Retry-After` header. When enabled, pages are kept
+//! in the on-disk [`Cache`] and revalidated instead of downloaded again.
+//!
+//! Cookies set by sites are kept for the life of the client, letting sites
+//! log in with the configured accounts.
+//!
+//! Chapter images are downloaded into [`Images`] instead of the page cache.
+
+use {
+    crate::{
+        cache::{Cache, Entry, Pruned},
+        cookies::Cookies,
+        images::Images,
+        Uri,
+    },
+    chrono::{DateTime, Utc},
+    futures::{future::BoxFuture, io::AsyncReadExt},
+    isahc::{
+        http::{
+            header::{
+                CONTENT_TYPE, COOKIE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+                LOCATION, RETRY_AFTER,
+            },
+            HeaderMap, HeaderName, Response, StatusCode,
+        },
+        prelude::*,
+        Body,
+    },
+    std::{
+        collections::HashMap,
+        fs, io,
+        sync::{Arc, Mutex, RwLock},
+        time::{Duration, Instant},
+    },
+    stry_config::{Account, RateLimit, Scraper},
+    stry_scraper::ScrapeError,
+};
+
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+const USER_AGENT: &str = concat!(
+    "Mozilla/5.0 (X11; Linux x86_64; rv:63.0) Servo/1.0 Firefox/63.0 StoryDL/",
+    env!("CARGO_PKG_VERSION"),
+    " (txuritan@protonmail.com)"
+);
+#[cfg(all(target_os = "linux", not(target_arch = "x86_64")))]
+const USER_AGENT: &str = concat!(
+    "Mozilla/5.0 (X11; Linux i686; rv:63.0) Servo/1.0 Firefox/63.0 StoryDL/",
+    env!("CARGO_PKG_VERSION"),
+    " (txuritan@protonmail.com)"
+);
+
+#[cfg(all(target_os = "windows", target_arch = "x86_64"))]
+const USER_AGENT: &str = concat!(
+    "Mozilla/5.0 (Windows NT 6.1; Win64; x64; rv:63.0) Servo/1.0 Firefox/63.0 StoryDL/",
+    env!("CARGO_PKG_VERSION"),
+    " (txuritan@protonmail.com)"
+);
+#[cfg(all(target_os = "windows", not(target_arch = "x86_64")))]
+const USER_AGENT: &str = concat!(
+    "Mozilla/5.0 (Windows NT 6.1; rv:63.0) Servo/1.0 Firefox/63.0 StoryDL/",
+    env!("CARGO_PKG_VERSION"),
+    " (txuritan@protonmail.com)"
+);
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+// Neither Linux nor Windows, so maybe OS X, and if not then OS X is an okay fallback.
+const USER_AGENT: &str = concat!(
+    "Mozilla/5.0 (Macintosh; Intel Mac OS X 10.10; rv:63.0) Servo/1.0 Firefox/63.0 StoryDL/",
+    env!("CARGO_PKG_VERSION"),
+    " (txuritan@protonmail.com)"
+);
+
+#[cfg(target_os = "android")]
+const USER_AGENT: &str = concat!(
+    "Mozilla/5.0 (Android; Mobile; rv:63.0) Servo/1.0 Firefox/63.0 StoryDL/",
+    env!("CARGO_PKG_VERSION"),
+    " (txuritan@protonmail.com)"
+);
+#[cfg(target_os = "ios")]
+const USER_AGENT: &str = concat!(
+    "Mozilla/5.0 (iPhone; CPU iPhone OS 8_3 like Mac OS X; rv:63.0) Servo/1.0 Firefox/63.0 StoryDL/",
+    env!("CARGO_PKG_VERSION"),
+    " (txuritan@protonmail.com)"
+);
+
+/// The longest a request will wait before being retried.
+const MAX_BACKOFF: Duration = Duration::from_secs(10 * 60);
+
+/// The largest image that will be downloaded, anything bigger is likely not a
+/// chapter image and would otherwise be read into memory whole.
+const MAX_IMAGE_SIZE: u64 = 20 * 1024 * 1024;
+
+lazy_static::lazy_static! {
+    static ref CLIENT: RwLock<Arc<Client>> = RwLock::new(Arc::new(
+        Client::new(&Scraper::default()).expect("Unable to create HTTP client")
+    ));
+}
+
+/// Replaces the shared client with one using the given settings.
+pub fn configure(config: &Scraper) -> anyhow::Result<()> {
+    let client = Client::new(config)?;
+
+    *CLIENT.write().expect("HTTP client lock poisoned") = Arc::new(client);
+
+    Ok(())
+}
+
+/// Returns the shared client.
+pub fn client() -> Arc<Client> {
+    CLIENT.read().expect("HTTP client lock poisoned").clone()
+}
+
+pub struct Client {
+    inner: HttpClient,
+    limiter: RateLimiter,
+    cache: Option<Cache>,
+    offline: bool,
+    images: Option<Images>,
+
+    cookies: Cookies,
+    accounts: HashMap<String, Account>,
+
+    retries: u32,
+    backoff: u64,
+}
+
+/// A request that was answered with an unsuccessful status that is not a
+/// [`ScrapeError`], such as a redirect.
+#[derive(Debug, thiserror::Error)]
+#[error("Request for {url} failed with status: {status}")]
+pub struct StatusError {
+    pub url: String,
+    pub status: StatusCode,
+    /// Where the page redirects to, for redirect statuses.
+    pub location: Option<String>,
+}
+
+/// The response to a form submission.
+#[derive(Debug)]
+pub struct Submitted {
+    pub status: StatusCode,
+    pub location: Option<String>,
+    pub body: String,
+}
+
+impl Client {
+    pub fn new(config: &Scraper) -> anyhow::Result<Self> {
+        let inner = HttpClient::builder()
+            .timeout(Duration::from_secs(config.timeout))
+            .build()?;
+
+        let cookies = Cookies::new();
+
+        for (host, account) in &config.accounts {
+            if let Account::Cookies { file } = account {
+                let text = fs::read_to_string(file).map_err(|err| {
+                    anyhow::anyhow!("Unable to read cookies for {} from {}: {}", host, file, err)
+                })?;
+
+                let count = cookies.import(&text);
+
+                tracing::info!("Imported {} cookies for {} from {}", count, host, file);
+            }
+        }
+
+        Ok(Self {
+            inner,
+            limiter: RateLimiter::new(config.rate_limit, config.hosts.clone()),
+            cache: if config.cache.enabled {
+                Some(Cache::new(&config.cache.directory))
+            } else {
+                None
+            },
+            offline: config.cache.enabled && config.cache.offline,
+            images: if config.images.enabled {
+                Some(Images::new(&config.images.directory))
+            } else {
+                None
+            },
+
+            cookies,
+            accounts: config.accounts.clone(),
+
+            retries: config.retries,
+            backoff: config.backoff,
+        })
+    }
+
+    /// Requests the page, using the cached copy if the server says that it
+    /// has not changed.
+    ///
+    /// Requests are retried if the server is rate limiting or returns an
+    /// error, any other unsuccessful status is returned as an error. Missing
+    /// and deleted pages, rate limits and network errors are returned as a
+    /// [`ScrapeError`] once there are no retries left.
+    pub async fn get(&self, url: &Uri) -> anyhow::Result<String> {
+        let cache = match &self.cache {
+            Some(cache) => cache.clone(),
+            None => {
+                return self
+                    .fetch(url, None, read_text)
+                    .await
+                    .map(|fetched| fetched.body)
+            }
+        };
+
+        let cached = blocking({
+            let cache = cache.clone();
+            let url = url.clone();
+
+            move || cache.get(&url)
+        })
+        .await?;
+
+        if self.offline {
+            return cached.map(|(_, body)| body).ok_or_else(|| {
+                anyhow::anyhow!("Page is not cached, unable to fetch offline: {}", url)
+            });
+        }
+
+        let fetched = self
+            .fetch(url, cached.as_ref().map(|(entry, _)| entry), read_text)
+            .await;
+
+        match (fetched, cached) {
+            (Ok(Fetched { status, .. }), Some((entry, body)))
+                if status == StatusCode::NOT_MODIFIED =>
+            {
+                tracing::debug!("[{}] Using cached page", url);
+
+                blocking({
+                    let url = url.clone();
+
+                    move || cache.touch(&url, entry)
+                })
+                .await?;
+
+                Ok(body)
+            }
+            (Ok(fetched), _) => {
+                blocking({
+                    let url = url.clone();
+                    let body = fetched.body.clone();
+
+                    move || cache.insert(&url, fetched.etag, fetched.last_modified, &body)
+                })
+                .await?;
+
+                Ok(fetched.body)
+            }
+            (Err(err), _) => Err(err),
+        }
+    }
+
+    /// Requests the page without using the cache, for pages that change with
+    /// every request such as login forms.
+    pub async fn get_uncached(&self, url: &Uri) -> anyhow::Result<String> {
+        self.fetch(url, None, read_text)
+            .await
+            .map(|fetched| fetched.body)
+    }
+
+    /// Downloads the image into the image store, returning the name it is
+    /// stored under, or `None` if images are not being stored.
+    ///
+    /// Images that have been downloaded before are not requested again.
+    pub async fn image(&self, url: &Uri) -> anyhow::Result<Option<String>> {
+        let images = match &self.images {
+            Some(images) => images.clone(),
+            None => return Ok(None),
+        };
+
+        let stored = blocking({
+            let images = images.clone();
+            let url = url.clone();
+
+            move || images.get(&url)
+        })
+        .await?;
+
+        if let Some(name) = stored {
+            return Ok(Some(name));
+        }
+
+        if self.offline {
+            return Err(anyhow::anyhow!(
+                "Image is not stored, unable to fetch offline: {}",
+                url
+            ));
+        }
+
+        let fetched = self.fetch(url, None, read_bytes).await?;
+
+        let name = blocking({
+            let url = url.clone();
+
+            move || images.insert(&url, fetched.content_type.as_deref(), &fetched.body)
+        })
+        .await?;
+
+        Ok(Some(name))
+    }
+
+    /// Submits a form, redirects are returned instead of being an error.
+    pub async fn post_form(&self, url: &Uri, fields: &[(&str, &str)]) -> anyhow::Result<Submitted> {
+        let host = url.host().unwrap_or_default();
+
+        if self.offline {
+            return Err(anyhow::anyhow!("Unable to submit form offline: {}", url));
+        }
+
+        self.limiter.acquire(host).await;
+
+        let body = fields
+            .iter()
+            .map(|(key, value)| format!("{}={}", form_encode(key), form_encode(value)))
+            .collect::<Vec<_>>()
+            .join("&");
+
+        let mut req = Request::post(url)
+            .header("User-Agent", USER_AGENT)
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded");
+
+        if let Some(cookies) = self.cookies.header(host) {
+            req = req.header(COOKIE, cookies);
+        }
+
+        let mut res = self.inner.send_async(req.body(body)?).await?;
+
+        self.cookies.store(host, res.headers());
+
+        let status = res.status();
+        let location = header(res.headers(), LOCATION);
+
+        if !(status.is_success() || status.is_redirection()) {
+            return Err(StatusError {
+                url: url.to_string(),
+                status,
+                location,
+            }
+            .into());
+        }
+
+        Ok(Submitted {
+            status,
+            location,
+            body: res.text_async().await?,
+        })
+    }
+
+    /// The account configured for the host.
+    pub fn account(&self, host: &str) -> Option<&Account> {
+        self.accounts.get(host)
+    }
+
+    pub fn cookies(&self) -> &Cookies {
+        &self.cookies
+    }
+
+    async fn fetch<T>(
+        &self,
+        url: &Uri,
+        cached: Option<&Entry>,
+        read: fn(&mut Response<Body>) -> BoxFuture<'_, io::Result<T>>,
+    ) -> anyhow::Result<Fetched<T>> {
+        let host = url.host().unwrap_or_default();
+
+        let mut attempt = 0;
+
+        loop {
+            self.limiter.acquire(host).await;
+
+            let mut req = Request::get(url).header("User-Agent", USER_AGENT);
+
+            if let Some(cookies) = self.cookies.header(host) {
+                req = req.header(COOKIE, cookies);
+            }
+
+            if let Some(entry) = cached {
+                if let Some(etag) = &entry.etag {
+                    req = req.header(IF_NONE_MATCH, etag.as_str());
+                }
+
+                if let Some(last_modified) = &entry.last_modified {
+                    req = req.header(IF_MODIFIED_SINCE, last_modified.as_str());
+                }
+            }
+
+            let wait = match self.inner.send_async(req.body(())?).await {
+                Ok(mut res) => {
+                    self.cookies.store(host, res.headers());
+
+                    let status = res.status();
+
+                    if status.is_success()
+                        || (cached.is_some() && status == StatusCode::NOT_MODIFIED)
+                    {
+                        let content_type = header(res.headers(), CONTENT_TYPE);
+                        let etag = header(res.headers(), ETAG);
+                        let last_modified = header(res.headers(), LAST_MODIFIED);
+
+                        return Ok(Fetched {
+                            status,
+                            body: read(&mut res).await?,
+                            content_type,
+                            etag,
+                            last_modified,
+                        });
+                    }
+
+                    if !is_retryable(status) || attempt >= self.retries {
+                        return Err(status_error(url, status, res.headers()));
+                    }
+
+                    retry_after(res.headers()).unwrap_or_else(|| self.backoff(attempt))
+                }
+                Err(err) => {
+                    if attempt >= self.retries {
+                        return Err(ScrapeError::Network {
+                            url: url.to_string(),
+                            reason: err.to_string(),
+                        }
+                        .into());
+                    }
+
+                    tracing::warn!("[{}] Request failed: {}", url, err);
+
+                    self.backoff(attempt)
+                }
+            };
+
+            attempt += 1;
+
+            tracing::info!(
+                "[{}] Retrying in {:?} ({}/{})",
+                url,
+                wait,
+                attempt,
+                self.retries
+            );
+
+            tokio::time::delay_for(wait.min(MAX_BACKOFF)).await;
+        }
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        Duration::from_millis(self.backoff.saturating_mul(1 << attempt.min(16)))
+    }
+}
+
+/// Removes pages from the cache, see [`Cache::prune`].
+pub fn prune_cache(
+    config: &Scraper,
+    older_than: Option<chrono::Duration>,
+) -> anyhow::Result<Pruned> {
+    Cache::new(&config.cache.directory).prune(older_than)
+}
+
+struct Fetched<T> {
+    status: StatusCode,
+    body: T,
+    content_type: Option<String>,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+// The bodies are read without blocking, as a slow server would otherwise
+// hold up a runtime thread until it finishes sending the page.
+fn read_text(res: &mut Response<Body>) -> BoxFuture<'_, io::Result<String>> {
+    Box::pin(res.text_async())
+}
+
+fn read_bytes(res: &mut Response<Body>) -> BoxFuture<'_, io::Result<Vec<u8>>> {
+    Box::pin(async move {
+        let mut bytes = Vec::new();
+
+        // Reading one byte past the limit tells a body that is exactly the
+        // limit apart from one that is bigger
+        res.body_mut()
+            .take(MAX_IMAGE_SIZE + 1)
+            .read_to_end(&mut bytes)
+            .await?;
+
+        if bytes.len() as u64 > MAX_IMAGE_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Image is larger than {} bytes", MAX_IMAGE_SIZE),
+            ));
+        }
+
+        Ok(bytes)
+    })
+}
+
+async fn blocking<F, T>(f: F) -> anyhow::Result<T>
+where
+    F: FnOnce() -> anyhow::Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .expect("Thread pool closed")
+}
+
+fn header(headers: &HeaderMap, name: HeaderName) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(String::from)
+}
+
+/// Encodes a form value as `application/x-www-form-urlencoded`.
+fn form_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'*' | b'-' | b'.' | b'_' => {
+                encoded.push(byte as char)
+            }
+            b' ' => encoded.push('+'),
+            byte => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    encoded
+}
+
+/// Turns an unsuccessful response into a [`ScrapeError`] if it is one, or a
+/// [`StatusError`] otherwise.
+fn status_error(url: &Uri, status: StatusCode, headers: &HeaderMap) -> anyhow::Error {
+    match status {
+        StatusCode::NOT_FOUND => ScrapeError::NotFound {
+            url: url.to_string(),
+        }
+        .into(),
+        StatusCode::GONE => ScrapeError::Deleted {
+            url: url.to_string(),
+        }
+        .into(),
+        StatusCode::TOO_MANY_REQUESTS => ScrapeError::RateLimited {
+            host: url.host().unwrap_or_default().to_string(),
+        }
+        .into(),
+        status if status.is_server_error() => ScrapeError::Network {
+            url: url.to_string(),
+            reason: format!("Server responded with status: {}", status),
+        }
+        .into(),
+        status => StatusError {
+            url: url.to_string(),
+            status,
+            location: header(headers, LOCATION),
+        }
+        .into(),
+    }
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Parses a `Retry-After` header, which is either a number of seconds or a
+/// HTTP date.
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+
+    Some(
+        date.with_timezone(&Utc)
+            .signed_duration_since(Utc::now())
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+/// Keeps a token bucket for every host a request has been made to.
+pub struct RateLimiter {
+    default: RateLimit,
+    hosts: HashMap<String, RateLimit>,
+
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(default: RateLimit, hosts: HashMap<String, RateLimit>) -> Self {
+        Self {
+            default,
+            hosts,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Waits until a request can be made to the host.
+    pub async fn acquire(&self, host: &str) {
+        loop {
+            let wait = {
+                let mut buckets = self.buckets.lock().expect("Rate limiter lock poisoned");
+
+                let limit = self.hosts.get(host).copied().unwrap_or(self.default);
+
+                buckets
+                    .entry(host.to_string())
+                    .or_insert_with(|| Bucket::new(limit, Instant::now()))
+                    .take(Instant::now())
+            };
+
+            match wait {
+                Some(wait) => {
+                    tracing::debug!("[{}] Rate limited for {:?}", host, wait);
+
+                    tokio::time::delay_for(wait).await;
+                }
+                None => return,
+            }
+        }
+    }
+}
+
+/// A token bucket that starts full.
+#[derive(Clone, Debug)]
+pub struct Bucket {
+    capacity: f64,
+    tokens: f64,
+    interval: Duration,
+    last: Instant,
+}
+
+impl Bucket {
+    pub fn new(limit: RateLimit, now: Instant) -> Self {
+        let capacity = f64::from(limit.burst.max(1));
+
+        Self {
+            capacity,
+            tokens: capacity,
+            interval: Duration::from_millis(limit.interval),
+            last: now,
+        }
+    }
+
+    /// Takes a token from the bucket, or returns how long it will be until
+    /// there is one to take.
+    pub fn take(&mut self, now: Instant) -> Option<Duration> {
+        if self.interval == Duration::from_secs(0) {
+            return None;
+        }
+
+        let interval = self.interval.as_secs_f64();
+        let elapsed = now.saturating_duration_since(self.last).as_secs_f64();
+
+        self.tokens = (self.tokens + (elapsed / interval)).min(self.capacity);
+        self.last = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - self.tokens) * interval))
+        }
+    }
+}
@@ -0,0 +1,241 @@
+//! Images found in chapters, stored so that stories can be read offline and
+//! without requests being made to the site they came from.
+//!
+//! Images are stored by the SHA-256 hash of their contents, so an image used
+//! by many chapters is only stored once, with the URLs it was downloaded from
+//! pointing to it so that it is not downloaded again.
+//!
+//! ```text
+//! <directory>/<image hash>.<extension>
+//! <directory>/urls/<url hash>
+//! ```
+
+use {
+    crate::{http::Client, Uri},
+    sha2::{Digest, Sha256},
+    std::{
+        fs, io,
+        ops::Range,
+        path::{Path, PathBuf},
+    },
+};
+
+/// The path stored images are served from.
+pub const ROUTE: &str = "/images/";
+
+#[derive(Clone, Debug)]
+pub struct Images {
+    directory: PathBuf,
+}
+
+impl Images {
+    pub fn new<P>(directory: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self {
+            directory: directory.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Returns the name of the image downloaded from the URL, if it has been
+    /// stored.
+    pub fn get(&self, url: &Uri) -> anyhow::Result<Option<String>> {
+        let name = match fs::read_to_string(self.url_path(url)) {
+            Ok(name) => name,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        if self.directory.join(&name).exists() {
+            Ok(Some(name))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Stores the image, returning the name it is stored under.
+    pub fn insert(
+        &self,
+        url: &Uri,
+        content_type: Option<&str>,
+        bytes: &[u8],
+    ) -> anyhow::Result<String> {
+        let extension = extension(content_type).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Not an image, {} has the content type: {}",
+                url,
+                content_type.unwrap_or("none")
+            )
+        })?;
+
+        let name = format!("{}.{}", hash(bytes), extension);
+
+        let path = self.directory.join(&name);
+
+        if !path.exists() {
+            fs::create_dir_all(&self.directory)?;
+            fs::write(&path, bytes)?;
+        }
+
+        fs::create_dir_all(self.directory.join("urls"))?;
+        fs::write(self.url_path(url), &name)?;
+
+        Ok(name)
+    }
+
+    fn url_path(&self, url: &Uri) -> PathBuf {
+        self.directory
+            .join("urls")
+            .join(hash(url.to_string().as_bytes()))
+    }
+}
+
+/// The file extension for an image's content type, `None` if it is not an
+/// image that can be stored.
+pub fn extension(content_type: Option<&str>) -> Option<&'static str> {
+    let mime = content_type?.split(';').next()?.trim().to_lowercase();
+
+    match mime.as_str() {
+        "image/bmp" => Some("bmp"),
+        "image/gif" => Some("gif"),
+        "image/jpeg" | "image/jpg" => Some("jpg"),
+        "image/png" => Some("png"),
+        // SVGs are left out as they can carry scripts, which would run on the
+        // frontend's origin when an image is opened directly
+        "image/webp" => Some("webp"),
+        _ => None,
+    }
+}
+
+/// Finds the images in chapter markdown, as written by `stry_remark`,
+/// returning the range of each image's URL, without the title if it has one.
+pub fn find(markdown: &str) -> Vec<Range<usize>> {
+    let mut found = Vec::new();
+
+    let mut offset = 0;
+
+    while let Some(start) = markdown[offset..].find("![") {
+        let start = offset + start;
+
+        let url_start = match markdown[start..].find("](") {
+            Some(index) => start + index + 2,
+            None => break,
+        };
+
+        let url_end = match markdown[url_start..].find(|c: char| c == ')' || c.is_whitespace()) {
+            Some(index) => url_start + index,
+            None => break,
+        };
+
+        found.push(url_start..url_end);
+
+        offset = url_end;
+    }
+
+    found
+}
+
+/// Downloads the images in the markdown and points them to the stored copy,
+/// images that can not be downloaded are left pointing to the site.
+///
+/// Relative image URLs are resolved against `base`.
+pub async fn localize(client: &Client, base: &Uri, markdown: &str) -> String {
+    let mut localized = String::with_capacity(markdown.len());
+
+    let mut last = 0;
+
+    for range in find(markdown) {
+        let src = &markdown[range.clone()];
+
+        let url = match resolve(base, src) {
+            Some(url) => url,
+            None => continue,
+        };
+
+        match client.image(&url).await {
+            Ok(Some(name)) => {
+                localized.push_str(&markdown[last..range.start]);
+                localized.push_str(ROUTE);
+                localized.push_str(&name);
+
+                last = range.end;
+            }
+            Ok(None) => return markdown.to_string(),
+            Err(err) => {
+                tracing::warn!("[{}] Unable to download image: {}", url, err);
+            }
+        }
+    }
+
+    localized.push_str(&markdown[last..]);
+
+    localized
+}
+
+/// Resolves an image's `src` against the page it is on, images that are
+/// embedded or already stored are skipped.
+pub fn resolve(base: &Uri, src: &str) -> Option<Uri> {
+    let src = src.trim();
+
+    let scheme = base.scheme_str().unwrap_or("https");
+
+    let url = if src.starts_with("http://") || src.starts_with("https://") {
+        src.to_string()
+    } else if src.starts_with("//") {
+        format!("{}:{}", scheme, src)
+    } else if src.starts_with('/') {
+        if src.starts_with(ROUTE) {
+            return None;
+        }
+
+        format!("{}://{}{}", scheme, base.authority()?, src)
+    } else if is_path_relative(src) {
+        let path = base.path();
+        let directory = &path[..path.rfind('/').map(|index| index + 1).unwrap_or(0)];
+
+        format!(
+            "{}://{}{}",
+            scheme,
+            base.authority()?,
+            remove_dots(&format!("/{}{}", directory.trim_start_matches('/'), src))
+        )
+    } else {
+        return None;
+    };
+
+    url.parse().ok()
+}
+
+/// If the `src` is a path relative to the page, and not a URL with another
+/// scheme such as `data:`.
+fn is_path_relative(src: &str) -> bool {
+    let first = src
+        .find(|c| c == '/' || c == '?' || c == '#')
+        .unwrap_or(src.len());
+
+    !src.is_empty() && first != 0 && !src[..first].contains(':')
+}
+
+/// Removes the `.` and `..` segments from an absolute path.
+fn remove_dots(path: &str) -> String {
+    let (path, query) = path.split_at(path.find('?').unwrap_or(path.len()));
+
+    let mut segments = Vec::new();
+
+    for segment in path.split('/').skip(1) {
+        match segment {
+            "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+
+    format!("/{}{}", segments.join("/"), query)
+}
+
+fn hash(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
@@ -0,0 +1,272 @@
+//! Background jobs, run by the workers alongside their scraping tasks.
+//!
+//! Jobs are taken from the backend's job queue and given to the [`Handler`]
+//! registered for their kind, jobs of kinds without a handler are left in
+//! the queue. Scheduled jobs are queued by the workers as well, see
+//! [`Scheduler`].
+
+use {
+    crate::{http, schedule::Scheduler, Uri},
+    chrono::Utc,
+    std::{fs, future::Future, path::Path, pin::Pin, sync::Arc},
+    stry_backend::DataBackend,
+    stry_config::{Schedule, Scraper},
+    stry_evermore::Worker,
+    stry_models::{
+        job::{Backup, ImageDownload, PruneCache, UpdateAll, UpdateCheck, Vacuum},
+        Job, JobKind, JobState,
+    },
+    tracing_futures::Instrument,
+};
+
+/// How long to wait before running a failed job again, in seconds, doubled
+/// for every attempt.
+const RETRY_DELAY: i64 = 60;
+
+/// How long to wait before checking for jobs again, when there are none.
+const IDLE_DELAY: tokio::time::Duration = tokio::time::Duration::from_secs(30);
+
+pub type JobFuture = Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send>>;
+
+/// Runs the jobs of a kind.
+pub trait Handler: Send + Sync {
+    fn run(&self, backend: DataBackend, job: Job) -> JobFuture;
+}
+
+impl<F, Fut> Handler for F
+where
+    F: Fn(DataBackend, Job) -> Fut + Send + Sync,
+    Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+{
+    fn run(&self, backend: DataBackend, job: Job) -> JobFuture {
+        Box::pin((self)(backend, job))
+    }
+}
+
+/// The handlers of each job kind, and the jobs that are scheduled.
+#[derive(Clone, Default)]
+pub struct Jobs {
+    handlers: Vec<(JobKind, Arc<dyn Handler>)>,
+    scheduler: Scheduler,
+}
+
+impl Jobs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates the handlers for the jobs stry can run on its own.
+    pub fn builtin(scraper: &Scraper) -> Self {
+        let scraper = scraper.clone();
+
+        Self::new()
+            .register(JobKind::Backup, backup)
+            .register(JobKind::ImageDownload, image_download)
+            .register(
+                JobKind::PruneCache,
+                move |_backend: DataBackend, job: Job| prune_cache(scraper.clone(), job),
+            )
+            .register(JobKind::UpdateAll, update_all)
+            .register(JobKind::UpdateCheck, update_check)
+            .register(JobKind::Vacuum, vacuum)
+    }
+
+    /// Sets the jobs that are queued on a schedule.
+    pub fn schedule(mut self, schedules: &[Schedule]) -> Self {
+        self.scheduler = Scheduler::new(schedules);
+
+        self
+    }
+
+    /// Sets the handler of a kind, replacing any handler it already had.
+    pub fn register<H>(mut self, kind: JobKind, handler: H) -> Self
+    where
+        H: Handler + 'static,
+    {
+        self.handlers.retain(|(registered, _)| *registered != kind);
+        self.handlers.push((kind, Arc::new(handler)));
+
+        self
+    }
+
+    fn kinds(&self) -> Vec<JobKind> {
+        self.handlers.iter().map(|(kind, _)| kind.clone()).collect()
+    }
+
+    fn handler(&self, kind: &JobKind) -> Option<Arc<dyn Handler>> {
+        self.handlers
+            .iter()
+            .find(|(registered, _)| registered == kind)
+            .map(|(_, handler)| handler.clone())
+    }
+}
+
+#[tracing::instrument(skip(worker, jobs), err)]
+pub async fn task(worker: Worker<DataBackend>, jobs: Jobs) -> anyhow::Result<()> {
+    loop {
+        if worker.should_stop() {
+            tracing::info!("Received shutdown signal, shutting down");
+
+            break;
+        }
+
+        let next = if jobs.scheduler.is_empty() {
+            None
+        } else {
+            match jobs.scheduler.tick(&worker.data, Utc::now()).await {
+                Ok(next) => next,
+                Err(err) => {
+                    tracing::error!("Unable to queue scheduled jobs: {}", err);
+
+                    None
+                }
+            }
+        };
+
+        // A failed claim, usually a busy database, is retried on the next check
+        // instead of stopping the worker
+        let job = match worker.data.claim_job(jobs.kinds()).await {
+            Ok(job) => job,
+            Err(err) => {
+                tracing::error!("Unable to claim a job: {}", err);
+
+                None
+            }
+        };
+
+        let job = match job {
+            Some(job) => job,
+            None => {
+                // Job check runs every 30 seconds, or sooner if a scheduled job is due
+                let wait = next
+                    .and_then(|next| (next - Utc::now()).to_std().ok())
+                    .map_or(IDLE_DELAY, |wait| wait.min(IDLE_DELAY));
+
+                tokio::select! {
+                    _ = tokio::time::delay_for(wait) => {}
+                    _ = worker.cancelled() => {}
+                }
+
+                continue;
+            }
+        };
+
+        let handler = match jobs.handler(&job.kind) {
+            Some(handler) => handler,
+            None => continue,
+        };
+
+        let id = job.id.clone();
+        let attempts = job.attempts;
+
+        let span = tracing::info_span!("job", id = %id, kind = %job.kind, attempt = attempts);
+
+        // Jobs stopped by a shutdown are left running, and are queued again
+        // on the next start
+        let res = tokio::select! {
+            res = handler.run(worker.data.clone(), job).instrument(span) => res,
+            _ = worker.cancelled() => {
+                tracing::info!("Received shutdown signal, shutting down");
+
+                break;
+            }
+        };
+
+        match res {
+            Ok(()) => worker.data.complete_job(id.into()).await?,
+            Err(err) => {
+                let delay = RETRY_DELAY.saturating_mul(1 << (attempts.max(1).min(16) - 1));
+                let retry_at = Utc::now() + chrono::Duration::seconds(delay);
+
+                let state = worker
+                    .data
+                    .fail_job(id.clone().into(), err.to_string().into(), retry_at)
+                    .await?;
+
+                if state == JobState::Dead {
+                    tracing::error!("Job {} has used all of its attempts: {}", id, err);
+                } else {
+                    tracing::warn!("Job {} will be retried: {}", id, err);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn backup(backend: DataBackend, job: Job) -> anyhow::Result<()> {
+    let backup: Backup = job.payload_as()?;
+
+    fs::create_dir_all(&backup.directory)?;
+
+    let path = Path::new(&backup.directory)
+        .join(format!("stry-{}.db", Utc::now().format("%Y%m%d-%H%M%S")));
+
+    backend
+        .backup(path.to_string_lossy().into_owned().into())
+        .await?;
+
+    tracing::info!("Backed up the database to {}", path.display());
+
+    Ok(())
+}
+
+async fn image_download(_backend: DataBackend, job: Job) -> anyhow::Result<()> {
+    let download: ImageDownload = job.payload_as()?;
+
+    let url: Uri = download.url.parse()?;
+
+    if http::client().image(&url).await?.is_none() {
+        tracing::warn!("Images are not being stored, skipping {}", url);
+    }
+
+    Ok(())
+}
+
+async fn prune_cache(scraper: Scraper, job: Job) -> anyhow::Result<()> {
+    let prune: PruneCache = job.payload_as()?;
+
+    let pruned = tokio::task::spawn_blocking(move || {
+        http::prune_cache(&scraper, prune.older_than.map(chrono::Duration::days))
+    })
+    .await??;
+
+    tracing::info!(
+        "Pruned {} cached pages and {} bodies",
+        pruned.entries,
+        pruned.bodies
+    );
+
+    Ok(())
+}
+
+async fn update_all(backend: DataBackend, job: Job) -> anyhow::Result<()> {
+    let _: UpdateAll = job.payload_as()?;
+
+    let requeued = backend.requeue_tasks().await?;
+
+    tracing::info!("Queued {} stories to check for new chapters", requeued);
+
+    Ok(())
+}
+
+async fn update_check(backend: DataBackend, job: Job) -> anyhow::Result<()> {
+    let check: UpdateCheck = job.payload_as()?;
+
+    let added = backend
+        .add_task(check.name.into(), check.site, check.url.clone().into())
+        .await?;
+
+    if !added {
+        tracing::debug!("Story is already waiting to be scraped: {}", check.url);
+    }
+
+    Ok(())
+}
+
+async fn vacuum(backend: DataBackend, job: Job) -> anyhow::Result<()> {
+    let _: Vacuum = job.payload_as()?;
+
+    backend.vacuum().await
+}
@@ -4,11 +4,19 @@ pub mod sites;
 #[cfg(test)]
 pub mod tests;
 
+pub mod cache;
+pub mod cookies;
+pub mod events;
+pub mod http;
+pub mod images;
+pub mod jobs;
 pub mod models;
+pub mod schedule;
+pub mod script;
 pub mod task;
 pub mod utils;
 
-pub use {isahc::http::uri::Uri, sites::*};
+pub use {isahc::http::uri::Uri, sites::*, stry_scraper::ScrapeError};
 
 use stry_common::LibraryDetails;
 
@@ -1,3 +1,5 @@
+pub use stry_models::{Language, WorkerSite};
+
 use chrono::{DateTime, Utc};
 
 #[cfg(any(feature = "epub", feature = "json", feature = "messagepack"))]
@@ -86,8 +88,9 @@ impl std::fmt::Display for Format {
     }
 }
 
-#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(
+    Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
 pub struct Story {
     pub name: String,
     pub summary: String,
@@ -102,6 +105,7 @@ pub struct Story {
     pub authors: Vec<String>,
     pub origins: Vec<String>,
     pub tags: Vec<(TagType, String)>,
+    pub pairings: Vec<Pairing>,
 
     pub series: Option<Series>,
 
@@ -125,8 +129,9 @@ impl Story {
             authors: details.authors,
             origins: details.origins,
             tags: details.tags,
+            pairings: details.pairings,
 
-            series: None,
+            series: details.series,
 
             created: details.created,
             updated: details.updated,
@@ -134,7 +139,9 @@ impl Story {
     }
 }
 
-#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(
+    Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
 pub struct Details {
     pub name: String,
     pub summary: String,
@@ -148,46 +155,61 @@ pub struct Details {
     pub authors: Vec<String>,
     pub origins: Vec<String>,
     pub tags: Vec<(TagType, String)>,
+    pub pairings: Vec<Pairing>,
+
+    pub series: Option<Series>,
 
     pub created: DateTime<Utc>,
     pub updated: DateTime<Utc>,
 }
 
+/// A story linked from a listing, such as an author's works or a collection.
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub enum Language {
-    #[cfg_attr(feature = "serde", serde(rename = "english"))]
-    English,
+pub struct ListedStory {
+    pub site: WorkerSite,
+
+    pub name: String,
+    pub url: String,
 }
 
+/// A single page of a listing, `next` is the URL of the following page.
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Listing {
+    pub stories: Vec<ListedStory>,
+    pub next: Option<String>,
+}
+
+#[derive(
+    Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
 pub enum Rating {
-    #[cfg_attr(feature = "serde", serde(rename = "explicit"))]
+    #[serde(rename = "explicit")]
     Explicit,
-    #[cfg_attr(feature = "serde", serde(rename = "mature"))]
+    #[serde(rename = "mature")]
     Mature,
-    #[cfg_attr(feature = "serde", serde(rename = "teen"))]
+    #[serde(rename = "teen")]
     Teen,
-    #[cfg_attr(feature = "serde", serde(rename = "general"))]
+    #[serde(rename = "general")]
     General,
 }
 
-#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(
+    Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
 pub enum State {
-    #[cfg_attr(feature = "serde", serde(rename = "completed"))]
+    #[serde(rename = "completed")]
     Completed,
-    #[cfg_attr(feature = "serde", serde(rename = "in-progress"))]
+    #[serde(rename = "in-progress")]
     InProgress,
-    #[cfg_attr(feature = "serde", serde(rename = "hiatus"))]
+    #[serde(rename = "hiatus")]
     Hiatus,
-    #[cfg_attr(feature = "serde", serde(rename = "abandoned"))]
+    #[serde(rename = "abandoned")]
     Abandoned,
 }
 
-#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(
+    Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
 pub struct Chapter {
     pub name: String,
 
@@ -198,8 +220,9 @@ pub struct Chapter {
     pub words: u32,
 }
 
-#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(
+    Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
 pub struct Series {
     pub name: String,
 
@@ -208,15 +231,27 @@ pub struct Series {
     pub place: Option<i32>,
 }
 
-#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A relationship between characters, platonic relationships are friendships
+/// or family instead of romantic.
+#[derive(
+    Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+pub struct Pairing {
+    pub characters: Vec<String>,
+
+    pub platonic: bool,
+}
+
+#[derive(
+    Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
 pub enum TagType {
-    #[cfg_attr(feature = "serde", serde(rename = "warning"))]
+    #[serde(rename = "warning")]
     Warning,
-    #[cfg_attr(feature = "serde", serde(rename = "pairing"))]
+    #[serde(rename = "pairing")]
     Pairing,
-    #[cfg_attr(feature = "serde", serde(rename = "character"))]
+    #[serde(rename = "character")]
     Character,
-    #[cfg_attr(feature = "serde", serde(rename = "general"))]
+    #[serde(rename = "general")]
     General,
 }
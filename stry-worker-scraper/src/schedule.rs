@@ -0,0 +1,212 @@
+//! Jobs queued whenever their cron expression matches, configured with
+//! [`Schedule`].
+//!
+//! The last and next run of every schedule are kept in the `Settings` table.
+//! Once a run is due, the first worker to move the next run forward queues
+//! the job, so a run is only queued once no matter the number of workers.
+
+use {
+    chrono::{DateTime, Utc},
+    std::{borrow::Cow, collections::BTreeMap, str::FromStr},
+    stry_backend::DataBackend,
+    stry_config::{Schedule, ScheduledJob},
+    stry_models::{
+        job::{Backup, PruneCache, UpdateAll, Vacuum},
+        Settings,
+    },
+};
+
+/// The start of the keys of every schedule's settings.
+pub const PREFIX: &str = "schedule.";
+
+const LAST_RUN: &str = ".last-run";
+const NEXT_RUN: &str = ".next-run";
+
+#[derive(Clone, Debug, Default)]
+pub struct Scheduler {
+    entries: Vec<Entry>,
+}
+
+#[derive(Clone, Debug)]
+struct Entry {
+    name: String,
+    cron: cron::Schedule,
+    job: ScheduledJob,
+}
+
+impl Scheduler {
+    /// Schedules with an invalid cron expression are logged and skipped.
+    pub fn new(schedules: &[Schedule]) -> Self {
+        let entries = schedules
+            .iter()
+            .filter_map(|schedule| match cron::Schedule::from_str(&schedule.cron) {
+                Ok(cron) => Some(Entry {
+                    name: schedule.name.clone(),
+                    cron,
+                    job: schedule.job.clone(),
+                }),
+                Err(err) => {
+                    tracing::error!(
+                        "Schedule {} has an invalid cron expression '{}': {}",
+                        schedule.name,
+                        schedule.cron,
+                        err
+                    );
+
+                    None
+                }
+            })
+            .collect();
+
+        Self { entries }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Queues the jobs that are due, returning when the next job is due.
+    pub async fn tick(
+        &self,
+        backend: &DataBackend,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<Option<DateTime<Utc>>> {
+        let mut soonest: Option<DateTime<Utc>> = None;
+
+        for entry in &self.entries {
+            if let Some(next) = entry.tick(backend, now).await? {
+                soonest = Some(soonest.map_or(next, |soonest| soonest.min(next)));
+            }
+        }
+
+        Ok(soonest)
+    }
+}
+
+impl Entry {
+    async fn tick(
+        &self,
+        backend: &DataBackend,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<Option<DateTime<Utc>>> {
+        let key = format!("{}{}{}", PREFIX, self.name, NEXT_RUN);
+
+        let stored = backend.get_setting(key.clone().into()).await?;
+
+        let due = match stored.as_deref().and_then(parse) {
+            Some(next) if next > now => return Ok(Some(next)),
+            Some(_) => true,
+            // The schedule is new, its first run is its next match
+            None => false,
+        };
+
+        let upcoming = self.cron.after(&now).next();
+
+        let swapped = backend
+            .swap_setting(
+                key.into(),
+                stored.map(Cow::from),
+                upcoming
+                    .map(|upcoming| upcoming.to_rfc3339())
+                    .unwrap_or_default()
+                    .into(),
+            )
+            .await?;
+
+        // Another worker has already queued this run
+        if !swapped || !due {
+            return Ok(upcoming);
+        }
+
+        tracing::info!(schedule = %self.name, "Queuing scheduled job");
+
+        self.queue(backend, now).await?;
+
+        backend
+            .set_setting(
+                format!("{}{}{}", PREFIX, self.name, LAST_RUN).into(),
+                now.to_rfc3339().into(),
+            )
+            .await?;
+
+        Ok(upcoming)
+    }
+
+    async fn queue(&self, backend: &DataBackend, now: DateTime<Utc>) -> anyhow::Result<String> {
+        match &self.job {
+            ScheduledJob::UpdateCheck => backend.add_job(&UpdateAll {}, 0, now).await,
+            ScheduledJob::Vacuum => backend.add_job(&Vacuum {}, 0, now).await,
+            ScheduledJob::Backup { directory } => {
+                backend
+                    .add_job(
+                        &Backup {
+                            directory: directory.clone(),
+                        },
+                        0,
+                        now,
+                    )
+                    .await
+            }
+            ScheduledJob::PruneCache { older_than } => {
+                backend
+                    .add_job(
+                        &PruneCache {
+                            older_than: *older_than,
+                        },
+                        0,
+                        now,
+                    )
+                    .await
+            }
+        }
+    }
+}
+
+/// The last and next run of a schedule.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Status {
+    pub name: String,
+    pub last_run: Option<DateTime<Utc>>,
+    pub next_run: Option<DateTime<Utc>>,
+}
+
+/// Reads the status of every schedule from their settings, ordered by the
+/// schedule's name.
+pub fn status(settings: &[Settings]) -> Vec<Status> {
+    let mut schedules: BTreeMap<&str, Status> = BTreeMap::new();
+
+    for setting in settings {
+        let key = match setting.key.strip_prefix(PREFIX) {
+            Some(key) => key,
+            None => continue,
+        };
+
+        let (name, last) = if let Some(name) = key.strip_suffix(LAST_RUN) {
+            (name, true)
+        } else if let Some(name) = key.strip_suffix(NEXT_RUN) {
+            (name, false)
+        } else {
+            continue;
+        };
+
+        let status = schedules.entry(name).or_insert_with(|| Status {
+            name: name.to_string(),
+            last_run: None,
+            next_run: None,
+        });
+
+        if last {
+            status.last_run = parse(&setting.value);
+        } else {
+            status.next_run = parse(&setting.value);
+        }
+    }
+
+    schedules.into_iter().map(|(_, status)| status).collect()
+}
+
+fn parse(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|date| date.with_timezone(&Utc))
+}
@@ -0,0 +1,160 @@
+use serde::Deserialize;
+
+/// A site scraper described by selectors and script functions, loaded from a
+/// RON file instead of being compiled in.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Definition {
+    pub name: String,
+
+    /// Host names that the site's stories can be found on.
+    pub hosts: Vec<String>,
+
+    pub urls: Urls,
+
+    pub details: DetailsDefinition,
+    pub chapter: ChapterDefinition,
+
+    /// Rhai source for the functions named by `Field::transform`, it must
+    /// also define `id(path)` to get a story ID from a URL path.
+    pub script: String,
+}
+
+/// URL templates, `{id}` and `{chapter}` are replaced before the request.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Urls {
+    pub details: String,
+    pub chapter: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct DetailsDefinition {
+    pub name: Field,
+    pub summary: Field,
+
+    pub chapters: Field,
+    pub language: Field,
+    pub rating: Field,
+    pub state: Field,
+
+    pub authors: Field,
+    #[serde(default)]
+    pub origins: Option<Field>,
+    /// Tags given the warning type, before any other tags.
+    #[serde(default)]
+    pub warnings: Option<Field>,
+    /// Tags given the character type, after the warnings.
+    #[serde(default)]
+    pub characters: Option<Field>,
+    #[serde(default)]
+    pub tags: Option<Field>,
+    /// Each pairing is a map of its `characters` and if it is `platonic`.
+    #[serde(default)]
+    pub pairings: Option<Field>,
+
+    #[serde(default)]
+    pub series: Option<SeriesDefinition>,
+
+    pub created: Field,
+    /// When missing or empty the creation date is used.
+    #[serde(default)]
+    pub updated: Option<Field>,
+}
+
+/// The series a story is part of, there is no series if the name's selector
+/// matches nothing.
+#[derive(Clone, Debug, Deserialize)]
+pub struct SeriesDefinition {
+    pub name: Field,
+
+    #[serde(default)]
+    pub summary: Option<Field>,
+    /// The story's place in the series.
+    #[serde(default)]
+    pub place: Option<Field>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ChapterDefinition {
+    pub name: Field,
+    pub main: Field,
+
+    /// The number of chapters on the page, notes only used for the last
+    /// chapter are skipped without it.
+    #[serde(default)]
+    pub chapters: Option<Field>,
+
+    /// Notes are joined by blank lines, empty notes are skipped.
+    #[serde(default)]
+    pub pre: Vec<Note>,
+    #[serde(default)]
+    pub post: Vec<Note>,
+}
+
+/// A part of the text before or after a chapter, such as its summary or the
+/// author's notes.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Note {
+    pub field: Field,
+
+    /// Only use the note for the first or the last chapter.
+    #[serde(default)]
+    pub only: Option<Place>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+pub enum Place {
+    First,
+    Last,
+}
+
+/// A value taken from a page, `{chapter}` in the selector is replaced with
+/// the current chapter number.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Field {
+    pub selector: String,
+
+    /// Use the value of this attribute instead of the element's content.
+    #[serde(default)]
+    pub attr: Option<String>,
+
+    /// Skips the selected elements that this selector also selects.
+    #[serde(default)]
+    pub exclude: Option<String>,
+
+    #[serde(default)]
+    pub kind: Kind,
+
+    /// Use every matching element, passed to the transform as an array.
+    #[serde(default)]
+    pub all: bool,
+
+    /// Convert the element's HTML into markdown, any matching elements are
+    /// joined together before converting.
+    #[serde(default)]
+    pub markdown: bool,
+
+    /// If the selector matches nothing the field is given as `()`.
+    #[serde(default)]
+    pub optional: bool,
+
+    /// The name of a script function used to transform the value.
+    #[serde(default)]
+    pub transform: Option<String>,
+
+    /// Used if the selector does not match any elements.
+    #[serde(default)]
+    pub fallback: Option<Box<Field>>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+pub enum Kind {
+    Text,
+    Html,
+    InnerHtml,
+}
+
+impl Default for Kind {
+    fn default() -> Self {
+        Kind::Text
+    }
+}
@@ -0,0 +1,287 @@
+use {
+    crate::models::{Language, Pairing, Rating, State, TagType},
+    chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc},
+    rhai::{Array, Dynamic, Engine, ImmutableString, Map, INT},
+};
+
+/// Far more than any transform should need, a field is only a bit of text.
+const MAX_OPERATIONS: u64 = 1_000_000;
+const MAX_CALL_LEVELS: usize = 32;
+const MAX_EXPR_DEPTH: usize = 64;
+const MAX_FUNCTION_EXPR_DEPTH: usize = 32;
+
+lazy_static::lazy_static! {
+    pub(crate) static ref ENGINE: Engine = {
+        let mut engine = Engine::new();
+
+        // Scripts run on the worker's thread, these stop a runaway script
+        // from holding it forever
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.set_max_call_levels(MAX_CALL_LEVELS);
+        engine.set_max_expr_depths(MAX_EXPR_DEPTH, MAX_FUNCTION_EXPR_DEPTH);
+
+        engine.register_fn("parse_date", parse_date);
+
+        engine
+    };
+}
+
+/// Returns the date as a unix timestamp, or `()` if it could not be parsed.
+fn parse_date(text: ImmutableString, format: ImmutableString) -> Dynamic {
+    let text = text.trim();
+
+    NaiveDateTime::parse_from_str(text, &format)
+        .or_else(|_| NaiveDate::parse_from_str(text, &format).map(|date| date.and_hms(0, 0, 0)))
+        .map(|dt| Dynamic::from(dt.timestamp() as INT))
+        .unwrap_or_else(|_| Dynamic::from(()))
+}
+
+pub(crate) fn from_string(value: String) -> Dynamic {
+    Dynamic::from(ImmutableString::from(value))
+}
+
+pub(crate) fn from_strings(values: Vec<String>) -> Dynamic {
+    Dynamic::from(values.into_iter().map(from_string).collect::<Array>())
+}
+
+pub(crate) fn to_string(site: &str, field: &str, value: Dynamic) -> anyhow::Result<String> {
+    let type_name = value.type_name();
+
+    value
+        .try_cast::<ImmutableString>()
+        .map(|value| value.to_string())
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Script field `{}` for site {} must be a string, found: {}",
+                field,
+                site,
+                type_name
+            )
+        })
+}
+
+/// A single string is treated as a list with one entry.
+pub(crate) fn to_strings(site: &str, field: &str, value: Dynamic) -> anyhow::Result<Vec<String>> {
+    if value.is::<()>() {
+        return Ok(Vec::new());
+    }
+
+    if value.is::<Array>() {
+        let values = value.cast::<Array>();
+
+        return values
+            .into_iter()
+            .map(|value| to_string(site, field, value))
+            .collect();
+    }
+
+    to_string(site, field, value).map(|value| vec![value])
+}
+
+pub(crate) fn to_u32(site: &str, field: &str, value: Dynamic) -> anyhow::Result<u32> {
+    if value.is::<INT>() {
+        return Ok(value.cast::<INT>() as u32);
+    }
+
+    to_string(site, field, value)?
+        .trim()
+        .parse::<u32>()
+        .map_err(|err| {
+            anyhow::anyhow!(
+                "Script field `{}` for site {} is not a number: {}",
+                field,
+                site,
+                err
+            )
+        })
+}
+
+/// Returns `None` if the value is `()`.
+pub(crate) fn to_i32(site: &str, field: &str, value: Dynamic) -> anyhow::Result<Option<i32>> {
+    if value.is::<()>() {
+        return Ok(None);
+    }
+
+    if value.is::<INT>() {
+        return Ok(Some(value.cast::<INT>() as i32));
+    }
+
+    to_string(site, field, value)?
+        .trim()
+        .parse::<i32>()
+        .map(Some)
+        .map_err(|err| {
+            anyhow::anyhow!(
+                "Script field `{}` for site {} is not a number: {}",
+                field,
+                site,
+                err
+            )
+        })
+}
+
+/// Returns `None` if the value is `()`.
+pub(crate) fn to_date(
+    site: &str,
+    field: &str,
+    value: Dynamic,
+) -> anyhow::Result<Option<DateTime<Utc>>> {
+    if value.is::<()>() {
+        return Ok(None);
+    }
+
+    let timestamp = if value.is::<INT>() {
+        value.cast::<INT>() as i64
+    } else {
+        to_string(site, field, value)?.trim().parse::<i64>()?
+    };
+
+    Utc.timestamp_opt(timestamp, 0)
+        .single()
+        .map(Some)
+        .ok_or_else(|| anyhow::anyhow!("Unparsable date time for site {}", site))
+}
+
+pub(crate) fn to_language(site: &str, field: &str, value: Dynamic) -> anyhow::Result<Language> {
+    Ok(Language::parse_lossy(&to_string(site, field, value)?))
+}
+
+pub(crate) fn to_rating(site: &str, field: &str, value: Dynamic) -> anyhow::Result<Rating> {
+    match to_string(site, field, value)?
+        .trim()
+        .to_lowercase()
+        .as_str()
+    {
+        "explicit" => Ok(Rating::Explicit),
+        "mature" => Ok(Rating::Mature),
+        "teen" => Ok(Rating::Teen),
+        "general" => Ok(Rating::General),
+        rating => Err(anyhow::anyhow!(
+            "Unknown rating for site {} found: {}",
+            site,
+            rating
+        )),
+    }
+}
+
+pub(crate) fn to_state(site: &str, field: &str, value: Dynamic) -> anyhow::Result<State> {
+    match to_string(site, field, value)?
+        .trim()
+        .to_lowercase()
+        .as_str()
+    {
+        "completed" => Ok(State::Completed),
+        "in-progress" => Ok(State::InProgress),
+        "hiatus" => Ok(State::Hiatus),
+        "abandoned" => Ok(State::Abandoned),
+        state => Err(anyhow::anyhow!(
+            "Unknown story status for site {} found: {}",
+            site,
+            state
+        )),
+    }
+}
+
+/// Tags are either a plain string, a general tag, or a `[type, name]` pair.
+pub(crate) fn to_tags(
+    site: &str,
+    field: &str,
+    value: Dynamic,
+) -> anyhow::Result<Vec<(TagType, String)>> {
+    if value.is::<()>() {
+        return Ok(Vec::new());
+    }
+
+    let values = if value.is::<Array>() {
+        value.cast::<Array>()
+    } else {
+        vec![value]
+    };
+
+    values
+        .into_iter()
+        .map(|value| {
+            if !value.is::<Array>() {
+                return to_string(site, field, value).map(|tag| (TagType::General, tag));
+            }
+
+            let mut pair = value.cast::<Array>().into_iter();
+
+            match (pair.next(), pair.next()) {
+                (Some(typ), Some(tag)) => {
+                    let typ = match to_string(site, field, typ)?.trim().to_lowercase().as_str() {
+                        "warning" => TagType::Warning,
+                        "pairing" => TagType::Pairing,
+                        "character" => TagType::Character,
+                        "general" => TagType::General,
+                        typ => {
+                            return Err(anyhow::anyhow!(
+                                "Unknown tag type for site {} found: {}",
+                                site,
+                                typ
+                            ))
+                        }
+                    };
+
+                    Ok((typ, to_string(site, field, tag)?))
+                }
+                _ => Err(anyhow::anyhow!(
+                    "Script field `{}` for site {} must be a `[type, name]` pair",
+                    field,
+                    site
+                )),
+            }
+        })
+        .collect()
+}
+
+/// Pairings are maps of their `characters`, and if they are `platonic`, which
+/// defaults to `false`.
+pub(crate) fn to_pairings(site: &str, field: &str, value: Dynamic) -> anyhow::Result<Vec<Pairing>> {
+    if value.is::<()>() {
+        return Ok(Vec::new());
+    }
+
+    let values = if value.is::<Array>() {
+        value.cast::<Array>()
+    } else {
+        vec![value]
+    };
+
+    values
+        .into_iter()
+        .map(|value| {
+            let type_name = value.type_name();
+
+            let mut pairing = value.try_cast::<Map>().ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Script field `{}` for site {} must be a map, found: {}",
+                    field,
+                    site,
+                    type_name
+                )
+            })?;
+
+            let characters = match pairing.remove("characters") {
+                Some(characters) => to_strings(site, field, characters)?,
+                None => Vec::new(),
+            };
+
+            let platonic = match pairing.remove("platonic") {
+                Some(platonic) => platonic.try_cast::<bool>().ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Script field `{}` for site {} must have a boolean `platonic`",
+                        field,
+                        site
+                    )
+                })?,
+                None => false,
+            };
+
+            Ok(Pairing {
+                characters,
+                platonic,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,486 @@
+//! Site scrapers defined by RON files and loaded at runtime.
+//!
+//! A definition gives the CSS selectors for each part of a story, with the
+//! [Rhai](https://rhai.rs/) script functions named in the definition used to
+//! turn the selected text into story details.
+
+mod definition;
+mod engine;
+
+pub use self::definition::{
+    ChapterDefinition, Definition, DetailsDefinition, Field, Kind, Note, Place, SeriesDefinition,
+    Urls,
+};
+
+use {
+    crate::{
+        models::{Chapter, Details, Series, TagType},
+        script::engine::ENGINE,
+        sites::{self, SiteRef, SiteScraper},
+        utils::{req, word_count},
+        ScrapeError, Uri,
+    },
+    rhai::{Dynamic, EvalAltResult, Scope, AST},
+    std::{fs, path::Path, str::FromStr, sync::Arc},
+    stry_scraper::Document,
+};
+
+/// Loads every `.ron` site definition in the directory, returning the number
+/// of sites loaded.
+///
+/// Definitions for the hosts of built in sites are skipped, see
+/// [`sites::register`].
+pub fn load<P>(directory: P) -> anyhow::Result<usize>
+where
+    P: AsRef<Path>,
+{
+    let directory = directory.as_ref();
+
+    let mut count = 0;
+
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+
+        if path.extension().and_then(|ext| ext.to_str()) != Some("ron") {
+            continue;
+        }
+
+        let site = fs::read_to_string(&path)?
+            .parse::<ScriptSite>()
+            .map_err(|err| anyhow::anyhow!("Unable to load site {}: {}", path.display(), err))?;
+
+        let name = site.name().to_string();
+
+        if let Err(err) = sites::register(Arc::new(site)) {
+            tracing::warn!("Skipping site definition {}: {}", path.display(), err);
+
+            continue;
+        }
+
+        tracing::info!("Loaded site definition for {}", name);
+
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+#[derive(Clone)]
+pub struct ScriptSite {
+    definition: Arc<Definition>,
+    ast: Arc<AST>,
+}
+
+impl ScriptSite {
+    pub fn new(definition: Definition) -> anyhow::Result<Self> {
+        let ast = ENGINE.compile(&definition.script).map_err(|err| {
+            anyhow::anyhow!(
+                "Unable to compile script for site {}: {}",
+                definition.name,
+                err
+            )
+        })?;
+
+        Ok(Self {
+            definition: Arc::new(definition),
+            ast: Arc::new(ast),
+        })
+    }
+
+    pub fn definition(&self) -> &Definition {
+        &self.definition
+    }
+
+    pub fn chapter_url(&self, id: &str, chapter: u32) -> anyhow::Result<Uri> {
+        let url = self
+            .definition
+            .urls
+            .chapter
+            .replace("{id}", id)
+            .replace("{chapter}", &chapter.to_string())
+            .parse::<Uri>()?;
+
+        Ok(url)
+    }
+
+    pub fn parse_details(&self, html: impl Into<Document>) -> anyhow::Result<Details> {
+        let html = html.into();
+
+        let definition = &self.definition.details;
+        let name = self.name();
+
+        let created = engine::to_date(
+            name,
+            "created",
+            self.field(&html, "created", &definition.created, 1)?,
+        )?
+        .ok_or_else(|| anyhow::anyhow!("Unparsable date time for site {}", name))?;
+
+        let updated = match &definition.updated {
+            Some(field) => {
+                engine::to_date(name, "updated", self.field(&html, "updated", field, 1)?)?
+            }
+            None => None,
+        };
+
+        let mut tags = Vec::new();
+
+        for (key, typ, field) in &[
+            ("warnings", TagType::Warning, &definition.warnings),
+            ("characters", TagType::Character, &definition.characters),
+        ] {
+            if let Some(field) = field {
+                tags.extend(
+                    engine::to_strings(name, key, self.field(&html, key, field, 1)?)?
+                        .into_iter()
+                        .map(|tag| (*typ, tag)),
+                );
+            }
+        }
+
+        if let Some(field) = &definition.tags {
+            tags.extend(engine::to_tags(
+                name,
+                "tags",
+                self.field(&html, "tags", field, 1)?,
+            )?);
+        }
+
+        Ok(Details {
+            name: engine::to_string(
+                name,
+                "name",
+                self.field(&html, "name", &definition.name, 1)?,
+            )?,
+            summary: engine::to_string(
+                name,
+                "summary",
+                self.field(&html, "summary", &definition.summary, 1)?,
+            )?,
+
+            chapters: engine::to_u32(
+                name,
+                "chapters",
+                self.field(&html, "chapters", &definition.chapters, 1)?,
+            )?,
+            language: engine::to_language(
+                name,
+                "language",
+                self.field(&html, "language", &definition.language, 1)?,
+            )?,
+            rating: engine::to_rating(
+                name,
+                "rating",
+                self.field(&html, "rating", &definition.rating, 1)?,
+            )?,
+            state: engine::to_state(
+                name,
+                "state",
+                self.field(&html, "state", &definition.state, 1)?,
+            )?,
+
+            authors: engine::to_strings(
+                name,
+                "authors",
+                self.field(&html, "authors", &definition.authors, 1)?,
+            )?,
+            origins: match &definition.origins {
+                Some(field) => {
+                    engine::to_strings(name, "origins", self.field(&html, "origins", field, 1)?)?
+                }
+                None => Vec::new(),
+            },
+            tags,
+            pairings: match &definition.pairings {
+                Some(field) => {
+                    engine::to_pairings(name, "pairings", self.field(&html, "pairings", field, 1)?)?
+                }
+                None => Vec::new(),
+            },
+
+            series: match &definition.series {
+                Some(series) => self.series(&html, series)?,
+                None => None,
+            },
+
+            created,
+            updated: updated.unwrap_or(created),
+        })
+    }
+
+    pub fn parse_chapter(
+        &self,
+        html: impl Into<Document>,
+        chapter: u32,
+    ) -> anyhow::Result<Chapter> {
+        let html = html.into();
+
+        let definition = &self.definition.chapter;
+        let name = self.name();
+
+        let main = engine::to_string(
+            name,
+            "main",
+            self.field(&html, "main", &definition.main, chapter)?,
+        )?;
+
+        let chapters = match &definition.chapters {
+            Some(field) => Some(engine::to_u32(
+                name,
+                "chapters",
+                self.field(&html, "chapters", field, chapter)?,
+            )?),
+            None => None,
+        };
+
+        let notes = |key: &str, notes: &[Note]| -> anyhow::Result<String> {
+            let mut parts = Vec::new();
+
+            for note in notes {
+                let used = match note.only {
+                    Some(Place::First) => chapter == 1,
+                    Some(Place::Last) => chapters == Some(chapter),
+                    None => true,
+                };
+
+                if !used {
+                    continue;
+                }
+
+                for text in
+                    engine::to_strings(name, key, self.field(&html, key, &note.field, chapter)?)?
+                {
+                    let text = text.trim();
+
+                    if !text.is_empty() {
+                        parts.push(text.to_string());
+                    }
+                }
+            }
+
+            Ok(parts.join("\n\n"))
+        };
+
+        Ok(Chapter {
+            name: engine::to_string(
+                name,
+                "name",
+                self.field(&html, "name", &definition.name, chapter)?,
+            )?,
+            words: word_count(&main),
+            pre: notes("pre", &definition.pre)?,
+            post: notes("post", &definition.post)?,
+            main,
+        })
+    }
+
+    fn series(
+        &self,
+        html: &Document,
+        definition: &SeriesDefinition,
+    ) -> anyhow::Result<Option<Series>> {
+        let name = self.name();
+
+        let series = self.field(html, "series", &definition.name, 1)?;
+
+        if series.is::<()>() {
+            return Ok(None);
+        }
+
+        let summary = match &definition.summary {
+            Some(field) => {
+                let value = self.field(html, "series summary", field, 1)?;
+
+                if value.is::<()>() {
+                    String::new()
+                } else {
+                    engine::to_string(name, "series summary", value)?
+                }
+            }
+            None => String::new(),
+        };
+
+        let place = match &definition.place {
+            Some(field) => engine::to_i32(
+                name,
+                "series place",
+                self.field(html, "series place", field, 1)?,
+            )?,
+            None => None,
+        };
+
+        Ok(Some(Series {
+            name: engine::to_string(name, "series", series)?,
+            summary,
+            place,
+        }))
+    }
+
+    /// Selects and transforms a field, falling back to the field's fallback
+    /// when nothing is selected.
+    fn field(
+        &self,
+        html: &Document,
+        key: &str,
+        field: &Field,
+        chapter: u32,
+    ) -> anyhow::Result<Dynamic> {
+        let selector = field.selector.replace("{chapter}", &chapter.to_string());
+
+        let excluded = match &field.exclude {
+            Some(exclude) => html.select(exclude.replace("{chapter}", &chapter.to_string())),
+            None => Vec::new(),
+        };
+
+        let values = html
+            .select(selector.as_str())
+            .into_iter()
+            .filter(|ele| !excluded.contains(ele))
+            .filter_map(|ele| match &field.attr {
+                Some(attr) => ele.attr(attr),
+                None => match field.kind {
+                    Kind::Text => ele.text(),
+                    Kind::Html => ele.html(),
+                    Kind::InnerHtml => ele.inner_html(),
+                },
+            })
+            .collect::<Vec<String>>();
+
+        if values.is_empty() {
+            if let Some(fallback) = &field.fallback {
+                return self.field(html, key, fallback, chapter);
+            }
+        }
+
+        let value = if field.markdown {
+            if values.is_empty() && (field.optional || field.all) {
+                Dynamic::from(())
+            } else if values.is_empty() {
+                return Err(ScrapeError::layout_changed(self.name(), selector).into());
+            } else {
+                engine::from_string(stry_remark::parse(values.concat())?)
+            }
+        } else if field.all {
+            engine::from_strings(values)
+        } else {
+            match values.into_iter().next() {
+                Some(value) => engine::from_string(value),
+                None if field.optional => Dynamic::from(()),
+                None => return Err(ScrapeError::layout_changed(self.name(), selector).into()),
+            }
+        };
+
+        match &field.transform {
+            Some(transform) => self
+                .call(transform, value)
+                .map_err(|err| anyhow::anyhow!("Unable to transform field `{}`: {}", key, err)),
+            None => Ok(value),
+        }
+    }
+
+    fn call(&self, function: &str, value: Dynamic) -> anyhow::Result<Dynamic> {
+        ENGINE
+            .call_fn::<_, Dynamic>(&mut Scope::new(), &self.ast, function, (value,))
+            .map_err(|err| match *err {
+                EvalAltResult::ErrorTooManyOperations(_)
+                | EvalAltResult::ErrorStackOverflow(_)
+                | EvalAltResult::ErrorDataTooLarge(..) => anyhow::anyhow!(
+                    "Script function `{}` for site {} was stopped for going over its limits: {}",
+                    function,
+                    self.name(),
+                    err
+                ),
+                _ => anyhow::anyhow!(
+                    "Script function `{}` for site {} failed: {}",
+                    function,
+                    self.name(),
+                    err
+                ),
+            })
+    }
+}
+
+impl FromStr for ScriptSite {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let definition: Definition = ron::de::from_str(s)?;
+
+        Self::new(definition)
+    }
+}
+
+#[async_trait::async_trait]
+impl SiteScraper for ScriptSite {
+    fn name(&self) -> &str {
+        &self.definition.name
+    }
+
+    fn hosts(&self) -> Vec<&str> {
+        self.definition.hosts.iter().map(String::as_str).collect()
+    }
+
+    fn id_from_url(&self, url: &Uri) -> anyhow::Result<String> {
+        let id = self.call("id", engine::from_string(url.path().to_string()))?;
+
+        if id.is::<()>() {
+            return Err(anyhow::anyhow!("Unable to find story ID in: {}", url));
+        }
+
+        engine::to_string(self.name(), "id", id)
+    }
+
+    fn details_url(&self, id: &str) -> anyhow::Result<Uri> {
+        let url = self
+            .definition
+            .urls
+            .details
+            .replace("{id}", id)
+            .parse::<Uri>()?;
+
+        Ok(url)
+    }
+
+    async fn get_details(&self, story: &mut SiteRef) -> anyhow::Result<Details> {
+        let document = story.document().await?;
+        let site = self.clone();
+
+        let details = tokio::task::spawn_blocking(move || site.parse_details(document))
+            .await
+            .expect("Thread pool closed")?;
+
+        Ok(details)
+    }
+
+    async fn get_chapter(&self, story: &mut SiteRef, chapter: u32) -> anyhow::Result<Chapter> {
+        let url = self.chapter_url(story.id(), chapter)?;
+
+        // Sites that show the whole story on one page reuse the details page
+        let body = if self.details_url(story.id())? == url {
+            story.document().await?
+        } else {
+            Arc::new(req(&url).await?)
+        };
+
+        let site = self.clone();
+
+        let chapter = tokio::task::spawn_blocking(move || site.parse_chapter(body, chapter))
+            .await
+            .expect("Thread pool closed")?;
+
+        Ok(chapter)
+    }
+
+    fn parse_details(&self, html: String) -> anyhow::Result<Details> {
+        ScriptSite::parse_details(self, html)
+    }
+
+    fn parse_chapter(
+        &self,
+        html: String,
+        chapter: u32,
+        _post: Option<&str>,
+    ) -> anyhow::Result<Chapter> {
+        ScriptSite::parse_chapter(self, html, chapter)
+    }
+}
@@ -1,21 +1,49 @@
 use {
     crate::{
-        models::{Chapter, Details, Language, Rating, State, Story},
+        http::{self, Client, StatusError},
+        models::{
+            Chapter, Details, Language, ListedStory, Listing, Pairing, Rating, Series, State,
+            Story, TagType, WorkerSite,
+        },
+        sites::{SiteRef, SiteScraper},
         utils::{req, word_count},
-        Uri,
+        ScrapeError, Uri,
     },
     chrono::{DateTime, NaiveDate, Utc},
     std::{fmt::Write, sync::Arc},
+    stry_config::Account,
     stry_scraper::{Document, Element},
 };
 
 const NAME: &str = "archive of our own";
+const HOST: &str = "archiveofourown.org";
+const BASE_URL: &str = "https://archiveofourown.org";
 
-const MULTIPLE_CHAPTER_NAME: &str = r#"#chapters > .chapter > div[role="complementary"] > h3"#;
+const LOGIN_URL: &str = "https://archiveofourown.org/users/login";
+const LOGIN_INPUTS: &str = "form#new_user input";
+
+/// Set once logged in, and kept while the session is valid.
+const SESSION_COOKIE: &str = "user_credentials";
+
+const RESTRICTED: &str = "This work is only available to registered users of the Archive";
+
+const MULTIPLE_CHAPTER_NAME: &str = r#" > div[role="complementary"] > h3"#;
 const SINGLE_CHAPTER_NAME: &str = r#"#workskin > .preface > .title"#;
 const CHAPTER_SINGLE: &str = r#"#chapters .userstuff > p"#;
 const CHAPTER_MULTI_START: &str = r#"#chapters > #chapter-"#;
-const CHAPTER_MULTI_END: &str = r#" .userstuff > p"#;
+const CHAPTER_MULTI_END: &str = r#" > .userstuff > p"#;
+
+const CHAPTERS: &str = "#chapters > .chapter";
+const CHAPTER_SUMMARY: &str = " > .preface > .summary > blockquote";
+const CHAPTER_NOTES: &str = " > .preface > .notes > blockquote";
+
+/// The work's notes, shown before the first chapter and after the last.
+const WORK_NOTES: &str = "#workskin > .preface > .notes > blockquote";
+const WORK_END_NOTES: &str = "#work_endnotes > blockquote";
+
+/// Works and bookmarks share the same blurb, the first link is the work.
+const LISTING_STORIES: &str = "li.blurb > div.header > h4.heading > a";
+const LISTING_NEXT: &str = "ol.pagination > li.next > a";
 
 const STORY_AUTHOR: &str = r#"#workskin > .preface > .byline.heading > a[rel="author"]"#;
 const STORY_SUMMARY: &str = "#workskin > .preface > .summary > blockquote";
@@ -23,28 +51,40 @@ const STORY_NAME: &str = "#workskin > .preface > .title";
 
 const STORY_RATING: &str = ".work > .rating.tags > ul > li > .tag";
 const STORY_ORIGINS: &str = ".work > .fandom.tags > ul > li > .tag";
+const STORY_WARNINGS: &str = ".work > .warning.tags > ul > li > .tag";
+const STORY_RELATIONSHIPS: &str = ".work > .relationship.tags > ul > li > .tag";
+const STORY_CHARACTERS: &str = ".work > .character.tags > ul > li > .tag";
+const STORY_FREEFORMS: &str = ".work > .freeform.tags > ul > li > .tag";
+
+const STORY_SERIES: &str = "dl.work > dd.series > span.series > span.position";
 
 const STORY_STATS_CHAPTERS: &str = "dl.work > dd.stats > dl.stats > dd.chapters";
 const STORY_STATS_LANGUAGE: &str = "dl.work > dd.language";
 const STORY_STATS_CREATED: &str = "dl.work > dd.stats > dl.stats > dd.published";
 const STORY_STATS_UPDATED: &str = "dl.work > dd.stats > dl.stats > dd.status";
 
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Unable to log in to archiveofourown.org as {username}")]
+    Login { username: String },
+}
+
 pub fn id_from_url(url: &Uri) -> anyhow::Result<String> {
     url.path()
         .split('/')
         .filter(|s| !s.is_empty())
         .nth(1)
         .map(String::from)
-        .ok_or_else(|| anyhow::anyhow!("Unable to find story ID in: {}", url))
+        .ok_or_else(|| {
+            ScrapeError::NotFound {
+                url: url.to_string(),
+            }
+            .into()
+        })
 }
 
 pub async fn scrape(url: &Uri) -> anyhow::Result<Story> {
-    let id = url
-        .path()
-        .split('/')
-        .filter(|s| !s.is_empty())
-        .nth(1)
-        .expect("No story ID found in URL");
+    let id = id_from_url(url)?;
 
     tracing::info!("[{}] Scraping initial details", url);
 
@@ -87,12 +127,141 @@ pub async fn scrape(url: &Uri) -> anyhow::Result<Story> {
     Ok(story)
 }
 
+/// Checks for the notice shown instead of works that are restricted to
+/// registered users.
+pub fn is_restricted(html: &str) -> bool {
+    html.contains(RESTRICTED)
+}
+
+/// Gets the token needed to submit the login form.
+pub fn get_authenticity_token(html: impl Into<Document>) -> anyhow::Result<String> {
+    let html = html.into();
+
+    html.select(LOGIN_INPUTS)
+        .into_iter()
+        .find(|ele| ele.attr("name").as_deref() == Some("authenticity_token"))
+        .and_then(|ele| ele.attr("value"))
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Sector element for site {} not found: {}",
+                NAME,
+                LOGIN_INPUTS
+            )
+        })
+}
+
+/// Logs in with the account configured for the site, unless there is no
+/// account or the client already has a session.
+pub async fn login(client: &Client) -> anyhow::Result<()> {
+    let (username, password) = match client.account(HOST) {
+        Some(Account::Login { username, password }) => (username, password),
+        _ => return Ok(()),
+    };
+
+    if client.cookies().get(HOST, SESSION_COOKIE).is_some() {
+        return Ok(());
+    }
+
+    tracing::info!("[{}] Logging in as {}", HOST, username);
+
+    let url = LOGIN_URL.parse::<Uri>()?;
+
+    let page = client.get_uncached(&url).await?;
+
+    let token = tokio::task::spawn_blocking(|| get_authenticity_token(page))
+        .await
+        .expect("Thread pool closed")?;
+
+    let res = client
+        .post_form(
+            &url,
+            &[
+                ("authenticity_token", token.as_str()),
+                ("user[login]", username.as_str()),
+                ("user[password]", password.as_str()),
+                ("user[remember_me]", "1"),
+                ("commit", "Log in"),
+            ],
+        )
+        .await?;
+
+    // Failed logins show the form again instead of redirecting
+    let redirected = res.status.is_redirection()
+        && !res
+            .location
+            .as_deref()
+            .map(|location| location.contains("/users/login"))
+            .unwrap_or(true);
+
+    if !redirected || client.cookies().get(HOST, SESSION_COOKIE).is_none() {
+        return Err(Error::Login {
+            username: username.clone(),
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Gets the work's page, logging in first if there is an account, returning
+/// [`ScrapeError::Locked`] if the work can't be viewed.
+async fn document(story: &mut SiteRef) -> anyhow::Result<Arc<String>> {
+    login(&http::client()).await?;
+
+    let url = story.site().details_url(story.id())?.to_string();
+
+    let document = match story.document().await {
+        Ok(document) => document,
+        Err(err) => {
+            // Restricted works redirect logged out users to the login page
+            let login_redirect = err
+                .downcast_ref::<StatusError>()
+                .and_then(|err| err.location.as_deref())
+                .map(|location| location.contains("/users/login"))
+                .unwrap_or(false);
+
+            if login_redirect {
+                return Err(ScrapeError::Locked { url }.into());
+            }
+
+            return Err(err);
+        }
+    };
+
+    if is_restricted(&document) {
+        return Err(ScrapeError::Locked { url }.into());
+    }
+
+    Ok(document)
+}
+
 pub fn get_details(html: impl Into<Document>) -> anyhow::Result<Details> {
     let html = html.into();
 
     let authors = stry_scraper::string_vec(&html, STORY_AUTHOR, NAME)?;
     let origins = stry_scraper::string_vec(&html, STORY_ORIGINS, NAME)?;
 
+    let mut tags = Vec::new();
+
+    for (typ, selector) in &[
+        (TagType::Warning, STORY_WARNINGS),
+        (TagType::Character, STORY_CHARACTERS),
+        (TagType::General, STORY_FREEFORMS),
+    ] {
+        tags.extend(
+            stry_scraper::string_vec(&html, *selector, NAME)?
+                .into_iter()
+                .map(|tag| (*typ, tag.trim().to_string())),
+        );
+    }
+
+    let pairings = stry_scraper::string_vec(&html, STORY_RELATIONSHIPS, NAME)?
+        .iter()
+        .map(|relationship| parse_pairing(relationship))
+        .collect::<Vec<_>>();
+
+    let series = get_series(&html);
+
     let name = stry_scraper::string(&html, STORY_NAME, NAME)?
         .trim()
         .to_string();
@@ -100,15 +269,30 @@ pub fn get_details(html: impl Into<Document>) -> anyhow::Result<Details> {
 
     let chapter_expected = stry_scraper::string(&html, STORY_STATS_CHAPTERS, NAME)?;
 
-    let chapters: u32 = chapter_expected
-        .split('/')
-        .next()
-        .and_then(|s| s.parse::<u32>().ok())
-        .unwrap();
+    // Chapters are shown as `current/expected`, expected is `?` if unknown
+    let (current, expected) = {
+        let mut split = chapter_expected.trim().split('/');
+
+        match (split.next(), split.next()) {
+            (Some(current), Some(expected)) => (current, expected),
+            _ => return Err(ScrapeError::layout_changed(NAME, STORY_STATS_CHAPTERS).into()),
+        }
+    };
 
-    let language: Language = match stry_scraper::string(&html, STORY_STATS_LANGUAGE, NAME)?.trim() {
-        "English" => Language::English,
-        _ => unreachable!(),
+    let chapters: u32 = current
+        .parse()
+        .map_err(|_| ScrapeError::layout_changed(NAME, STORY_STATS_CHAPTERS))?;
+
+    // AO3 shows the language in its native form but tags it with the code
+    let language: Language = match html
+        .select(STORY_STATS_LANGUAGE)
+        .first()
+        .and_then(|ele| ele.attr("lang"))
+        .as_deref()
+        .and_then(|lang| lang.split('-').next())
+    {
+        Some(lang) => Language::parse_lossy(lang),
+        None => Language::parse_lossy(&stry_scraper::string(&html, STORY_STATS_LANGUAGE, NAME)?),
     };
 
     let rating: Rating = match stry_scraper::string(&html, STORY_RATING, NAME)?.trim() {
@@ -116,20 +300,15 @@ pub fn get_details(html: impl Into<Document>) -> anyhow::Result<Details> {
         "Mature" => Rating::Mature,
         "Teen And Up Audiences" => Rating::Teen,
         "General Audiences" => Rating::General,
-        _ => unreachable!(),
+        // AO3 hides unrated works behind the same warning as explicit ones
+        "Not Rated" => Rating::Explicit,
+        _ => return Err(ScrapeError::layout_changed(NAME, STORY_RATING).into()),
     };
 
-    let state = {
-        let mut split = chapter_expected.split('/');
-
-        let current: &str = split.next().unwrap();
-        let expected: &str = split.next().unwrap();
-
-        if current == expected {
-            State::Completed
-        } else {
-            State::InProgress
-        }
+    let state = if current == expected {
+        State::Completed
+    } else {
+        State::InProgress
     };
 
     let created: Option<DateTime<Utc>> = NaiveDate::parse_from_str(
@@ -165,7 +344,10 @@ pub fn get_details(html: impl Into<Document>) -> anyhow::Result<Details> {
 
         authors,
         origins,
-        tags: Vec::new(),
+        tags,
+        pairings,
+
+        series,
 
         created: created
             .ok_or_else(|| anyhow::anyhow!("Unparsable date time for site {}", NAME))?,
@@ -174,9 +356,104 @@ pub fn get_details(html: impl Into<Document>) -> anyhow::Result<Details> {
     })
 }
 
+/// Splits a relationship tag into its characters, `/` is used for romantic
+/// relationships and `&` for platonic ones.
+pub fn parse_pairing(relationship: &str) -> Pairing {
+    let platonic = !relationship.contains('/');
+
+    let characters = relationship
+        .split(if platonic { '&' } else { '/' })
+        .map(|character| character.trim().to_string())
+        .filter(|character| !character.is_empty())
+        .collect();
+
+    Pairing {
+        characters,
+        platonic,
+    }
+}
+
+/// Gets the first series the work is part of, with the work's place in it.
+fn get_series(html: &Document) -> Option<Series> {
+    let position = html.select(STORY_SERIES).into_iter().next()?;
+
+    let name = position.select("a").first()?.text()?.trim().to_string();
+
+    // `Part 2 of `, followed by the series link
+    let place = position
+        .text()
+        .and_then(|text| text.split_whitespace().nth(1)?.parse::<i32>().ok());
+
+    Some(Series {
+        name,
+        summary: String::new(),
+        place,
+    })
+}
+
+/// Checks if the URL is a user's works or bookmarks (including a pseud's),
+/// or a collection's works or bookmarks.
+pub fn is_listing(url: &Uri) -> bool {
+    let segments = url
+        .path()
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>();
+
+    match segments.as_slice() {
+        ["users", _, "works"] | ["users", _, "bookmarks"] => true,
+        ["users", _, "pseuds", _, "works"] | ["users", _, "pseuds", _, "bookmarks"] => true,
+        ["collections", _, "works"] | ["collections", _, "bookmarks"] => true,
+        _ => false,
+    }
+}
+
+/// Gets the works from a page of a listing, bookmarked series and external
+/// works are skipped.
+pub fn get_listing(html: impl Into<Document>) -> anyhow::Result<Listing> {
+    let html = html.into();
+
+    let mut stories = Vec::new();
+
+    for link in html.select(LISTING_STORIES) {
+        let href = match link.attr("href") {
+            Some(href) => href,
+            None => continue,
+        };
+
+        let segments = href
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>();
+
+        if let ["works", id] = segments.as_slice() {
+            stories.push(ListedStory {
+                site: WorkerSite::ArchiveOfOurOwn,
+                name: link.text().unwrap_or_default().trim().to_string(),
+                url: format!("{}/works/{}", BASE_URL, id),
+            });
+        }
+    }
+
+    let next = html
+        .select(LISTING_NEXT)
+        .first()
+        .and_then(|ele| ele.attr("href"))
+        .map(|href| format!("{}{}", BASE_URL, href));
+
+    Ok(Listing { stories, next })
+}
+
+/// Gets a chapter from either a chapter's page or the full work page.
+///
+/// The chapter's summary and beginning notes are put into `pre` and its end
+/// notes into `post`, with the work's notes added to the first and last
+/// chapters.
 pub fn get_chapter(html: impl Into<Document>, chapter: u32) -> anyhow::Result<Chapter> {
     let html = html.into();
 
+    let last = html.select(CHAPTERS).len().max(1) as u32 == chapter;
+
     let multi = html.select(format!(
         "{}{}{}",
         CHAPTER_MULTI_START, chapter, CHAPTER_MULTI_END
@@ -203,31 +480,159 @@ pub fn get_chapter(html: impl Into<Document>, chapter: u32) -> anyhow::Result<Ch
 
                 buffer
             })
-            .expect("[chapter_text] HTML is missing the chapter text node, did the html change?"),
+            .ok_or_else(|| ScrapeError::layout_changed(NAME, CHAPTER_SINGLE))?,
     )?
     .trim()
     .replace('“', "\"")
     .replace('”', "\"");
 
-    let name: String = stry_scraper::string(&html, MULTIPLE_CHAPTER_NAME, NAME)
-        .or_else(|_| stry_scraper::string(&html, SINGLE_CHAPTER_NAME, NAME))
-        .map(|title| {
-            let mut title = title.trim().to_string();
+    let multi_name = html
+        .select(format!(
+            "{}{}{}",
+            CHAPTER_MULTI_START, chapter, MULTIPLE_CHAPTER_NAME
+        ))
+        .first()
+        .and_then(|ele| ele.text());
+
+    let name: String = match multi_name {
+        Some(name) => Ok(name),
+        None => stry_scraper::string(&html, SINGLE_CHAPTER_NAME, NAME),
+    }
+    .map(|title| {
+        let mut title = title.trim().to_string();
 
-            if title.starts_with(':') {
-                title.remove(0);
+        if title.starts_with(':') {
+            title.remove(0);
 
-                title.trim().to_string()
-            } else {
-                title
-            }
-        })?;
+            title.trim().to_string()
+        } else {
+            title
+        }
+    })?;
+
+    let mut pre = Vec::new();
+    let mut post = Vec::new();
+
+    if chapter == 1 {
+        notes(html.select(WORK_NOTES), &mut pre)?;
+    }
+
+    notes(
+        html.select(format!(
+            "{}{}{}",
+            CHAPTER_MULTI_START, chapter, CHAPTER_SUMMARY
+        )),
+        &mut pre,
+    )?;
+
+    // Beginning and end notes have the same classes, except for `end`
+    let (end, begin): (Vec<Element>, Vec<Element>) = html
+        .select(format!(
+            "{}{}{}",
+            CHAPTER_MULTI_START, chapter, CHAPTER_NOTES
+        ))
+        .into_iter()
+        .partition(|ele| {
+            ele.parent()
+                .and_then(|parent| parent.attr("class"))
+                .map(|class| class.split_whitespace().any(|class| class == "end"))
+                .unwrap_or(false)
+        });
+
+    notes(begin, &mut pre)?;
+    notes(end, &mut post)?;
+
+    if last {
+        notes(html.select(WORK_END_NOTES), &mut post)?;
+    }
 
     Ok(Chapter {
         name,
         words: word_count(&content),
-        pre: String::new(),
-        post: String::new(),
+        pre: pre.join("\n\n"),
+        post: post.join("\n\n"),
         main: content,
     })
 }
+
+/// Converts the summary or notes blocks to markdown, skipping empty blocks.
+fn notes(elements: Vec<Element>, parts: &mut Vec<String>) -> anyhow::Result<()> {
+    for html in elements.into_iter().filter_map(|ele| ele.inner_html()) {
+        let text = stry_remark::parse(html)?.trim().to_string();
+
+        if !text.is_empty() {
+            parts.push(text);
+        }
+    }
+
+    Ok(())
+}
+
+pub struct ArchiveOfOurOwn;
+
+#[async_trait::async_trait]
+impl SiteScraper for ArchiveOfOurOwn {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn hosts(&self) -> Vec<&str> {
+        vec!["archiveofourown.org", "www.archiveofourown.org"]
+    }
+
+    fn id_from_url(&self, url: &Uri) -> anyhow::Result<String> {
+        id_from_url(url)
+    }
+
+    fn details_url(&self, id: &str) -> anyhow::Result<Uri> {
+        let url = format!(
+            "https://archiveofourown.org/works/{}?view_full_work=true",
+            id
+        )
+        .parse::<Uri>()?;
+
+        Ok(url)
+    }
+
+    async fn get_details(&self, story: &mut SiteRef) -> anyhow::Result<Details> {
+        let document = document(story).await?;
+
+        let details = tokio::task::spawn_blocking(|| get_details(document))
+            .await
+            .expect("Thread pool closed")?;
+
+        Ok(details)
+    }
+
+    async fn get_chapter(&self, story: &mut SiteRef, chapter: u32) -> anyhow::Result<Chapter> {
+        // The details page is the full work, so every chapter is already on it
+        let document = document(story).await?;
+
+        let chapter = tokio::task::spawn_blocking(move || get_chapter(document, chapter))
+            .await
+            .expect("Thread pool closed")?;
+
+        Ok(chapter)
+    }
+
+    fn parse_details(&self, html: String) -> anyhow::Result<Details> {
+        get_details(html)
+    }
+
+    fn parse_chapter(
+        &self,
+        html: String,
+        chapter: u32,
+        _post: Option<&str>,
+    ) -> anyhow::Result<Chapter> {
+        get_chapter(html, chapter)
+    }
+
+    fn is_listing(&self, url: &Uri) -> bool {
+        is_listing(url)
+    }
+
+    fn get_listing(&self, _url: &Uri, html: String) -> anyhow::Result<Listing> {
+        get_listing(html)
+    }
+}
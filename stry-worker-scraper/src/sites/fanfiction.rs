@@ -1,8 +1,11 @@
 use {
     crate::{
-        models::{Chapter, Details, Language, Rating, State, Story},
-        utils::{req, sleep, word_count},
-        Uri,
+        models::{
+            Chapter, Details, Language, ListedStory, Listing, Rating, State, Story, WorkerSite,
+        },
+        sites::{SiteRef, SiteScraper},
+        utils::{req, word_count},
+        ScrapeError, Uri,
     },
     chrono::{TimeZone, Utc},
     fenn::StringExt,
@@ -11,6 +14,7 @@ use {
 };
 
 const NAME: &str = "fanfiction";
+const BASE_URL: &str = "https://www.fanfiction.net";
 
 const CHAPTER_NAME: &str = "select#chap_select > option[selected]";
 const CHAPTER_TEXT: &str = "#storytext";
@@ -24,22 +28,28 @@ const STORY_SUMMARY: &str = "#profile_top > div.xcontrast_txt";
 const STORY_NAME: &str = "#profile_top > b.xcontrast_txt";
 const STORY_ORIGINS: &str = "#pre_story_links > span.lc-left > a.xcontrast_txt";
 
+/// Author pages also list the author's favorites, only their own stories are
+/// wanted.
+const LISTING_AUTHOR_STORIES: &str = "div.z-list.mystories > a.stitle";
+const LISTING_COMMUNITY_STORIES: &str = "div.z-list > a.stitle";
+const LISTING_PAGES: &str = "center > a";
+
 pub fn id_from_url(url: &Uri) -> anyhow::Result<String> {
     url.path()
         .split('/')
         .filter(|s| !s.is_empty())
         .nth(1)
         .map(String::from)
-        .ok_or_else(|| anyhow::anyhow!("Unable to find story ID in: {}", url))
+        .ok_or_else(|| {
+            ScrapeError::NotFound {
+                url: url.to_string(),
+            }
+            .into()
+        })
 }
 
 pub async fn scrape(url: &Uri) -> anyhow::Result<Story> {
-    let id = url
-        .path()
-        .split('/')
-        .filter(|s| !s.is_empty())
-        .nth(1)
-        .expect("No story ID found in URL");
+    let id = id_from_url(url)?;
 
     tracing::info!("[{}] Scraping initial details", url);
 
@@ -59,8 +69,6 @@ pub async fn scrape(url: &Uri) -> anyhow::Result<Story> {
         for page in 1..=chapters {
             tracing::info!("[{}] Scraping chapter {}", url, page);
 
-            sleep().await?;
-
             let url = format!("https://www.fanfiction.net/s/{}/{}", id, page)
                 .as_str()
                 .parse()?;
@@ -76,8 +84,6 @@ pub async fn scrape(url: &Uri) -> anyhow::Result<Story> {
     } else {
         tracing::info!("[{}] Scraping chapter {}", url, 1);
 
-        sleep().await?;
-
         let url = format!("https://www.fanfiction.net/s/{}/{}", id, 1)
             .as_str()
             .parse()?;
@@ -215,10 +221,7 @@ pub fn get_details(body: impl Into<Document>) -> anyhow::Result<Details> {
         }
 
         if i == words - 2 {
-            language = match s {
-                "English" => Language::English,
-                _ => unreachable!(),
-            };
+            language = Language::parse_lossy(s);
         }
 
         if s.starts_with("Status: ") {
@@ -247,6 +250,9 @@ pub fn get_details(body: impl Into<Document>) -> anyhow::Result<Details> {
         authors: vec![author],
         origins,
         tags: Vec::new(),
+        pairings: Vec::new(),
+
+        series: None,
 
         created: created
             .ok_or_else(|| anyhow::anyhow!("Unparsable date time for site {}", NAME))?,
@@ -255,6 +261,63 @@ pub fn get_details(body: impl Into<Document>) -> anyhow::Result<Details> {
     })
 }
 
+/// Checks if the URL is an author's page or a community.
+pub fn is_listing(url: &Uri) -> bool {
+    let segments = url
+        .path()
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>();
+
+    matches!(segments.as_slice(), ["u", _, ..] | ["community", _, _, ..])
+}
+
+/// Gets the stories from an author's page or a page of a community, author
+/// pages are never split into pages.
+pub fn get_listing(url: &Uri, body: impl Into<Document>) -> anyhow::Result<Listing> {
+    let html = body.into();
+
+    let selector = if url.path().starts_with("/community/") {
+        LISTING_COMMUNITY_STORIES
+    } else {
+        LISTING_AUTHOR_STORIES
+    };
+
+    let mut stories = Vec::new();
+
+    for link in html.select(selector) {
+        let href = match link.attr("href") {
+            Some(href) => href,
+            None => continue,
+        };
+
+        // `/s/{id}/{chapter}/{title}`
+        let id = match href.split('/').filter(|s| !s.is_empty()).nth(1) {
+            Some(id) => id.to_string(),
+            None => continue,
+        };
+
+        stories.push(ListedStory {
+            site: WorkerSite::FanFictionNet,
+            name: link.text().unwrap_or_default().trim().to_string(),
+            url: format!("{}/s/{}", BASE_URL, id),
+        });
+    }
+
+    let next = html
+        .select(LISTING_PAGES)
+        .into_iter()
+        .find(|ele| {
+            ele.text()
+                .map(|text| text.trim().starts_with("Next"))
+                .unwrap_or(false)
+        })
+        .and_then(|ele| ele.attr("href"))
+        .map(|href| format!("{}{}", BASE_URL, href));
+
+    Ok(Listing { stories, next })
+}
+
 pub fn get_chapter(body: impl Into<Document>) -> anyhow::Result<Chapter> {
     let html = body.into();
 
@@ -262,28 +325,89 @@ pub fn get_chapter(body: impl Into<Document>) -> anyhow::Result<Chapter> {
         html.select(CHAPTER_TEXT)
             .first()
             .and_then(|node| node.inner_html())
-            .expect("[chapter_text] HTML is missing the chapter text node, did the html change?"),
+            .ok_or_else(|| ScrapeError::layout_changed(NAME, CHAPTER_TEXT))?,
     )?;
 
     main.trim_end();
 
+    // Single chapter stories use the story's title
+    let name = match html.select(CHAPTER_NAME).first().and_then(|cn| cn.text()) {
+        Some(cn) => cn.split(' ').skip(1).collect::<Vec<_>>().join(" "),
+        None => stry_scraper::string(&html, STORY_NAME, NAME)?,
+    };
+
     Ok(Chapter {
-        name: html
-            .select(CHAPTER_NAME)
-            .first()
-            .and_then(|cn| cn.text())
-            .map(|cn| cn.split(' ').skip(1).collect::<Vec<_>>().join(" "))
-            .or_else(|| {
-                Some(
-                    stry_scraper::string(&html, STORY_NAME, NAME).expect(
-                        "[chapter_name] Unable to use story title as fallback chapter title",
-                    ),
-                )
-            })
-            .expect("[chapter_name] No text in selected element"),
+        name,
         words: word_count(&main),
         pre: String::new(),
         post: String::new(),
         main,
     })
 }
+
+pub struct FanFictionNet;
+
+#[async_trait::async_trait]
+impl SiteScraper for FanFictionNet {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn hosts(&self) -> Vec<&str> {
+        vec!["fanfiction.net", "www.fanfiction.net", "m.fanfiction.net"]
+    }
+
+    fn id_from_url(&self, url: &Uri) -> anyhow::Result<String> {
+        id_from_url(url)
+    }
+
+    fn details_url(&self, id: &str) -> anyhow::Result<Uri> {
+        let url = format!("https://www.fanfiction.net/s/{}/{}", id, 1).parse::<Uri>()?;
+
+        Ok(url)
+    }
+
+    async fn get_details(&self, story: &mut SiteRef) -> anyhow::Result<Details> {
+        let document = story.document().await?;
+
+        let details = tokio::task::spawn_blocking(|| get_details(document))
+            .await
+            .expect("Thread pool closed")?;
+
+        Ok(details)
+    }
+
+    async fn get_chapter(&self, story: &mut SiteRef, chapter: u32) -> anyhow::Result<Chapter> {
+        let url =
+            format!("https://www.fanfiction.net/s/{}/{}", story.id(), chapter).parse::<Uri>()?;
+
+        let body = req(&url).await?;
+
+        let chapter = tokio::task::spawn_blocking(|| get_chapter(body))
+            .await
+            .expect("Thread pool closed")?;
+
+        Ok(chapter)
+    }
+
+    fn parse_details(&self, html: String) -> anyhow::Result<Details> {
+        get_details(html)
+    }
+
+    fn parse_chapter(
+        &self,
+        html: String,
+        _chapter: u32,
+        _post: Option<&str>,
+    ) -> anyhow::Result<Chapter> {
+        get_chapter(html)
+    }
+
+    fn is_listing(&self, url: &Uri) -> bool {
+        is_listing(url)
+    }
+
+    fn get_listing(&self, url: &Uri, html: String) -> anyhow::Result<Listing> {
+        get_listing(url, html)
+    }
+}
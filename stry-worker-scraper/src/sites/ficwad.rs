@@ -0,0 +1,350 @@
+use {
+    crate::{
+        models::{Chapter, Details, Language, Rating, State, Story, TagType},
+        sites::{SiteRef, SiteScraper},
+        utils::{req, word_count},
+        Uri,
+    },
+    chrono::{TimeZone, Utc},
+    std::sync::Arc,
+    stry_scraper::Document,
+};
+
+const NAME: &str = "ficwad";
+
+const CHAPTER_LIST: &str = "#chapters > ul > li > h4 > a";
+const CHAPTER_SELECT: &str = "#chapterlist > select > option";
+const CHAPTER_TEXT: &str = "#storytext";
+
+const STORY_AUTHOR: &str = "#story > .storylist > .title > .author > a";
+const STORY_DATES: &str = "#story > .storylist > p.meta > span";
+const STORY_META: &str = "#story > .storylist > p.meta";
+const STORY_NAME: &str = "#story > .storylist > .title > h4";
+const STORY_ORIGINS: &str = "#story > .storylist > p.meta > a";
+const STORY_SUMMARY: &str = "#story > .storylist > blockquote.summary";
+const STORY_WARNINGS: &str = "#story > .storylist > p.meta > .story-warnings > a";
+
+pub fn id_from_url(url: &Uri) -> anyhow::Result<String> {
+    let mut segments = url.path().split('/').filter(|s| !s.is_empty());
+
+    match (segments.next(), segments.next()) {
+        (Some("story"), Some(id)) if id.chars().all(|c| c.is_ascii_digit()) => Ok(id.to_string()),
+        _ => Err(anyhow::anyhow!("Unable to find story ID in: {}", url)),
+    }
+}
+
+pub async fn scrape(url: &Uri) -> anyhow::Result<Story> {
+    let id = id_from_url(url)?;
+
+    tracing::info!("[{}] Scraping initial details", url);
+
+    let url = format!("https://ficwad.com/story/{}", id).parse::<Uri>()?;
+
+    let body = req(&url).await?;
+    let html = Arc::new(body);
+
+    let details = tokio::task::spawn_blocking({
+        let html = html.clone();
+        || get_details(html)
+    })
+    .await
+    .expect("Thread pool closed")?;
+
+    let chapters = tokio::task::spawn_blocking({
+        let html = html.clone();
+        || get_chapters(html)
+    })
+    .await
+    .expect("Thread pool closed")?;
+
+    let mut story = Story::new(details);
+
+    tracing::info!("[{}] Beginning chapter scraping", url);
+
+    if chapters.is_empty() {
+        tracing::info!("[{}] Scraping chapter {}", url, 1);
+
+        story.chapters.push(
+            tokio::task::spawn_blocking(|| get_chapter(html))
+                .await
+                .expect("Thread pool closed")?,
+        );
+    } else {
+        for (i, chapter) in chapters.into_iter().enumerate() {
+            tracing::info!("[{}] Scraping chapter {}", url, i + 1);
+
+            let url = format!("https://ficwad.com/story/{}", chapter).parse::<Uri>()?;
+
+            let body = req(&url).await?;
+
+            story.chapters.push(
+                tokio::task::spawn_blocking(|| get_chapter(body))
+                    .await
+                    .expect("Thread pool closed")?,
+            );
+        }
+    }
+
+    story.words = story.chapters.iter().map(|c| word_count(&c.main)).sum();
+
+    Ok(story)
+}
+
+/// Returns the ID of every chapter listed on a story's index page.
+///
+/// Single chapter stories do not have an index page, the story page is the
+/// chapter, so an empty list is returned for them.
+pub fn get_chapters(html: impl Into<Document>) -> anyhow::Result<Vec<String>> {
+    let html = html.into();
+
+    html.select(CHAPTER_LIST)
+        .into_iter()
+        .map(|ele| {
+            ele.attr("href")
+                .and_then(|href| {
+                    href.trim_end_matches('/')
+                        .rsplit('/')
+                        .next()
+                        .map(String::from)
+                })
+                .ok_or_else(|| {
+                    anyhow::anyhow!("Element attribute for site {} not found: {}", NAME, "href")
+                })
+        })
+        .collect()
+}
+
+pub fn get_details(html: impl Into<Document>) -> anyhow::Result<Details> {
+    let html = html.into();
+
+    let name = stry_scraper::string(&html, STORY_NAME, NAME)?
+        .trim()
+        .to_string();
+    let summary = stry_remark::parse(stry_scraper::inner_html(&html, STORY_SUMMARY, NAME)?)?
+        .trim()
+        .to_string();
+
+    let authors = stry_scraper::string_vec(&html, STORY_AUTHOR, NAME)?;
+    let origins = stry_scraper::string_vec(&html, STORY_ORIGINS, NAME)?;
+
+    // The meta line mixes text and elements, only the text nodes are used
+    // here, leaving behind the keys of the values that are elements
+    let meta = stry_scraper::string(&html, STORY_META, NAME)?.replace('\u{a0}', " ");
+
+    let mut chapters = 1u32;
+    let mut rating = None;
+    let mut state = State::InProgress;
+
+    let mut tags = html
+        .select(STORY_WARNINGS)
+        .into_iter()
+        .map(|ele| {
+            ele.attr("title")
+                .map(|title| (TagType::Warning, title.trim().to_string()))
+                .ok_or_else(|| {
+                    anyhow::anyhow!("Element attribute for site {} not found: {}", NAME, "title")
+                })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let mut genres = Vec::new();
+
+    for s in meta.split(" - ").map(str::trim) {
+        let mut split = s.splitn(2, ':').map(str::trim);
+
+        match (split.next(), split.next()) {
+            (Some("Rating"), Some(value)) => {
+                rating = Some(match value {
+                    "NC-17" => Rating::Explicit,
+                    "R" => Rating::Mature,
+                    "PG-13" => Rating::Teen,
+                    "PG" | "G" => Rating::General,
+                    rating => {
+                        return Err(anyhow::anyhow!(
+                            "Unknown rating for site {} found: {}",
+                            NAME,
+                            rating
+                        ))
+                    }
+                });
+            }
+            (Some("Characters"), Some(value)) => {
+                tags.extend(list(value).map(|character| (TagType::Character, character)));
+            }
+            (Some("Genres"), Some(value)) => {
+                genres.extend(list(value).map(|genre| (TagType::General, genre)));
+            }
+            (Some("Chapters"), Some(value)) => {
+                if let Ok(ch) = value.parse::<u32>() {
+                    chapters = ch;
+                }
+            }
+            (Some("Complete"), None) => {
+                state = State::Completed;
+            }
+            _ => {}
+        }
+    }
+
+    tags.append(&mut genres);
+
+    let dates = html
+        .select(STORY_DATES)
+        .into_iter()
+        .filter_map(|ele| ele.attr("data-ts"))
+        .map(|ts| Utc.datetime_from_str(&ts, "%s").ok())
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| anyhow::anyhow!("Unparsable date time for site {}", NAME))?;
+
+    let (created, updated) = match dates.as_slice() {
+        [published] => (*published, *published),
+        [published, updated] => (*published, *updated),
+        _ => {
+            return Err(anyhow::anyhow!("Unparsable date time for site {}", NAME));
+        }
+    };
+
+    Ok(Details {
+        name,
+        summary,
+
+        chapters,
+        // FicWad only hosts English stories
+        language: Language::English,
+        rating: rating.ok_or_else(|| {
+            anyhow::anyhow!("Sector element for site {} not found: {}", NAME, "Rating")
+        })?,
+        state,
+
+        authors,
+        origins,
+        tags,
+        pairings: Vec::new(),
+
+        series: None,
+
+        created,
+        updated,
+    })
+}
+
+pub fn get_chapter(html: impl Into<Document>) -> anyhow::Result<Chapter> {
+    let html = html.into();
+
+    let main = stry_remark::parse(stry_scraper::inner_html(&html, CHAPTER_TEXT, NAME)?)?
+        .trim()
+        .to_string();
+
+    let name = match html
+        .select(CHAPTER_SELECT)
+        .into_iter()
+        .find(|ele| ele.attr("selected").is_some())
+        .and_then(|ele| ele.text())
+    {
+        // Chapter options are numbered (`1. Name`), remove the number
+        Some(option) => option
+            .trim()
+            .splitn(2, ". ")
+            .nth(1)
+            .map(String::from)
+            .unwrap_or(option),
+        // Single chapter stories use the story name as the chapter name
+        None => stry_scraper::string(&html, STORY_NAME, NAME)?
+            .trim()
+            .to_string(),
+    };
+
+    Ok(Chapter {
+        name,
+        words: word_count(&main),
+        pre: String::new(),
+        post: String::new(),
+        main,
+    })
+}
+
+fn list(value: &str) -> impl Iterator<Item = String> + '_ {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+}
+
+pub struct FicWad;
+
+#[async_trait::async_trait]
+impl SiteScraper for FicWad {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn hosts(&self) -> Vec<&str> {
+        vec!["ficwad.com", "www.ficwad.com"]
+    }
+
+    fn id_from_url(&self, url: &Uri) -> anyhow::Result<String> {
+        id_from_url(url)
+    }
+
+    fn details_url(&self, id: &str) -> anyhow::Result<Uri> {
+        let url = format!("https://ficwad.com/story/{}", id).parse::<Uri>()?;
+
+        Ok(url)
+    }
+
+    async fn get_details(&self, story: &mut SiteRef) -> anyhow::Result<Details> {
+        let document = story.document().await?;
+
+        let details = tokio::task::spawn_blocking(|| get_details(document))
+            .await
+            .expect("Thread pool closed")?;
+
+        Ok(details)
+    }
+
+    async fn get_chapter(&self, story: &mut SiteRef, chapter: u32) -> anyhow::Result<Chapter> {
+        let document = story.document().await?;
+
+        let chapters = tokio::task::spawn_blocking({
+            let document = document.clone();
+            || get_chapters(document)
+        })
+        .await
+        .expect("Thread pool closed")?;
+
+        // Single chapter stories have no index, the story page is the chapter
+        let body = if chapters.is_empty() && chapter == 1 {
+            document
+        } else {
+            let part = chapters
+                .get((chapter as usize).saturating_sub(1))
+                .ok_or_else(|| {
+                    anyhow::anyhow!("Story {} has no chapter {}", story.id(), chapter)
+                })?;
+
+            let url = format!("https://ficwad.com/story/{}", part).parse::<Uri>()?;
+
+            Arc::new(req(&url).await?)
+        };
+
+        let chapter = tokio::task::spawn_blocking(|| get_chapter(body))
+            .await
+            .expect("Thread pool closed")?;
+
+        Ok(chapter)
+    }
+
+    fn parse_details(&self, html: String) -> anyhow::Result<Details> {
+        get_details(html)
+    }
+
+    fn parse_chapter(
+        &self,
+        html: String,
+        _chapter: u32,
+        _post: Option<&str>,
+    ) -> anyhow::Result<Chapter> {
+        get_chapter(html)
+    }
+}
@@ -1,164 +1,290 @@
 pub mod archive_of_our_own;
 pub mod fanfiction;
+pub mod ficwad;
+pub mod royal_road;
+pub mod wattpad;
+pub mod xenforo;
 
 use {
     crate::{
-        models::{Chapter, Details},
+        http, images,
+        models::{Chapter, Details, ListedStory, Listing},
         utils::req,
         Uri,
     },
-    std::{convert::TryInto, fmt, sync::Arc},
+    std::{
+        collections::{HashMap, HashSet},
+        convert::TryInto,
+        sync::{Arc, RwLock},
+    },
 };
 
-#[derive(Clone, Copy, Debug, Hash, Ord, PartialOrd, Eq, PartialEq)]
-pub enum Sites {
-    ArchiveOfOurOwn,
-    FanFictionNet,
-}
+lazy_static::lazy_static! {
+    static ref SITES: RwLock<HashMap<String, Arc<dyn SiteScraper>>> = {
+        let mut sites = HashMap::new();
 
-impl Sites {
-    pub fn url(&self) -> &'static str {
-        match self {
-            Sites::ArchiveOfOurOwn => "https://archiveofourown.org/",
-            Sites::FanFictionNet => "https://fanfiction.net/",
+        for site in builtin() {
+            for host in site.hosts() {
+                sites.insert(host.to_string(), site.clone());
+            }
         }
-    }
+
+        RwLock::new(sites)
+    };
+
+    /// Hosts handled by the built in sites, which can't be replaced.
+    static ref BUILTIN_HOSTS: HashSet<String> = builtin()
+        .iter()
+        .flat_map(|site| site.hosts().into_iter().map(String::from).collect::<Vec<_>>())
+        .collect();
 }
 
-impl fmt::Display for Sites {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            Sites::ArchiveOfOurOwn => write!(f, "Archive of Our Own"),
-            Sites::FanFictionNet => write!(f, "FanFiction.net"),
-        }
+fn builtin() -> Vec<Arc<dyn SiteScraper>> {
+    vec![
+        Arc::new(archive_of_our_own::ArchiveOfOurOwn),
+        Arc::new(fanfiction::FanFictionNet),
+        Arc::new(ficwad::FicWad),
+        Arc::new(xenforo::QUESTIONABLE_QUESTING.clone()),
+        Arc::new(royal_road::RoyalRoad),
+        Arc::new(xenforo::SPACEBATTLES.clone()),
+        Arc::new(xenforo::SUFFICIENT_VELOCITY.clone()),
+        Arc::new(wattpad::Wattpad),
+    ]
+}
+
+/// A site that stories can be scraped from.
+///
+/// Sites are found by the host of a story's URL, see [`register`] and
+/// [`init_from_url`].
+#[async_trait::async_trait]
+pub trait SiteScraper: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Host names that the site's stories can be found on.
+    fn hosts(&self) -> Vec<&str>;
+
+    fn id_from_url(&self, url: &Uri) -> anyhow::Result<String>;
+
+    /// The URL of the story's main page, requested once by [`SiteRef::document`].
+    fn details_url(&self, id: &str) -> anyhow::Result<Uri>;
+
+    async fn get_details(&self, story: &mut SiteRef) -> anyhow::Result<Details>;
+    async fn get_chapter(&self, story: &mut SiteRef, chapter: u32) -> anyhow::Result<Chapter>;
+
+    /// Gets the story's details from its main page, without making any
+    /// requests.
+    fn parse_details(&self, html: String) -> anyhow::Result<Details>;
+
+    /// Gets the chapter from the page it is on, without making any requests.
+    ///
+    /// Forums show many chapters on a page, so are given the chapter's
+    /// threadmarked `post`.
+    fn parse_chapter(
+        &self,
+        html: String,
+        chapter: u32,
+        post: Option<&str>,
+    ) -> anyhow::Result<Chapter>;
+
+    /// Returns if the URL is a page that lists stories, like an author's
+    /// works, instead of a story, see [`expand`].
+    fn is_listing(&self, _url: &Uri) -> bool {
+        false
+    }
+
+    /// Gets the stories and the next page from a single page of a listing.
+    fn get_listing(&self, _url: &Uri, _html: String) -> anyhow::Result<Listing> {
+        Err(anyhow::anyhow!(
+            "Site {} does not support story listings",
+            self.name()
+        ))
     }
 }
 
-pub struct SiteRef {
-    inner: Inner,
+/// Adds a site, replacing any added site that already handles one of its
+/// hosts.
+///
+/// Built in sites can't be replaced, a site with one of their hosts is not
+/// added.
+pub fn register(site: Arc<dyn SiteScraper>) -> anyhow::Result<()> {
+    if let Some(host) = site
+        .hosts()
+        .into_iter()
+        .find(|host| BUILTIN_HOSTS.contains(*host))
+    {
+        anyhow::bail!(
+            "Site {} can't replace the built in site for {}",
+            site.name(),
+            host
+        );
+    }
+
+    let mut sites = SITES.write().expect("Site registry lock poisoned");
+
+    for host in site.hosts() {
+        sites.insert(host.to_string(), site.clone());
+    }
+
+    Ok(())
 }
 
-impl SiteRef {
-    pub async fn get_details(&mut self) -> anyhow::Result<Details> {
-        match &mut self.inner {
-            Inner::ArchiveOfOurOwn { id, document } => {
-                if document.is_none() {
-                    let url = format!(
-                        "https://archiveofourown.org/works/{}?view_full_work=true",
-                        id
-                    )
-                    .parse::<Uri>()?;
+/// Finds the site that handles the given host.
+pub fn find(host: &str) -> Option<Arc<dyn SiteScraper>> {
+    SITES
+        .read()
+        .expect("Site registry lock poisoned")
+        .get(host)
+        .cloned()
+}
 
-                    let body = req(&url).await?;
+/// Finds the site for the URL and gets the story ID from it.
+pub fn init_from_url<T>(url: T) -> anyhow::Result<SiteRef>
+where
+    T: TryInto<Uri>,
+    <T as TryInto<Uri>>::Error: std::error::Error,
+{
+    let url: Uri = url
+        .try_into()
+        .map_err(|err| anyhow::anyhow!("Unable to convert string to URL: {}", err))?;
 
-                    *document = Some(Arc::new(body));
-                }
+    let site = url
+        .host()
+        .and_then(find)
+        .ok_or_else(|| anyhow::anyhow!("No site found that can handle: {}", url))?;
 
-                let document = document.clone().expect("This should not be `None`");
+    let id = site.id_from_url(&url)?;
 
-                let details =
-                    tokio::task::spawn_blocking(|| archive_of_our_own::get_details(document))
-                        .await
-                        .expect("Thread pool closed")?;
+    Ok(SiteRef::new(site, id))
+}
 
-                Ok(details)
-            }
-            Inner::FanFictionNet { id } => {
-                let url = format!("https://www.fanfiction.net/s/{}/{}", id, 1).parse::<Uri>()?;
+/// The most pages of a single listing that are followed.
+const LISTING_PAGE_LIMIT: usize = 100;
 
-                let body = req(&url).await?;
+/// Expands a listing URL into the stories on every one of its pages, a
+/// story listed more than once is only returned the first time.
+///
+/// Returns `None` if the URL is not a listing.
+pub async fn expand<T>(url: T) -> anyhow::Result<Option<Vec<ListedStory>>>
+where
+    T: TryInto<Uri>,
+    <T as TryInto<Uri>>::Error: std::error::Error,
+{
+    let url: Uri = url
+        .try_into()
+        .map_err(|err| anyhow::anyhow!("Unable to convert string to URL: {}", err))?;
 
-                let details = tokio::task::spawn_blocking(|| fanfiction::get_details(body))
-                    .await
-                    .expect("Thread pool closed")?;
+    let site = url
+        .host()
+        .and_then(find)
+        .ok_or_else(|| anyhow::anyhow!("No site found that can handle: {}", url))?;
 
-                Ok(details)
-            }
-        }
+    if !site.is_listing(&url) {
+        return Ok(None);
     }
 
-    pub async fn get_chapter(&mut self, chapter: u32) -> anyhow::Result<Chapter> {
-        match &mut self.inner {
-            Inner::ArchiveOfOurOwn { id, document } => {
-                if document.is_none() {
-                    let url = format!(
-                        "https://archiveofourown.org/works/{}?view_full_work=true",
-                        id
-                    )
-                    .parse::<Uri>()?;
+    let mut seen = HashSet::new();
+    let mut stories = Vec::new();
 
-                    let body = req(&url).await?;
+    let mut next = Some(url);
 
-                    *document = Some(Arc::new(body));
-                }
+    for page in 1..=LISTING_PAGE_LIMIT {
+        let url = match next.take() {
+            Some(url) => url,
+            None => break,
+        };
 
-                todo!()
-            }
-            Inner::FanFictionNet { id } => {
-                let url =
-                    format!("https://www.fanfiction.net/s/{}/{}", id, chapter).parse::<Uri>()?;
+        tracing::info!("[{}] Scraping listing page {}", url, page);
 
-                let body = req(&url).await?;
+        let html = req(&url).await?;
 
-                let chapter = tokio::task::spawn_blocking(|| fanfiction::get_chapter(body))
-                    .await
-                    .expect("Thread pool closed")?;
+        let listing = tokio::task::spawn_blocking({
+            let site = site.clone();
 
-                Ok(chapter)
+            move || site.get_listing(&url, html)
+        })
+        .await
+        .expect("Thread pool closed")?;
+
+        for story in listing.stories {
+            if seen.insert(story.url.clone()) {
+                stories.push(story);
             }
         }
+
+        next = listing.next.map(|next| next.parse::<Uri>()).transpose()?;
     }
-}
 
-enum Inner {
-    ArchiveOfOurOwn {
-        id: String,
-        document: Option<Arc<String>>,
-    },
-    FanFictionNet {
-        id: String,
-    },
+    if let Some(url) = next {
+        tracing::warn!(
+            "[{}] Listing has more than {} pages, the rest are skipped",
+            url,
+            LISTING_PAGE_LIMIT
+        );
+    }
+
+    Ok(Some(stories))
 }
 
-pub trait Site: Copy {
-    fn init(self, id: impl Into<String>) -> SiteRef;
-    fn init_from_url<T>(self, url: T) -> anyhow::Result<SiteRef>
-    where
-        T: TryInto<Uri>,
-        <T as TryInto<Uri>>::Error: std::error::Error;
+/// A story on a site, the story's main page is kept after it is requested.
+pub struct SiteRef {
+    site: Arc<dyn SiteScraper>,
+    id: String,
+    document: Option<Arc<String>>,
 }
 
-impl Site for Sites {
-    fn init(self, id: impl Into<String>) -> SiteRef {
-        match self {
-            Sites::ArchiveOfOurOwn => SiteRef {
-                inner: Inner::ArchiveOfOurOwn {
-                    id: id.into(),
-                    document: None,
-                },
-            },
-            Sites::FanFictionNet => SiteRef {
-                inner: Inner::FanFictionNet { id: id.into() },
-            },
+impl SiteRef {
+    pub fn new(site: Arc<dyn SiteScraper>, id: impl Into<String>) -> Self {
+        Self {
+            site,
+            id: id.into(),
+            document: None,
         }
     }
 
-    fn init_from_url<T>(self, url: T) -> anyhow::Result<SiteRef>
-    where
-        T: TryInto<Uri>,
-        <T as TryInto<Uri>>::Error: std::error::Error,
-    {
-        let url: Uri = url
-            .try_into()
-            .map_err(|err| anyhow::anyhow!("Unable to convert string to URL: {}", err))?;
-
-        match self {
-            Sites::ArchiveOfOurOwn => archive_of_our_own::id_from_url(&url).map(|id| SiteRef {
-                inner: Inner::ArchiveOfOurOwn { id, document: None },
-            }),
-            Sites::FanFictionNet => fanfiction::id_from_url(&url).map(|id| SiteRef {
-                inner: Inner::FanFictionNet { id },
-            }),
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn site(&self) -> &Arc<dyn SiteScraper> {
+        &self.site
+    }
+
+    /// Returns the story's main page, requesting it if it has not been yet,
+    /// the request uses the page cache if it is enabled.
+    pub async fn document(&mut self) -> anyhow::Result<Arc<String>> {
+        if let Some(document) = &self.document {
+            return Ok(document.clone());
         }
+
+        let url = self.site.details_url(&self.id)?;
+
+        let document = Arc::new(req(&url).await?);
+
+        self.document = Some(document.clone());
+
+        Ok(document)
+    }
+
+    pub async fn get_details(&mut self) -> anyhow::Result<Details> {
+        let site = self.site.clone();
+
+        site.get_details(self).await
+    }
+
+    /// Gets the chapter, with its images downloaded and pointing to the
+    /// stored copies, see [`images::localize`].
+    pub async fn get_chapter(&mut self, chapter: u32) -> anyhow::Result<Chapter> {
+        let site = self.site.clone();
+
+        let mut chapter = site.get_chapter(self, chapter).await?;
+
+        let base = site.details_url(&self.id)?;
+        let client = http::client();
+
+        chapter.pre = images::localize(&client, &base, &chapter.pre).await;
+        chapter.main = images::localize(&client, &base, &chapter.main).await;
+        chapter.post = images::localize(&client, &base, &chapter.post).await;
+
+        Ok(chapter)
     }
 }
@@ -0,0 +1,328 @@
+use {
+    crate::{
+        models::{Chapter, Details, Language, Rating, State, Story, TagType},
+        sites::{SiteRef, SiteScraper},
+        utils::{req, word_count},
+        Uri,
+    },
+    chrono::{TimeZone, Utc},
+    std::sync::Arc,
+    stry_scraper::Document,
+};
+
+const NAME: &str = "royal road";
+
+const CHAPTER_NAME: &str = ".fic-header h1";
+const CHAPTER_PAGE: &str = ".chapter-page";
+
+const STORY_AUTHOR: &str = ".fic-header .fic-title > h4 > span > a";
+const STORY_LABELS: &str = ".fiction-info .margin-bottom-10 > span.label";
+const STORY_NAME: &str = ".fic-header .fic-title > h1";
+const STORY_SUMMARY: &str = ".fiction-info .description > .hidden-content";
+const STORY_TAGS: &str = ".fiction-info .tags > a.fiction-tag";
+const STORY_WARNINGS: &str = ".fiction-info .text-center > ul.list-inline > li";
+
+const STORY_CHAPTERS: &str = "#chapters > tbody > tr.chapter-row";
+const STORY_CHAPTERS_DATE: &str = "#chapters > tbody > tr.chapter-row > td > a > time";
+
+pub fn id_from_url(url: &Uri) -> anyhow::Result<String> {
+    let mut segments = url.path().split('/').filter(|s| !s.is_empty());
+
+    match (segments.next(), segments.next()) {
+        (Some("fiction"), Some(id)) if id.chars().all(|c| c.is_ascii_digit()) => Ok(id.to_string()),
+        _ => Err(anyhow::anyhow!("Unable to find story ID in: {}", url)),
+    }
+}
+
+pub async fn scrape(url: &Uri) -> anyhow::Result<Story> {
+    let id = id_from_url(url)?;
+
+    tracing::info!("[{}] Scraping initial details", url);
+
+    let url = format!("https://www.royalroad.com/fiction/{}", id).parse::<Uri>()?;
+
+    let body = req(&url).await?;
+    let html = Arc::new(body);
+
+    let details = tokio::task::spawn_blocking({
+        let html = html.clone();
+        || get_details(html)
+    })
+    .await
+    .expect("Thread pool closed")?;
+
+    let chapters = tokio::task::spawn_blocking(|| get_chapters(html))
+        .await
+        .expect("Thread pool closed")?;
+
+    let mut story = Story::new(details);
+
+    tracing::info!("[{}] Beginning chapter scraping", url);
+
+    for (i, chapter) in chapters.into_iter().enumerate() {
+        tracing::info!("[{}] Scraping chapter {}", url, i + 1);
+
+        let url = format!("https://www.royalroad.com{}", chapter).parse::<Uri>()?;
+
+        let body = req(&url).await?;
+
+        story.chapters.push(
+            tokio::task::spawn_blocking(|| get_chapter(body))
+                .await
+                .expect("Thread pool closed")?,
+        );
+    }
+
+    story.words = story.chapters.iter().map(|c| word_count(&c.main)).sum();
+
+    Ok(story)
+}
+
+/// Returns the path of every chapter listed in the fiction's table of contents.
+pub fn get_chapters(html: impl Into<Document>) -> anyhow::Result<Vec<String>> {
+    let html = html.into();
+
+    html.select(STORY_CHAPTERS)
+        .into_iter()
+        .map(|ele| {
+            ele.attr("data-url").ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Element attribute for site {} not found: {}",
+                    NAME,
+                    "data-url"
+                )
+            })
+        })
+        .collect()
+}
+
+pub fn get_details(html: impl Into<Document>) -> anyhow::Result<Details> {
+    let html = html.into();
+
+    let name = stry_scraper::string(&html, STORY_NAME, NAME)?
+        .trim()
+        .to_string();
+    let summary = stry_remark::parse(stry_scraper::inner_html(&html, STORY_SUMMARY, NAME)?)?
+        .trim()
+        .to_string();
+
+    let authors = stry_scraper::string_vec(&html, STORY_AUTHOR, NAME)?;
+
+    let state = stry_scraper::string_vec(&html, STORY_LABELS, NAME)?
+        .iter()
+        .find_map(|label| match label.trim() {
+            "COMPLETED" => Some(State::Completed),
+            "HIATUS" => Some(State::Hiatus),
+            "DROPPED" => Some(State::Abandoned),
+            "ONGOING" | "STUB" => Some(State::InProgress),
+            _ => None,
+        })
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Sector element for site {} not found: {}",
+                NAME,
+                STORY_LABELS
+            )
+        })?;
+
+    let warnings = stry_scraper::string_vec(&html, STORY_WARNINGS, NAME)?
+        .into_iter()
+        .map(|warning| warning.trim().to_string())
+        .collect::<Vec<_>>();
+
+    // Royal Road has no ratings, only content warnings, so the rating is
+    // based on the strongest warning given
+    let rating = if warnings.iter().any(|warning| warning == "Sexual Content") {
+        Rating::Mature
+    } else if warnings.is_empty() {
+        Rating::General
+    } else {
+        Rating::Teen
+    };
+
+    let tags = warnings
+        .into_iter()
+        .map(|warning| (TagType::Warning, warning))
+        .chain(
+            stry_scraper::string_vec(&html, STORY_TAGS, NAME)?
+                .into_iter()
+                .map(|tag| (TagType::General, tag.trim().to_string())),
+        )
+        .collect::<Vec<_>>();
+
+    let dates = html
+        .select(STORY_CHAPTERS_DATE)
+        .into_iter()
+        .map(|ele| {
+            ele.attr("unixtime")
+                .and_then(|ts| Utc.datetime_from_str(&ts, "%s").ok())
+        })
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| anyhow::anyhow!("Unparsable date time for site {}", NAME))?;
+
+    Ok(Details {
+        name,
+        summary,
+
+        chapters: dates.len() as u32,
+        // Royal Road only hosts English stories
+        language: Language::English,
+        rating,
+        state,
+
+        authors,
+        origins: Vec::new(),
+        tags,
+        pairings: Vec::new(),
+
+        series: None,
+
+        created: dates
+            .first()
+            .copied()
+            .ok_or_else(|| anyhow::anyhow!("Unparsable date time for site {}", NAME))?,
+        updated: dates
+            .iter()
+            .max()
+            .copied()
+            .ok_or_else(|| anyhow::anyhow!("Unparsable date time for site {}", NAME))?,
+    })
+}
+
+pub fn get_chapter(html: impl Into<Document>) -> anyhow::Result<Chapter> {
+    let html = html.into();
+
+    let page = html
+        .select(CHAPTER_PAGE)
+        .into_iter()
+        .next()
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Sector element for site {} not found: {}",
+                NAME,
+                CHAPTER_PAGE
+            )
+        })?;
+
+    let mut pre = String::new();
+    let mut main = None;
+    let mut post = String::new();
+
+    // Author notes are siblings of the chapter content, their position
+    // decides if they are a pre or post chapter note
+    for child in page.children() {
+        let class = child.attr("class").unwrap_or_default();
+        let classes = class.split_whitespace().collect::<Vec<_>>();
+
+        if classes.contains(&"chapter-content") {
+            main = child.inner_html();
+        } else if classes.contains(&"author-note-portlet") {
+            let note = match child
+                .select(".author-note")
+                .first()
+                .and_then(|note| note.inner_html())
+            {
+                Some(note) => stry_remark::parse(note)?.trim().to_string(),
+                None => continue,
+            };
+
+            let notes = if main.is_none() { &mut pre } else { &mut post };
+
+            if !notes.is_empty() {
+                notes.push_str("\n\n");
+            }
+
+            notes.push_str(&note);
+        }
+    }
+
+    let main = stry_remark::parse(main.ok_or_else(|| {
+        anyhow::anyhow!(
+            "Sector element for site {} not found: {}",
+            NAME,
+            ".chapter-content"
+        )
+    })?)?
+    .trim()
+    .to_string();
+
+    let name = stry_scraper::string(&html, CHAPTER_NAME, NAME)?
+        .trim()
+        .to_string();
+
+    Ok(Chapter {
+        name,
+        words: word_count(&main),
+        pre,
+        post,
+        main,
+    })
+}
+
+pub struct RoyalRoad;
+
+#[async_trait::async_trait]
+impl SiteScraper for RoyalRoad {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn hosts(&self) -> Vec<&str> {
+        vec!["royalroad.com", "www.royalroad.com"]
+    }
+
+    fn id_from_url(&self, url: &Uri) -> anyhow::Result<String> {
+        id_from_url(url)
+    }
+
+    fn details_url(&self, id: &str) -> anyhow::Result<Uri> {
+        let url = format!("https://www.royalroad.com/fiction/{}", id).parse::<Uri>()?;
+
+        Ok(url)
+    }
+
+    async fn get_details(&self, story: &mut SiteRef) -> anyhow::Result<Details> {
+        let document = story.document().await?;
+
+        let details = tokio::task::spawn_blocking(|| get_details(document))
+            .await
+            .expect("Thread pool closed")?;
+
+        Ok(details)
+    }
+
+    async fn get_chapter(&self, story: &mut SiteRef, chapter: u32) -> anyhow::Result<Chapter> {
+        let document = story.document().await?;
+
+        let chapters = tokio::task::spawn_blocking(|| get_chapters(document))
+            .await
+            .expect("Thread pool closed")?;
+
+        let path = chapters
+            .get((chapter as usize).saturating_sub(1))
+            .ok_or_else(|| anyhow::anyhow!("Story {} has no chapter {}", story.id(), chapter))?;
+
+        let url = format!("https://www.royalroad.com{}", path).parse::<Uri>()?;
+
+        let body = req(&url).await?;
+
+        let chapter = tokio::task::spawn_blocking(|| get_chapter(body))
+            .await
+            .expect("Thread pool closed")?;
+
+        Ok(chapter)
+    }
+
+    fn parse_details(&self, html: String) -> anyhow::Result<Details> {
+        get_details(html)
+    }
+
+    fn parse_chapter(
+        &self,
+        html: String,
+        _chapter: u32,
+        _post: Option<&str>,
+    ) -> anyhow::Result<Chapter> {
+        get_chapter(html)
+    }
+}
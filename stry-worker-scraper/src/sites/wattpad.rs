@@ -0,0 +1,317 @@
+use {
+    crate::{
+        models::{Chapter, Details, Language, Rating, State, Story, TagType},
+        sites::{SiteRef, SiteScraper},
+        utils::{req, word_count},
+        Uri,
+    },
+    chrono::{DateTime, NaiveDate, Utc},
+    std::{fmt::Write, sync::Arc},
+    stry_scraper::{Document, Element},
+};
+
+const NAME: &str = "wattpad";
+
+const CHAPTER_NAME: &str = "#story-reading > header.panel-reading > h1";
+const CHAPTER_TEXT: &str = "#story-reading .page .panel-reading > pre > p";
+
+const STORY_AUTHOR: &str = "#story-landing .author-info > a.author-info__username";
+const STORY_LANGUAGE: &str = "html";
+const STORY_MATURE: &str = "#story-landing .story-badges > .mature";
+const STORY_NAME: &str = "#story-landing .story-info > h1.story-info__title";
+const STORY_STATUS: &str = "#story-landing .story-badges > .story-status";
+const STORY_SUMMARY: &str = "#story-landing .description > pre.description-text";
+const STORY_TAGS: &str = "#story-landing ul.tag-items > li > a.tag-item";
+
+const STORY_PARTS: &str = "#story-landing .table-of-contents > ul > li > a.story-parts__part";
+const STORY_PARTS_DATE: &str = "#story-landing .table-of-contents > ul > li > a > .part__date";
+
+pub fn id_from_url(url: &Uri) -> anyhow::Result<String> {
+    let mut segments = url.path().split('/').filter(|s| !s.is_empty());
+
+    // Only story landing pages (`/story/<id>-<slug>`) contain the story ID,
+    // a part URL (`/<id>-<slug>`) only gives the ID of the part.
+    match (segments.next(), segments.next()) {
+        (Some("story"), Some(segment)) => segment
+            .split('-')
+            .next()
+            .filter(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()))
+            .map(String::from)
+            .ok_or_else(|| anyhow::anyhow!("Unable to find story ID in: {}", url)),
+        _ => Err(anyhow::anyhow!("Unable to find story ID in: {}", url)),
+    }
+}
+
+pub async fn scrape(url: &Uri) -> anyhow::Result<Story> {
+    let id = id_from_url(url)?;
+
+    tracing::info!("[{}] Scraping initial details", url);
+
+    let url = format!("https://www.wattpad.com/story/{}", id).parse::<Uri>()?;
+
+    let body = req(&url).await?;
+    let html = Arc::new(body);
+
+    let details = tokio::task::spawn_blocking({
+        let html = html.clone();
+        || get_details(html)
+    })
+    .await
+    .expect("Thread pool closed")?;
+
+    let parts = tokio::task::spawn_blocking(|| get_parts(html))
+        .await
+        .expect("Thread pool closed")?;
+
+    let mut story = Story::new(details);
+
+    tracing::info!("[{}] Beginning chapter scraping", url);
+
+    for (i, part) in parts.into_iter().enumerate() {
+        tracing::info!("[{}] Scraping chapter {}", url, i + 1);
+
+        let url = format!("https://www.wattpad.com/{}", part).parse::<Uri>()?;
+
+        let body = req(&url).await?;
+
+        story.chapters.push(
+            tokio::task::spawn_blocking(|| get_chapter(body))
+                .await
+                .expect("Thread pool closed")?,
+        );
+    }
+
+    story.words = story.chapters.iter().map(|c| word_count(&c.main)).sum();
+
+    Ok(story)
+}
+
+/// Returns the path of every part (chapter) listed on the story landing page,
+/// in reading order.
+pub fn get_parts(html: impl Into<Document>) -> anyhow::Result<Vec<String>> {
+    let html = html.into();
+
+    let parts = html
+        .select(STORY_PARTS)
+        .into_iter()
+        .map(|ele| {
+            ele.attr("href")
+                .map(|href| href.trim_start_matches('/').to_string())
+        })
+        .collect::<Option<Vec<String>>>()
+        .ok_or_else(|| {
+            anyhow::anyhow!("Element attribute for site {} not found: {}", NAME, "href")
+        })?;
+
+    if parts.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Sector element for site {} not found: {}",
+            NAME,
+            STORY_PARTS
+        ));
+    }
+
+    Ok(parts)
+}
+
+pub fn get_details(html: impl Into<Document>) -> anyhow::Result<Details> {
+    let html = html.into();
+
+    let name = stry_scraper::string(&html, STORY_NAME, NAME)?
+        .trim()
+        .to_string();
+    let summary = stry_scraper::string(&html, STORY_SUMMARY, NAME)?
+        .trim()
+        .to_string();
+
+    let authors = stry_scraper::string_vec(&html, STORY_AUTHOR, NAME)?
+        .into_iter()
+        .map(|author| author.trim().to_string())
+        .collect::<Vec<_>>();
+
+    let tags = stry_scraper::string_vec(&html, STORY_TAGS, NAME)?
+        .into_iter()
+        .map(|tag| tag.trim().trim_start_matches('#').to_string())
+        .filter(|tag| !tag.is_empty())
+        .map(|tag| (TagType::General, tag))
+        .collect::<Vec<_>>();
+
+    let language = match html
+        .select(STORY_LANGUAGE)
+        .first()
+        .and_then(|ele| ele.attr("lang"))
+        .as_deref()
+        .and_then(|lang| lang.split('-').next())
+    {
+        Some(lang) => Language::parse_lossy(lang),
+        None => return Err(anyhow::anyhow!("Missing language for site {}", NAME)),
+    };
+
+    // Wattpad only flags mature works, everything else is open to all readers
+    let rating = if html.select(STORY_MATURE).is_empty() {
+        Rating::General
+    } else {
+        Rating::Mature
+    };
+
+    let state = match stry_scraper::string(&html, STORY_STATUS, NAME)?.trim() {
+        "Complete" => State::Completed,
+        "Ongoing" => State::InProgress,
+        state => {
+            return Err(anyhow::anyhow!(
+                "Unknown story status for site {} found: {}",
+                NAME,
+                state
+            ))
+        }
+    };
+
+    let dates = html
+        .select(STORY_PARTS_DATE)
+        .into_iter()
+        .map(|ele| ele.text().and_then(|text| parse_date(&text)))
+        .collect::<Option<Vec<DateTime<Utc>>>>()
+        .ok_or_else(|| anyhow::anyhow!("Unparsable date time for site {}", NAME))?;
+
+    let chapters = dates.len() as u32;
+
+    Ok(Details {
+        name,
+        summary,
+
+        chapters,
+        language,
+        rating,
+        state,
+
+        authors,
+        origins: Vec::new(),
+        tags,
+        pairings: Vec::new(),
+
+        series: None,
+
+        created: dates
+            .first()
+            .copied()
+            .ok_or_else(|| anyhow::anyhow!("Unparsable date time for site {}", NAME))?,
+        updated: dates
+            .iter()
+            .max()
+            .copied()
+            .ok_or_else(|| anyhow::anyhow!("Unparsable date time for site {}", NAME))?,
+    })
+}
+
+pub fn get_chapter(html: impl Into<Document>) -> anyhow::Result<Chapter> {
+    let html = html.into();
+
+    let elements: Vec<Element> = html.select(CHAPTER_TEXT);
+
+    if elements.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Sector element for site {} not found: {}",
+            NAME,
+            CHAPTER_TEXT
+        ));
+    }
+
+    let main = stry_remark::parse(elements.into_iter().filter_map(|n| n.html()).fold(
+        String::new(),
+        |mut buffer, html| {
+            write!(buffer, "{}", html).unwrap();
+
+            buffer
+        },
+    ))?
+    .trim()
+    .to_string();
+
+    let name = stry_scraper::string(&html, CHAPTER_NAME, NAME)?
+        .trim()
+        .to_string();
+
+    Ok(Chapter {
+        name,
+        words: word_count(&main),
+        pre: String::new(),
+        post: String::new(),
+        main,
+    })
+}
+
+fn parse_date(text: &str) -> Option<DateTime<Utc>> {
+    NaiveDate::parse_from_str(text.trim(), "%a, %b %e, %Y")
+        .map(|date| date.and_hms(0, 0, 0))
+        .map(|dt| DateTime::from_utc(dt, Utc))
+        .ok()
+}
+
+pub struct Wattpad;
+
+#[async_trait::async_trait]
+impl SiteScraper for Wattpad {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn hosts(&self) -> Vec<&str> {
+        vec!["wattpad.com", "www.wattpad.com", "m.wattpad.com"]
+    }
+
+    fn id_from_url(&self, url: &Uri) -> anyhow::Result<String> {
+        id_from_url(url)
+    }
+
+    fn details_url(&self, id: &str) -> anyhow::Result<Uri> {
+        let url = format!("https://www.wattpad.com/story/{}", id).parse::<Uri>()?;
+
+        Ok(url)
+    }
+
+    async fn get_details(&self, story: &mut SiteRef) -> anyhow::Result<Details> {
+        let document = story.document().await?;
+
+        let details = tokio::task::spawn_blocking(|| get_details(document))
+            .await
+            .expect("Thread pool closed")?;
+
+        Ok(details)
+    }
+
+    async fn get_chapter(&self, story: &mut SiteRef, chapter: u32) -> anyhow::Result<Chapter> {
+        let document = story.document().await?;
+
+        // Wattpad stores each part on its own page, the landing page only links to them
+        let parts = tokio::task::spawn_blocking(|| get_parts(document))
+            .await
+            .expect("Thread pool closed")?;
+
+        let part = parts
+            .get((chapter as usize).saturating_sub(1))
+            .ok_or_else(|| anyhow::anyhow!("Story {} has no chapter {}", story.id(), chapter))?;
+
+        let url = format!("https://www.wattpad.com/{}", part).parse::<Uri>()?;
+
+        let body = req(&url).await?;
+
+        let chapter = tokio::task::spawn_blocking(|| get_chapter(body))
+            .await
+            .expect("Thread pool closed")?;
+
+        Ok(chapter)
+    }
+
+    fn parse_details(&self, html: String) -> anyhow::Result<Details> {
+        get_details(html)
+    }
+
+    fn parse_chapter(
+        &self,
+        html: String,
+        _chapter: u32,
+        _post: Option<&str>,
+    ) -> anyhow::Result<Chapter> {
+        get_chapter(html)
+    }
+}
@@ -0,0 +1,366 @@
+//! Shared scraper for XenForo 2 based forums that use threadmarks.
+//!
+//! Threadmarked posts are treated as the chapters of a story, with the
+//! thread's threadmark index used for the story details.
+
+use {
+    crate::{
+        models::{Chapter, Details, Language, Rating, State, Story, TagType},
+        sites::{SiteRef, SiteScraper},
+        utils::{req, word_count},
+        ScrapeError, Uri,
+    },
+    chrono::{TimeZone, Utc},
+    std::sync::Arc,
+    stry_scraper::Document,
+};
+
+/// The number of threadmarked posts shown on each reader mode page.
+pub const READER_PAGE_SIZE: u32 = 10;
+
+const POST_CONTENT: &str = ".message-body > .bbWrapper";
+const POST_NAME: &str = ".threadmarkLabel";
+
+const STORY_AUTHOR: &str = ".p-body-header .p-description > ul > li > a.username";
+const STORY_NAME: &str = ".p-body-header .p-title > h1.p-title-value";
+const STORY_STATS: &str = ".threadmarkListingHeader-stats > dl";
+const STORY_TAGS: &str = ".tagList > a.tagItem";
+
+const STORY_THREADMARKS: &str = ".block--threadmarks .structItem--threadmark .structItem-title > a";
+const STORY_THREADMARKS_DATE: &str =
+    ".block--threadmarks .structItem--threadmark .structItem-cell--latest > time";
+
+/// A XenForo forum that can be scraped.
+#[derive(Clone, Debug)]
+pub struct Forum {
+    pub name: &'static str,
+    pub host: &'static str,
+
+    /// Forums have no story ratings, so the rating is based on the forum's rules.
+    pub rating: Rating,
+}
+
+pub static QUESTIONABLE_QUESTING: Forum = Forum {
+    name: "questionable questing",
+    host: "forum.questionablequesting.com",
+    rating: Rating::Mature,
+};
+
+pub static SPACEBATTLES: Forum = Forum {
+    name: "spacebattles",
+    host: "forums.spacebattles.com",
+    rating: Rating::Teen,
+};
+
+pub static SUFFICIENT_VELOCITY: Forum = Forum {
+    name: "sufficient velocity",
+    host: "forums.sufficientvelocity.com",
+    rating: Rating::Teen,
+};
+
+impl Forum {
+    pub fn threadmarks_url(&self, id: &str) -> anyhow::Result<Uri> {
+        let url = format!("https://{}/threads/{}/threadmarks", self.host, id).parse::<Uri>()?;
+
+        Ok(url)
+    }
+
+    /// Returns the URL of the reader mode page that holds the given chapter.
+    pub fn reader_url(&self, id: &str, chapter: u32) -> anyhow::Result<Uri> {
+        let page = (chapter.saturating_sub(1) / READER_PAGE_SIZE) + 1;
+
+        let url =
+            format!("https://{}/threads/{}/reader/page-{}", self.host, id, page).parse::<Uri>()?;
+
+        Ok(url)
+    }
+}
+
+/// Gets the thread ID from a thread URL, which can either be the plain ID
+/// (`/threads/12345/`) or a slug with the ID (`/threads/some-title.12345/`).
+pub fn id_from_url(url: &Uri) -> anyhow::Result<String> {
+    let mut segments = url.path().split('/').filter(|s| !s.is_empty());
+
+    match (segments.next(), segments.next()) {
+        (Some("threads"), Some(segment)) => segment
+            .rsplit('.')
+            .next()
+            .filter(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()))
+            .map(String::from)
+            .ok_or_else(|| anyhow::anyhow!("Unable to find story ID in: {}", url)),
+        _ => Err(anyhow::anyhow!("Unable to find story ID in: {}", url)),
+    }
+}
+
+pub async fn scrape(forum: &Forum, url: &Uri) -> anyhow::Result<Story> {
+    let id = id_from_url(url)?;
+
+    tracing::info!("[{}] Scraping initial details", url);
+
+    let url = forum.threadmarks_url(&id)?;
+
+    let body = req(&url).await?;
+    let html = Arc::new(body);
+
+    let details = tokio::task::spawn_blocking({
+        let html = html.clone();
+        let rating = forum.rating.clone();
+        let name = forum.name;
+
+        move || get_details(html, name, rating)
+    })
+    .await
+    .expect("Thread pool closed")?;
+
+    let threadmarks = tokio::task::spawn_blocking({
+        let name = forum.name;
+
+        move || get_threadmarks(html, name)
+    })
+    .await
+    .expect("Thread pool closed")?;
+
+    let mut story = Story::new(details);
+
+    tracing::info!("[{}] Beginning chapter scraping", url);
+
+    let mut page: Option<(Uri, Arc<String>)> = None;
+
+    for (i, post) in threadmarks.into_iter().enumerate() {
+        let chapter = (i as u32) + 1;
+
+        tracing::info!("[{}] Scraping chapter {}", url, chapter);
+
+        let url = forum.reader_url(&id, chapter)?;
+
+        // Reader mode pages hold multiple threadmarks, only request new pages
+        let body = match page.take() {
+            Some((page_url, body)) if page_url == url => body,
+            _ => Arc::new(req(&url).await?),
+        };
+
+        page = Some((url, body.clone()));
+
+        let name = forum.name;
+
+        story.chapters.push(
+            tokio::task::spawn_blocking(move || get_chapter(body, name, &post))
+                .await
+                .expect("Thread pool closed")?,
+        );
+    }
+
+    story.words = story.chapters.iter().map(|c| word_count(&c.main)).sum();
+
+    Ok(story)
+}
+
+/// Returns the post ID of every threadmark on the thread's threadmark index.
+pub fn get_threadmarks(
+    html: impl Into<Document>,
+    name: &'static str,
+) -> anyhow::Result<Vec<String>> {
+    let html = html.into();
+
+    html.select(STORY_THREADMARKS)
+        .into_iter()
+        .map(|ele| {
+            ele.attr("href")
+                .and_then(|href| {
+                    href.trim_end_matches('/')
+                        .rsplit('/')
+                        .next()
+                        .and_then(|segment| segment.strip_prefix("post-"))
+                        .map(String::from)
+                })
+                .ok_or_else(|| {
+                    anyhow::anyhow!("Element attribute for site {} not found: {}", name, "href")
+                })
+        })
+        .collect()
+}
+
+pub fn get_details(
+    html: impl Into<Document>,
+    name: &'static str,
+    rating: Rating,
+) -> anyhow::Result<Details> {
+    let html = html.into();
+
+    let title = stry_scraper::string(&html, STORY_NAME, name)?
+        .trim()
+        .to_string();
+
+    let authors = stry_scraper::string_vec(&html, STORY_AUTHOR, name)?;
+
+    let tags = stry_scraper::string_vec(&html, STORY_TAGS, name)?
+        .into_iter()
+        .map(|tag| (TagType::General, tag.trim().to_string()))
+        .collect::<Vec<_>>();
+
+    let mut state = State::InProgress;
+
+    for stat in html.select(STORY_STATS) {
+        let key = stat.select("dt").first().and_then(|ele| ele.text());
+        let value = stat.select("dd").first().and_then(|ele| ele.text());
+
+        if let (Some("Status"), Some(value)) = (key.as_deref().map(str::trim), value) {
+            state = match value.trim() {
+                "Complete" | "Completed" => State::Completed,
+                "Hiatus" => State::Hiatus,
+                "Cancelled" | "Abandoned" => State::Abandoned,
+                _ => State::InProgress,
+            };
+        }
+    }
+
+    let dates = html
+        .select(STORY_THREADMARKS_DATE)
+        .into_iter()
+        .map(|ele| {
+            ele.attr("data-time")
+                .and_then(|ts| Utc.datetime_from_str(&ts, "%s").ok())
+        })
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| anyhow::anyhow!("Unparsable date time for site {}", name))?;
+
+    Ok(Details {
+        name: title,
+        summary: String::new(),
+
+        chapters: dates.len() as u32,
+        language: Language::English,
+        rating,
+        state,
+
+        authors,
+        origins: Vec::new(),
+        tags,
+        pairings: Vec::new(),
+
+        series: None,
+
+        created: dates
+            .first()
+            .copied()
+            .ok_or_else(|| anyhow::anyhow!("Unparsable date time for site {}", name))?,
+        updated: dates
+            .iter()
+            .max()
+            .copied()
+            .ok_or_else(|| anyhow::anyhow!("Unparsable date time for site {}", name))?,
+    })
+}
+
+/// Gets the chapter from the threadmarked post with the given ID, the post
+/// can be on any page of the thread.
+pub fn get_chapter(
+    html: impl Into<Document>,
+    name: &'static str,
+    post: &str,
+) -> anyhow::Result<Chapter> {
+    let html = html.into();
+
+    let selector = format!("#js-post-{}", post);
+
+    let post = html
+        .select(selector.as_str())
+        .into_iter()
+        .next()
+        .ok_or_else(|| ScrapeError::layout_changed(name, selector))?;
+
+    let main = stry_remark::parse(
+        post.select(POST_CONTENT)
+            .first()
+            .and_then(|ele| ele.inner_html())
+            .ok_or_else(|| ScrapeError::layout_changed(name, POST_CONTENT))?,
+    )?
+    .trim()
+    .to_string();
+
+    let title = post
+        .select(POST_NAME)
+        .first()
+        .and_then(|ele| ele.text())
+        .map(|title| title.trim().to_string())
+        .ok_or_else(|| ScrapeError::layout_changed(name, POST_NAME))?;
+
+    Ok(Chapter {
+        name: title,
+        words: word_count(&main),
+        pre: String::new(),
+        post: String::new(),
+        main,
+    })
+}
+
+#[async_trait::async_trait]
+impl SiteScraper for Forum {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn hosts(&self) -> Vec<&str> {
+        vec![self.host]
+    }
+
+    fn id_from_url(&self, url: &Uri) -> anyhow::Result<String> {
+        id_from_url(url)
+    }
+
+    fn details_url(&self, id: &str) -> anyhow::Result<Uri> {
+        self.threadmarks_url(id)
+    }
+
+    async fn get_details(&self, story: &mut SiteRef) -> anyhow::Result<Details> {
+        let document = story.document().await?;
+        let name = self.name;
+        let rating = self.rating.clone();
+
+        let details = tokio::task::spawn_blocking(move || get_details(document, name, rating))
+            .await
+            .expect("Thread pool closed")?;
+
+        Ok(details)
+    }
+
+    async fn get_chapter(&self, story: &mut SiteRef, chapter: u32) -> anyhow::Result<Chapter> {
+        let document = story.document().await?;
+        let name = self.name;
+
+        let threadmarks = tokio::task::spawn_blocking(move || get_threadmarks(document, name))
+            .await
+            .expect("Thread pool closed")?;
+
+        let post = threadmarks
+            .get((chapter as usize).saturating_sub(1))
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Story {} has no chapter {}", story.id(), chapter))?;
+
+        let url = self.reader_url(story.id(), chapter)?;
+
+        let body = req(&url).await?;
+
+        let chapter = tokio::task::spawn_blocking(move || get_chapter(body, name, &post))
+            .await
+            .expect("Thread pool closed")?;
+
+        Ok(chapter)
+    }
+
+    fn parse_details(&self, html: String) -> anyhow::Result<Details> {
+        get_details(html, self.name, self.rating.clone())
+    }
+
+    fn parse_chapter(
+        &self,
+        html: String,
+        _chapter: u32,
+        post: Option<&str>,
+    ) -> anyhow::Result<Chapter> {
+        let post = post
+            .ok_or_else(|| anyhow::anyhow!("Site {} needs the post of the chapter", self.name))?;
+
+        get_chapter(html, self.name, post)
+    }
+}
@@ -1,16 +1,13 @@
 use {
-    crate::{Site, Sites},
+    crate::{events, sites, ScrapeError},
+    chrono::Utc,
     stry_backend::DataBackend,
     stry_evermore::Worker,
-    stry_models::WorkerSite,
+    stry_models::{TaskEvent, WorkerTask},
 };
 
 macro_rules! stop {
     ($lbl:lifetime, $state:expr) => {
-        // tracing::info!("Delay for 1 second");
-
-        tokio::time::delay_for(tokio::time::Duration::from_millis(1)).await;
-
         if $state.should_stop() {
             tracing::info!("Received shutdown signal, shutting down");
 
@@ -19,6 +16,39 @@ macro_rules! stop {
     };
 }
 
+/// Waits on the future unless the worker is told to stop first, in which
+/// case the future is dropped and the loop is left.
+macro_rules! cancellable {
+    ($lbl:lifetime, $state:expr, $fut:expr) => {
+        tokio::select! {
+            value = $fut => value,
+            _ = $state.cancelled() => {
+                tracing::info!("Received shutdown signal, shutting down");
+
+                break $lbl;
+            }
+        }
+    };
+}
+
+/// Records a task's error and moves on to the next task, instead of stopping
+/// the worker.
+macro_rules! attempt {
+    ($lbl:lifetime, $worker:expr, $task:expr, $res:expr) => {
+        match $res {
+            Ok(value) => value,
+            Err(err) => {
+                fail(&$worker, &$task, err).await?;
+
+                continue $lbl;
+            }
+        }
+    };
+}
+
+/// How long a task waits before it is tried again after a temporary error.
+const RETRY_DELAY: i64 = 5 * 60;
+
 #[allow(clippy::unit_arg)]
 #[tracing::instrument(skip(worker), err)]
 pub async fn task(worker: Worker<DataBackend>) -> anyhow::Result<()> {
@@ -29,7 +59,11 @@ pub async fn task(worker: Worker<DataBackend>) -> anyhow::Result<()> {
             Some(task) => task,
             None => {
                 // Task check runs every 30 seconds
-                tokio::time::delay_for(tokio::time::Duration::from_secs(30)).await;
+                cancellable!(
+                    'l,
+                    worker,
+                    tokio::time::delay_for(tokio::time::Duration::from_secs(30))
+                );
 
                 continue;
             }
@@ -39,31 +73,98 @@ pub async fn task(worker: Worker<DataBackend>) -> anyhow::Result<()> {
 
         stop!('l, worker);
 
-        let site = match task.site {
-            WorkerSite::ArchiveOfOurOwn => Sites::ArchiveOfOurOwn,
-            WorkerSite::FanFictionNet => Sites::FanFictionNet,
-        };
-
-        let mut init = site.init_from_url(task.url.as_str())?;
+        // The site is found from the URL's host, this includes loaded site definitions
+        let mut init = attempt!('l, worker, task, sites::init_from_url(task.url.as_str()));
 
         stop!('l, worker);
 
-        let _details = init.get_details().await?;
+        let details = attempt!(
+            'l,
+            worker,
+            task,
+            cancellable!('l, worker, init.get_details())
+        );
 
         stop!('l, worker);
 
-        // Get story chapter (loop)
+        // Chapters fetched before the task was stopped, or the last time the
+        // story was checked, are skipped
+        for number in (task.chapter.max(0) as u32 + 1)..=details.chapters {
+            let _chapter = attempt!(
+                'l,
+                worker,
+                task,
+                cancellable!('l, worker, init.get_chapter(number))
+            );
+
+            // TODO: store story chapter
+
+            worker
+                .data
+                .update_task(
+                    task.id.clone().into(),
+                    number as i32,
+                    details.chapters as i32,
+                )
+                .await?;
+
+            events::send(TaskEvent::Progress {
+                task: task.id.clone(),
+                chapter: number,
+                chapters: details.chapters,
+            });
+
+            stop!('l, worker);
+        }
 
-        stop!('l, worker);
+        // TODO: store the story and complete the task, until then the task
+        //       stays queued with its progress, and is checked again after
+        //       the same wait as an empty queue
+        cancellable!(
+            'l,
+            worker,
+            tokio::time::delay_for(tokio::time::Duration::from_secs(30))
+        );
+    }
+
+    Ok(())
+}
 
-        // Store story chapter (loop)
+/// Stores the reason a task failed, tasks that failed because of a
+/// temporary [`ScrapeError`] are kept to be tried again after
+/// [`RETRY_DELAY`], other tasks are worked on in the meantime.
+async fn fail(
+    worker: &Worker<DataBackend>,
+    task: &WorkerTask,
+    err: anyhow::Error,
+) -> anyhow::Result<()> {
+    let retry = err
+        .downcast_ref::<ScrapeError>()
+        .map(ScrapeError::is_retryable)
+        .unwrap_or(false);
+
+    if retry {
+        tracing::warn!("Task {} will be retried: {}", task.id, err);
+    } else {
+        tracing::error!("Task {} failed: {}", task.id, err);
+    }
 
-        stop!('l, worker);
+    let retry_at = if retry {
+        Some(Utc::now() + chrono::Duration::seconds(RETRY_DELAY))
+    } else {
+        None
+    };
 
-        // Move story from working
+    worker
+        .data
+        .fail_task(task.id.clone().into(), err.to_string().into(), retry_at)
+        .await?;
 
-        stop!('l, worker);
-    }
+    events::send(TaskEvent::Failed {
+        task: task.id.clone(),
+        error: err.to_string(),
+        retry,
+    });
 
     Ok(())
 }
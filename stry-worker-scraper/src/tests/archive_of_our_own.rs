@@ -1,7 +1,11 @@
 use {
     crate::{
         archive_of_our_own,
-        models::{Chapter, Details, Language, Rating, State},
+        models::{
+            Chapter, Details, Language, ListedStory, Listing, Pairing, Rating, Series, State,
+            TagType, WorkerSite,
+        },
+        ScrapeError,
     },
     chrono::prelude::*,
 };
@@ -11,6 +15,11 @@ const MULTIPLE_CHAPTERS_HTML: &str =
 const MULTIPLE_CHAPTERS_MARKDOWN: &str =
     include_str!("./data/archive-of-our-own/multiple-chapters.md");
 
+const BOOKMARKS_HTML: &str = include_str!("./data/archive-of-our-own/bookmarks.html");
+
+const LOGIN_HTML: &str = include_str!("./data/archive-of-our-own/login.html");
+const RESTRICTED_HTML: &str = include_str!("./data/archive-of-our-own/restricted.html");
+
 const SINGLE_CHAPTER_HTML: &str = include_str!("./data/archive-of-our-own/single-chapter.html");
 const SINGLE_CHAPTER_MARKDOWN: &str = include_str!("./data/archive-of-our-own/single-chapter.md");
 
@@ -22,8 +31,8 @@ fn multiple_chapters__chapter() {
             name: String::from("Before"),
             main: String::from(MULTIPLE_CHAPTERS_MARKDOWN),
             words: 6504,
-            pre: String::new(),
-            post: String::new(),
+            pre: String::from("Rhea and her friends, just a bunch of ordinary students, prepare for exams and can't wait for the Hunger Games to start.\n\nExam season and the Reapings arrive together."),
+            post: String::from("The second half is already written and will be posted next week."),
         },
         archive_of_our_own::get_chapter(MULTIPLE_CHAPTERS_HTML, 1).unwrap()
     );
@@ -41,7 +50,30 @@ fn multiple_chapters__details() {
         state: State::Completed,
         authors: vec![String::from("quiet_wraith")],
         origins: vec![String::from("Hunger Games Series - All Media Types")],
-        tags: vec![],
+        tags: vec![
+            (TagType::Warning, String::from("Creator Chose Not To Use Archive Warnings")),
+            (TagType::Character, String::from("Original Characters")),
+            (TagType::General, String::from("Canon-Typical Behavior")),
+            (TagType::General, String::from("Worldbuilding")),
+            (TagType::General, String::from("The Capitol")),
+            (TagType::General, String::from("Original Character-centric")),
+            (TagType::General, String::from("The Rebellion")),
+        ],
+        pairings: vec![
+            Pairing {
+                characters: vec![String::from("Original Female Character(s)"), String::from("Original Male Character(s)")],
+                platonic: true,
+            },
+            Pairing {
+                characters: vec![String::from("Katniss Everdeen"), String::from("Peeta Mellark")],
+                platonic: false,
+            },
+        ],
+        series: Some(Series {
+            name: String::from("Capitol Stories"),
+            summary: String::new(),
+            place: Some(1),
+        }),
         created: Utc.ymd(2019, 9, 27).and_hms(0, 0, 0),
         updated: Utc.ymd(2019, 10, 4).and_hms(0, 0, 0),
     }, archive_of_our_own::get_details(MULTIPLE_CHAPTERS_HTML).unwrap());
@@ -56,7 +88,7 @@ fn single_chapter__chapter() {
             main: String::from(SINGLE_CHAPTER_MARKDOWN),
             words: 1075,
             pre: String::new(),
-            post: String::new(),
+            post: String::from("This story is heavily inspired by a real-life person. At the time of writing, a former Nazi by the name of Bruno Dey is on trial for having been a concentration camp guard in 1944."),
         },
         archive_of_our_own::get_chapter(SINGLE_CHAPTER_HTML, 1).unwrap()
     );
@@ -74,8 +106,147 @@ fn single_chapter__details() {
         state: State::Completed,
         authors: vec![String::from("quiet_wraith")],
         origins: vec![String::from("Hunger Games Series - All Media Types")],
-        tags: vec![],
+        tags: vec![
+            (TagType::Warning, String::from("No Archive Warnings Apply")),
+            (TagType::Character, String::from("Original Characters")),
+            (TagType::General, String::from("Original Character-centric")),
+            (TagType::General, String::from("Canon-Typical Violence")),
+            (TagType::General, String::from("District 2")),
+            (TagType::General, String::from("Peacekeepers")),
+            (TagType::General, String::from("Post-Canon")),
+            (TagType::General, String::from("Crimes Against Humanity")),
+        ],
+        pairings: vec![],
+        series: None,
         created: Utc.ymd(2020, 1, 5).and_hms(0, 0, 0),
         updated: Utc.ymd(2020, 1, 5).and_hms(0, 0, 0),
     }, archive_of_our_own::get_details(SINGLE_CHAPTER_HTML).unwrap());
 }
+
+#[test]
+fn not_rated() {
+    let html = SINGLE_CHAPTER_HTML.replace(
+        "<a class=\"tag\" href=\"/tags/Teen%20And%20Up%20Audiences/works\">Teen And Up Audiences</a>",
+        "<a class=\"tag\" href=\"/tags/Not%20Rated/works\">Not Rated</a>",
+    );
+
+    assert_eq!(
+        Rating::Explicit,
+        archive_of_our_own::get_details(html).unwrap().rating
+    );
+}
+
+#[test]
+fn unknown_rating() {
+    let html =
+        SINGLE_CHAPTER_HTML.replace(">Teen And Up Audiences</a>", ">Rated By The Council</a>");
+
+    let err = archive_of_our_own::get_details(html).unwrap_err();
+
+    match err.downcast_ref::<ScrapeError>() {
+        Some(ScrapeError::LayoutChanged { selector, .. }) => {
+            assert_eq!(".work > .rating.tags > ul > li > .tag", selector)
+        }
+        _ => panic!("Expected a layout error, got: {}", err),
+    }
+}
+
+#[test]
+fn unknown_chapters() {
+    let html = SINGLE_CHAPTER_HTML.replace(
+        "<dd class=\"chapters\">1/1</dd>",
+        "<dd class=\"chapters\">one</dd>",
+    );
+
+    assert!(archive_of_our_own::get_details(html).is_err());
+}
+
+#[test]
+fn authenticity_token() {
+    assert_eq!(
+        "bG9naW4tcGFnZS1mb3JtLXRva2Vu",
+        archive_of_our_own::get_authenticity_token(LOGIN_HTML).unwrap()
+    );
+}
+
+#[test]
+fn restricted() {
+    assert!(archive_of_our_own::is_restricted(RESTRICTED_HTML));
+    assert!(!archive_of_our_own::is_restricted(LOGIN_HTML));
+    assert!(!archive_of_our_own::is_restricted(SINGLE_CHAPTER_HTML));
+}
+
+#[test]
+fn pairing() {
+    assert_eq!(
+        Pairing {
+            characters: vec![String::from("Clarke Griffin"), String::from("Lexa")],
+            platonic: false,
+        },
+        archive_of_our_own::parse_pairing("Clarke Griffin/Lexa")
+    );
+
+    assert_eq!(
+        Pairing {
+            characters: vec![
+                String::from("Harry Potter"),
+                String::from("Ron Weasley"),
+                String::from("Hermione Granger"),
+            ],
+            platonic: true,
+        },
+        archive_of_our_own::parse_pairing("Harry Potter & Ron Weasley & Hermione Granger")
+    );
+}
+
+#[test]
+fn is_listing() {
+    for url in &[
+        "https://archiveofourown.org/users/quietwraith/works",
+        "https://archiveofourown.org/users/quietreader/bookmarks",
+        "https://archiveofourown.org/users/quietwraith/pseuds/wraith/works",
+        "https://archiveofourown.org/collections/capitol_stories/works",
+    ] {
+        assert!(
+            archive_of_our_own::is_listing(&url.parse().unwrap()),
+            "{}",
+            url
+        );
+    }
+
+    for url in &[
+        "https://archiveofourown.org/works/24462004",
+        "https://archiveofourown.org/users/quietwraith",
+        "https://archiveofourown.org/collections/capitol_stories",
+    ] {
+        assert!(
+            !archive_of_our_own::is_listing(&url.parse().unwrap()),
+            "{}",
+            url
+        );
+    }
+}
+
+#[test]
+fn listing() {
+    assert_eq!(
+        Listing {
+            stories: vec![
+                ListedStory {
+                    site: WorkerSite::ArchiveOfOurOwn,
+                    name: String::from("Fellow Traveler"),
+                    url: String::from("https://archiveofourown.org/works/24462004"),
+                },
+                ListedStory {
+                    site: WorkerSite::ArchiveOfOurOwn,
+                    name: String::from("Bruno Dey"),
+                    url: String::from("https://archiveofourown.org/works/26020435"),
+                },
+            ],
+            next: Some(String::from(
+                "https://archiveofourown.org/users/quietreader/bookmarks?page=2"
+            )),
+        },
+        archive_of_our_own::get_listing(BOOKMARKS_HTML).unwrap()
+    );
+}
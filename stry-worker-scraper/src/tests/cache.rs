@@ -0,0 +1,138 @@
+use {
+    crate::{cache::Cache, http::Client, tests::http::MockServer, Uri},
+    std::{env, fs, path::PathBuf},
+    stry_config::{RateLimit, Scraper},
+};
+
+fn directory(name: &str) -> PathBuf {
+    let directory = env::temp_dir().join(format!("stry-cache-{}-{}", name, std::process::id()));
+
+    let _ = fs::remove_dir_all(&directory);
+
+    directory
+}
+
+fn client(directory: &PathBuf, offline: bool) -> Client {
+    Client::new(&Scraper {
+        timeout: 5,
+        retries: 0,
+        rate_limit: RateLimit {
+            burst: 10,
+            interval: 0,
+        },
+        cache: stry_config::Cache {
+            enabled: true,
+            directory: directory.to_string_lossy().into_owned(),
+            offline,
+        },
+        ..Scraper::default()
+    })
+    .unwrap()
+}
+
+const PAGE: &str =
+    "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: 4\r\nConnection: close\r\n\r\npage";
+const NOT_MODIFIED: &str = "HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n";
+
+#[test]
+fn insert_and_get() {
+    let directory = directory("insert");
+    let cache = Cache::new(&directory);
+
+    let url: Uri = "https://example.com/story/1".parse().unwrap();
+
+    assert!(cache.get(&url).unwrap().is_none());
+
+    cache
+        .insert(&url, Some(String::from("\"v1\"")), None, "page")
+        .unwrap();
+
+    let (entry, body) = cache.get(&url).unwrap().unwrap();
+
+    assert_eq!("page", body);
+    assert_eq!("https://example.com/story/1", entry.url);
+    assert_eq!(Some("\"v1\""), entry.etag.as_deref());
+    assert_eq!(None, entry.last_modified);
+
+    fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn bodies_are_shared() {
+    let directory = directory("shared");
+    let cache = Cache::new(&directory);
+
+    let one: Uri = "https://example.com/story/1".parse().unwrap();
+    let two: Uri = "https://example.com/story/2".parse().unwrap();
+
+    cache.insert(&one, None, None, "page").unwrap();
+    cache.insert(&two, None, None, "page").unwrap();
+
+    assert_eq!(2, fs::read_dir(directory.join("entries")).unwrap().count());
+    assert_eq!(1, fs::read_dir(directory.join("bodies")).unwrap().count());
+
+    fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn prune() {
+    let directory = directory("prune");
+    let cache = Cache::new(&directory);
+
+    let one: Uri = "https://example.com/story/1".parse().unwrap();
+    let two: Uri = "https://example.com/story/2".parse().unwrap();
+
+    cache.insert(&one, None, None, "one").unwrap();
+    cache.insert(&two, None, None, "two").unwrap();
+
+    let pruned = cache.prune(Some(chrono::Duration::days(1))).unwrap();
+
+    assert_eq!(0, pruned.entries);
+    assert_eq!(0, pruned.bodies);
+
+    let pruned = cache.prune(None).unwrap();
+
+    assert_eq!(2, pruned.entries);
+    assert_eq!(2, pruned.bodies);
+    assert!(cache.get(&one).unwrap().is_none());
+
+    fs::remove_dir_all(&directory).unwrap();
+}
+
+#[tokio::test]
+async fn revalidates_cached_pages() {
+    let directory = directory("revalidate");
+    let server = MockServer::new(vec![PAGE, NOT_MODIFIED]);
+
+    let client = client(&directory, false);
+    let url: Uri = server.url.parse().unwrap();
+
+    assert_eq!("page", client.get(&url).await.unwrap());
+    assert_eq!("page", client.get(&url).await.unwrap());
+
+    let requests = server.requests.lock().unwrap();
+
+    assert_eq!(2, requests.len());
+    assert!(!requests[0].to_lowercase().contains("if-none-match"));
+    assert!(requests[1].to_lowercase().contains("if-none-match: \"v1\""));
+
+    fs::remove_dir_all(&directory).unwrap();
+}
+
+#[tokio::test]
+async fn offline_uses_cached_pages() {
+    let directory = directory("offline");
+    let url: Uri = "http://127.0.0.1:1/story/1".parse().unwrap();
+
+    let client = client(&directory, true);
+
+    assert!(client.get(&url).await.is_err());
+
+    Cache::new(&directory)
+        .insert(&url, None, None, "page")
+        .unwrap();
+
+    assert_eq!("page", client.get(&url).await.unwrap());
+
+    fs::remove_dir_all(&directory).unwrap();
+}
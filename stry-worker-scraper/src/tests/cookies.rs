@@ -0,0 +1,89 @@
+use {
+    crate::cookies::Cookies,
+    isahc::http::{header::SET_COOKIE, HeaderMap, HeaderValue},
+};
+
+#[test]
+fn header() {
+    let cookies = Cookies::new();
+
+    cookies.set("archiveofourown.org", "_otwarchive_session", "abc");
+    cookies.set(".archiveofourown.org", "user_credentials", "1");
+    cookies.set("fanfiction.net", "session", "xyz");
+
+    assert_eq!(
+        Some(String::from("_otwarchive_session=abc; user_credentials=1")),
+        cookies.header("archiveofourown.org")
+    );
+    assert_eq!(
+        Some(String::from("_otwarchive_session=abc; user_credentials=1")),
+        cookies.header("www.archiveofourown.org")
+    );
+    assert_eq!(None, cookies.header("wattpad.com"));
+
+    // Subdomain cookies are not sent to the parent domain
+    assert_eq!(
+        Some(String::from("session=xyz")),
+        cookies.header("www.fanfiction.net")
+    );
+    cookies.set("m.fanfiction.net", "mobile", "1");
+    assert_eq!(
+        Some(String::from("session=xyz")),
+        cookies.header("fanfiction.net")
+    );
+}
+
+#[test]
+fn store() {
+    let cookies = Cookies::new();
+    let mut headers = HeaderMap::new();
+
+    headers.append(
+        SET_COOKIE,
+        HeaderValue::from_static("_otwarchive_session=abc; path=/; HttpOnly"),
+    );
+    headers.append(
+        SET_COOKIE,
+        HeaderValue::from_static("user_credentials=1; domain=.archiveofourown.org; path=/"),
+    );
+
+    cookies.store("archiveofourown.org", &headers);
+
+    assert_eq!(
+        Some(String::from("abc")),
+        cookies.get("archiveofourown.org", "_otwarchive_session")
+    );
+    assert_eq!(
+        Some(String::from("1")),
+        cookies.get("www.archiveofourown.org", "user_credentials")
+    );
+
+    let mut headers = HeaderMap::new();
+
+    headers.append(
+        SET_COOKIE,
+        HeaderValue::from_static("user_credentials=; domain=.archiveofourown.org; max-age=0"),
+    );
+
+    cookies.store("archiveofourown.org", &headers);
+
+    assert_eq!(None, cookies.get("archiveofourown.org", "user_credentials"));
+}
+
+#[test]
+fn import() {
+    let cookies = Cookies::new();
+
+    let count = cookies.import(
+        "# Netscape HTTP Cookie File\n\
+         \n\
+         .archiveofourown.org\tTRUE\t/\tTRUE\t1924905600\tuser_credentials\t1\n\
+         #HttpOnly_archiveofourown.org\tFALSE\t/\tTRUE\t0\t_otwarchive_session\tabc\n",
+    );
+
+    assert_eq!(2, count);
+    assert_eq!(
+        Some(String::from("user_credentials=1; _otwarchive_session=abc")),
+        cookies.header("archiveofourown.org")
+    );
+}
@@ -1,11 +1,15 @@
 use {
     crate::{
         fanfiction,
-        models::{Chapter, Details, Language, Rating, State},
+        models::{Chapter, Details, Language, ListedStory, Listing, Rating, State, WorkerSite},
+        ScrapeError,
     },
     chrono::prelude::*,
 };
 
+const AUTHOR_HTML: &str = include_str!("./data/fanfiction/author.html");
+const COMMUNITY_HTML: &str = include_str!("./data/fanfiction/community.html");
+
 const MULTIPLE_CHAPTERS_HTML: &str = include_str!("./data/fanfiction/multiple-chapters.html");
 const MULTIPLE_CHAPTERS_MARKDOWN: &str = include_str!("./data/fanfiction/multiple-chapters.md");
 
@@ -40,6 +44,8 @@ fn multiple_chapters__details() {
         authors: vec![String::from("quietwraith")],
         origins: vec![String::from("Hunger Games")],
         tags: vec![],
+        pairings: vec![],
+        series: None,
         created: Utc.ymd(2019, 9, 27).and_hms(22, 30, 35),
         updated: Utc.ymd(2019, 10, 4).and_hms(11, 44, 18),
     }, fanfiction::get_details(MULTIPLE_CHAPTERS_HTML).unwrap());
@@ -73,7 +79,106 @@ fn single_chapter__details() {
         authors: vec![String::from("quietwraith")],
         origins: vec![String::from("Hunger Games")],
         tags: vec![],
+        pairings: vec![],
+        series: None,
         created: Utc.ymd(2020, 1, 5).and_hms(21, 0, 55),
         updated: Utc.ymd(2020, 1, 5).and_hms(21, 0, 55),
     }, fanfiction::get_details(SINGLE_CHAPTER_HTML).unwrap());
 }
+
+#[test]
+fn layout_changed() {
+    let err = fanfiction::get_chapter("<html><body></body></html>").unwrap_err();
+
+    match err.downcast_ref::<ScrapeError>() {
+        Some(ScrapeError::LayoutChanged { selector, .. }) => assert_eq!("#storytext", selector),
+        _ => panic!("Expected a layout error, got: {}", err),
+    }
+}
+
+#[test]
+fn id_from_url() {
+    let err =
+        fanfiction::id_from_url(&"https://www.fanfiction.net/s".parse().unwrap()).unwrap_err();
+
+    assert!(matches!(
+        err.downcast_ref::<ScrapeError>(),
+        Some(ScrapeError::NotFound { .. })
+    ));
+}
+
+#[test]
+fn is_listing() {
+    for url in &[
+        "https://www.fanfiction.net/u/12345678/quietwraith",
+        "https://www.fanfiction.net/community/Capitol-Stories/123456/",
+    ] {
+        assert!(fanfiction::is_listing(&url.parse().unwrap()), "{}", url);
+    }
+
+    assert!(!fanfiction::is_listing(
+        &"https://www.fanfiction.net/s/13389741/1/Fellow-Traveler"
+            .parse()
+            .unwrap()
+    ));
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn listing__author() {
+    assert_eq!(
+        Listing {
+            stories: vec![
+                ListedStory {
+                    site: WorkerSite::FanFictionNet,
+                    name: String::from("Fellow Traveler"),
+                    url: String::from("https://www.fanfiction.net/s/13389741"),
+                },
+                ListedStory {
+                    site: WorkerSite::FanFictionNet,
+                    name: String::from("Little Cog"),
+                    url: String::from("https://www.fanfiction.net/s/13252950"),
+                },
+            ],
+            next: None,
+        },
+        fanfiction::get_listing(
+            &"https://www.fanfiction.net/u/12345678/quietwraith"
+                .parse()
+                .unwrap(),
+            AUTHOR_HTML
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn listing__community() {
+    assert_eq!(
+        Listing {
+            stories: vec![
+                ListedStory {
+                    site: WorkerSite::FanFictionNet,
+                    name: String::from("Fellow Traveler"),
+                    url: String::from("https://www.fanfiction.net/s/13389741"),
+                },
+                ListedStory {
+                    site: WorkerSite::FanFictionNet,
+                    name: String::from("Someone Else's Story"),
+                    url: String::from("https://www.fanfiction.net/s/12996420"),
+                },
+            ],
+            next: Some(String::from(
+                "https://www.fanfiction.net/community/Capitol-Stories/123456/99/0/2/0/0/0/0/"
+            )),
+        },
+        fanfiction::get_listing(
+            &"https://www.fanfiction.net/community/Capitol-Stories/123456/"
+                .parse()
+                .unwrap(),
+            COMMUNITY_HTML
+        )
+        .unwrap()
+    );
+}
@@ -0,0 +1,125 @@
+use {
+    crate::{
+        ficwad,
+        models::{Chapter, Details, Language, Rating, State, TagType},
+    },
+    chrono::prelude::*,
+};
+
+const MULTIPLE_CHAPTERS_HTML: &str = include_str!("./data/ficwad/multiple-chapters.html");
+const MULTIPLE_CHAPTERS_CHAPTER_HTML: &str =
+    include_str!("./data/ficwad/multiple-chapters-chapter.html");
+const MULTIPLE_CHAPTERS_CHAPTER_MARKDOWN: &str =
+    include_str!("./data/ficwad/multiple-chapters-chapter.md");
+
+const SINGLE_CHAPTER_HTML: &str = include_str!("./data/ficwad/single-chapter.html");
+const SINGLE_CHAPTER_MARKDOWN: &str = include_str!("./data/ficwad/single-chapter.md");
+
+#[test]
+#[allow(non_snake_case)]
+fn multiple_chapters__chapter() {
+    assert_eq!(
+        Chapter {
+            name: String::from("The Apprentice"),
+            main: String::from(MULTIPLE_CHAPTERS_CHAPTER_MARKDOWN),
+            words: 81,
+            pre: String::new(),
+            post: String::new(),
+        },
+        ficwad::get_chapter(MULTIPLE_CHAPTERS_CHAPTER_HTML).unwrap(),
+    );
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn multiple_chapters__chapters() {
+    assert_eq!(
+        vec![String::from("287301"), String::from("287302")],
+        ficwad::get_chapters(MULTIPLE_CHAPTERS_HTML).unwrap(),
+    );
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn multiple_chapters__details() {
+    assert_eq!(
+        Details {
+            name: String::from("Salt and Ember"),
+            summary: String::from(
+                "Two apprentices, one forge, and a guild exam that only one of them can pass."
+            ),
+            chapters: 2,
+            language: Language::English,
+            rating: Rating::Teen,
+            state: State::Completed,
+            authors: vec![String::from("hollowpine")],
+            origins: vec![String::from("Avatar: The Last Airbender")],
+            tags: vec![
+                (TagType::Warning, String::from("Violence")),
+                (TagType::Character, String::from("Zuko")),
+                (TagType::Character, String::from("Original Character")),
+                (TagType::General, String::from("Drama")),
+                (TagType::General, String::from("Friendship")),
+            ],
+            pairings: vec![],
+            series: None,
+            created: Utc.ymd(2019, 3, 2).and_hms(18, 4, 11),
+            updated: Utc.ymd(2019, 4, 14).and_hms(9, 30, 52),
+        },
+        ficwad::get_details(MULTIPLE_CHAPTERS_HTML).unwrap()
+    );
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn single_chapter__chapter() {
+    assert_eq!(
+        Chapter {
+            name: String::from("Lantern Season"),
+            main: String::from(SINGLE_CHAPTER_MARKDOWN),
+            words: 62,
+            pre: String::new(),
+            post: String::new(),
+        },
+        ficwad::get_chapter(SINGLE_CHAPTER_HTML).unwrap(),
+    );
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn single_chapter__chapters() {
+    assert!(ficwad::get_chapters(SINGLE_CHAPTER_HTML)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn single_chapter__details() {
+    assert_eq!(
+        Details {
+            name: String::from("Lantern Season"),
+            summary: String::from(
+                "Every autumn the village floats its lanterns downriver. This year, one of them floats back."
+            ),
+            chapters: 1,
+            language: Language::English,
+            rating: Rating::General,
+            state: State::Completed,
+            authors: vec![String::from("hollowpine")],
+            origins: vec![String::from("Original Fiction")],
+            tags: vec![(TagType::General, String::from("General"))],
+            pairings: vec![],
+            series: None,
+            created: Utc.ymd(2018, 11, 23).and_hms(20, 15, 0),
+            updated: Utc.ymd(2018, 11, 23).and_hms(20, 15, 0),
+        },
+        ficwad::get_details(SINGLE_CHAPTER_HTML).unwrap()
+    );
+}
+
+#[test]
+fn site_ref() {
+    assert!(crate::init_from_url("https://ficwad.com/story/287300").is_ok());
+    assert!(crate::init_from_url("https://ficwad.com/a/hollowpine").is_err());
+}
@@ -0,0 +1,242 @@
+use {
+    crate::{
+        http::{retry_after, Bucket, Client},
+        ScrapeError,
+    },
+    isahc::http::{header::RETRY_AFTER, HeaderMap, HeaderValue},
+    std::{
+        io::{Read, Write},
+        net::TcpListener,
+        sync::{Arc, Mutex},
+        thread,
+        time::{Duration, Instant},
+    },
+    stry_config::{RateLimit, Scraper},
+};
+
+/// A local HTTP server that answers each connection with the next of the
+/// given responses, keeping the requests it was sent.
+pub struct MockServer {
+    pub url: String,
+    pub requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockServer {
+    pub fn new(responses: Vec<&'static str>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Unable to bind mock server");
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        thread::spawn({
+            let requests = requests.clone();
+
+            move || {
+                for response in responses {
+                    let (mut stream, _) = listener.accept().unwrap();
+
+                    let mut request = Vec::new();
+                    let mut buf = [0; 1024];
+
+                    while !request.ends_with(b"\r\n\r\n") {
+                        let read = stream.read(&mut buf).unwrap();
+
+                        if read == 0 {
+                            break;
+                        }
+
+                        request.extend_from_slice(&buf[..read]);
+                    }
+
+                    requests
+                        .lock()
+                        .unwrap()
+                        .push(String::from_utf8_lossy(&request).into_owned());
+
+                    stream.write_all(response.as_bytes()).unwrap();
+                }
+            }
+        });
+
+        Self { url, requests }
+    }
+
+    pub fn hits(&self) -> usize {
+        self.requests.lock().unwrap().len()
+    }
+}
+
+fn client(retries: u32) -> Client {
+    Client::new(&Scraper {
+        timeout: 5,
+        retries,
+        backoff: 10,
+        rate_limit: RateLimit {
+            burst: 10,
+            interval: 0,
+        },
+        ..Scraper::default()
+    })
+    .unwrap()
+}
+
+const OK: &str = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok";
+const NOT_FOUND: &str = "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+const TOO_MANY: &str =
+    "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 1\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+const UNAVAILABLE: &str =
+    "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+
+#[tokio::test]
+async fn retries_server_errors() {
+    let server = MockServer::new(vec![UNAVAILABLE, UNAVAILABLE, OK]);
+
+    let body = client(3).get(&server.url.parse().unwrap()).await.unwrap();
+
+    assert_eq!("ok", body);
+    assert_eq!(3, server.hits());
+}
+
+#[tokio::test]
+async fn honours_retry_after() {
+    let server = MockServer::new(vec![TOO_MANY, OK]);
+
+    let start = Instant::now();
+
+    let body = client(3).get(&server.url.parse().unwrap()).await.unwrap();
+
+    assert_eq!("ok", body);
+    assert_eq!(2, server.hits());
+    assert!(start.elapsed() >= Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn gives_up_after_retries() {
+    let server = MockServer::new(vec![UNAVAILABLE, UNAVAILABLE, UNAVAILABLE]);
+
+    let err = client(2)
+        .get(&server.url.parse().unwrap())
+        .await
+        .unwrap_err();
+
+    match err.downcast_ref::<ScrapeError>() {
+        Some(err @ ScrapeError::Network { .. }) => assert!(err.is_retryable()),
+        _ => panic!("Expected a network error, got: {}", err),
+    }
+
+    assert_eq!(3, server.hits());
+}
+
+#[tokio::test]
+async fn does_not_retry_client_errors() {
+    let server = MockServer::new(vec![NOT_FOUND]);
+
+    let err = client(3)
+        .get(&server.url.parse().unwrap())
+        .await
+        .unwrap_err();
+
+    match err.downcast_ref::<ScrapeError>() {
+        Some(err @ ScrapeError::NotFound { .. }) => assert!(!err.is_retryable()),
+        _ => panic!("Expected a not found error, got: {}", err),
+    }
+
+    assert_eq!(1, server.hits());
+}
+
+#[tokio::test]
+async fn sends_user_agent() {
+    let server = MockServer::new(vec![OK]);
+
+    client(0).get(&server.url.parse().unwrap()).await.unwrap();
+
+    let requests = server.requests.lock().unwrap();
+
+    assert!(requests[0]
+        .to_lowercase()
+        .contains("user-agent: mozilla/5.0"));
+}
+
+#[tokio::test]
+async fn keeps_cookies() {
+    let server = MockServer::new(vec![
+        "HTTP/1.1 200 OK\r\nSet-Cookie: session=abc; path=/\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok",
+        OK,
+    ]);
+
+    let client = client(0);
+    let url = server.url.parse().unwrap();
+
+    client.get(&url).await.unwrap();
+    client.get(&url).await.unwrap();
+
+    let requests = server.requests.lock().unwrap();
+
+    assert!(!requests[0].to_lowercase().contains("cookie:"));
+    assert!(requests[1].to_lowercase().contains("cookie: session=abc"));
+}
+
+#[tokio::test]
+async fn post_form_returns_redirects() {
+    let server = MockServer::new(vec![
+        "HTTP/1.1 302 Found\r\nLocation: /users/reader\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+    ]);
+
+    let res = client(0)
+        .post_form(
+            &server.url.parse().unwrap(),
+            &[("user[login]", "reader"), ("commit", "Log In")],
+        )
+        .await
+        .unwrap();
+
+    assert!(res.status.is_redirection());
+    assert_eq!(Some("/users/reader"), res.location.as_deref());
+}
+
+#[test]
+fn retry_after_header() {
+    let mut headers = HeaderMap::new();
+
+    assert_eq!(None, retry_after(&headers));
+
+    headers.insert(RETRY_AFTER, HeaderValue::from_static("120"));
+
+    assert_eq!(Some(Duration::from_secs(120)), retry_after(&headers));
+
+    // Dates in the past mean the request can be retried straight away
+    headers.insert(
+        RETRY_AFTER,
+        HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+    );
+
+    assert_eq!(Some(Duration::from_secs(0)), retry_after(&headers));
+}
+
+#[test]
+fn bucket() {
+    let start = Instant::now();
+
+    let mut bucket = Bucket::new(
+        RateLimit {
+            burst: 2,
+            interval: 1000,
+        },
+        start,
+    );
+
+    assert_eq!(None, bucket.take(start));
+    assert_eq!(None, bucket.take(start));
+    assert_eq!(Some(Duration::from_secs(1)), bucket.take(start));
+
+    assert_eq!(
+        Some(Duration::from_millis(500)),
+        bucket.take(start + Duration::from_millis(500))
+    );
+
+    assert_eq!(None, bucket.take(start + Duration::from_secs(1)));
+    assert_eq!(
+        Some(Duration::from_secs(1)),
+        bucket.take(start + Duration::from_secs(1))
+    );
+}
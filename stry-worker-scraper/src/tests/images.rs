@@ -0,0 +1,190 @@
+use {
+    crate::{
+        http::Client,
+        images::{self, Images},
+        tests::http::MockServer,
+        Uri,
+    },
+    std::{env, fs, path::PathBuf},
+    stry_config::{RateLimit, Scraper},
+};
+
+fn directory(name: &str) -> PathBuf {
+    let directory = env::temp_dir().join(format!("stry-images-{}-{}", name, std::process::id()));
+
+    let _ = fs::remove_dir_all(&directory);
+
+    directory
+}
+
+fn client(directory: &PathBuf) -> Client {
+    Client::new(&Scraper {
+        timeout: 5,
+        retries: 0,
+        backoff: 10,
+        rate_limit: RateLimit {
+            burst: 10,
+            interval: 0,
+        },
+        images: stry_config::Images {
+            enabled: true,
+            directory: directory.to_string_lossy().into_owned(),
+        },
+        ..Scraper::default()
+    })
+    .unwrap()
+}
+
+const IMAGE: &str =
+    "HTTP/1.1 200 OK\r\nContent-Type: image/png\r\nContent-Length: 5\r\nConnection: close\r\n\r\nimage";
+const NOT_FOUND: &str = "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+
+// sha256("image")
+const NAME: &str = "6105d6cc76af400325e94d588ce511be5bfdbb73b437dc51eca43917d7a43e3d.png";
+
+#[test]
+fn find() {
+    let markdown =
+        "Before ![a map](https://example.com/map.png) and ![no alt text](/art.jpg) after.";
+
+    let found = images::find(markdown)
+        .into_iter()
+        .map(|range| &markdown[range])
+        .collect::<Vec<_>>();
+
+    assert_eq!(vec!["https://example.com/map.png", "/art.jpg"], found);
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn find__title() {
+    let markdown = r#"![a map](https://example.com/map.png "The map")"#;
+
+    let found = images::find(markdown)
+        .into_iter()
+        .map(|range| &markdown[range])
+        .collect::<Vec<_>>();
+
+    assert_eq!(vec!["https://example.com/map.png"], found);
+}
+
+#[test]
+fn resolve() {
+    let base: Uri = "https://example.com/works/1/chapters/2".parse().unwrap();
+
+    let resolve = |src| images::resolve(&base, src).map(|url| url.to_string());
+
+    assert_eq!(
+        Some("https://cdn.example.com/art.png"),
+        resolve("https://cdn.example.com/art.png").as_deref()
+    );
+    assert_eq!(
+        Some("https://cdn.example.com/art.png"),
+        resolve("//cdn.example.com/art.png").as_deref()
+    );
+    assert_eq!(
+        Some("https://example.com/art.png"),
+        resolve("/art.png").as_deref()
+    );
+    assert_eq!(
+        Some("https://example.com/works/1/chapters/art.png"),
+        resolve("art.png").as_deref()
+    );
+    assert_eq!(
+        Some("https://example.com/works/1/chapters/art/one.png"),
+        resolve("./art/one.png").as_deref()
+    );
+    assert_eq!(
+        Some("https://example.com/works/1/art.png?size=2"),
+        resolve("../art.png?size=2").as_deref()
+    );
+
+    assert_eq!(None, resolve("data:image/png;base64,aW1hZ2U="));
+    assert_eq!(None, resolve("/images/stored.png"));
+    assert_eq!(None, resolve("#art"));
+}
+
+#[test]
+fn extension() {
+    assert_eq!(Some("png"), images::extension(Some("image/png")));
+    assert_eq!(
+        Some("jpg"),
+        images::extension(Some("image/jpeg; charset=binary"))
+    );
+    assert_eq!(None, images::extension(Some("text/html")));
+    assert_eq!(None, images::extension(Some("image/svg+xml")));
+    assert_eq!(None, images::extension(None));
+}
+
+#[test]
+fn images_are_shared() {
+    let directory = directory("shared");
+    let images = Images::new(&directory);
+
+    let one: Uri = "https://example.com/one.png".parse().unwrap();
+    let two: Uri = "https://example.com/two.png".parse().unwrap();
+
+    assert_eq!(None, images.get(&one).unwrap());
+
+    let name = images.insert(&one, Some("image/png"), b"image").unwrap();
+
+    assert_eq!(NAME, name);
+    assert_eq!(
+        name,
+        images.insert(&two, Some("image/png"), b"image").unwrap()
+    );
+    assert_eq!(Some(name), images.get(&two).unwrap());
+
+    assert!(images.insert(&one, Some("text/html"), b"<html>").is_err());
+
+    fs::remove_dir_all(&directory).unwrap();
+}
+
+#[tokio::test]
+async fn localize() {
+    let directory = directory("localize");
+    let server = MockServer::new(vec![IMAGE, IMAGE, NOT_FOUND]);
+
+    let client = client(&directory);
+    let base: Uri = server.url.parse().unwrap();
+
+    let markdown = format!(
+        "![one]({url}/one.png)\n\n![two](/two.png)\n\n![missing]({url}/missing.png)",
+        url = server.url
+    );
+
+    assert_eq!(
+        format!(
+            "![one](/images/{name})\n\n![two](/images/{name})\n\n![missing]({url}/missing.png)",
+            name = NAME,
+            url = server.url
+        ),
+        images::localize(&client, &base, &markdown).await
+    );
+
+    // Downloaded images are not requested again
+    images::localize(&client, &base, &format!("![one]({}/one.png)", server.url)).await;
+
+    assert_eq!(3, server.hits());
+
+    fs::remove_dir_all(&directory).unwrap();
+}
+
+#[tokio::test]
+#[allow(non_snake_case)]
+async fn localize__relative_with_title() {
+    let directory = directory("relative");
+    let server = MockServer::new(vec![IMAGE]);
+
+    let client = client(&directory);
+    let base: Uri = format!("{}/chapters/1", server.url).parse().unwrap();
+
+    assert_eq!(
+        format!(r#"![one](/images/{} "One")"#, NAME),
+        images::localize(&client, &base, r#"![one](one.png "One")"#).await
+    );
+
+    assert_eq!(1, server.hits());
+
+    fs::remove_dir_all(&directory).unwrap();
+}
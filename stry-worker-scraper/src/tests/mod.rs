@@ -1,2 +1,13 @@
 pub mod archive_of_our_own;
+pub mod cache;
+pub mod cookies;
 pub mod fanfiction;
+pub mod ficwad;
+pub mod http;
+pub mod images;
+pub mod royal_road;
+pub mod schedule;
+pub mod script;
+pub mod sites;
+pub mod wattpad;
+pub mod xenforo;
@@ -0,0 +1,84 @@
+use {
+    crate::{
+        models::{Chapter, Details, Language, Rating, State, TagType},
+        royal_road,
+    },
+    chrono::prelude::*,
+};
+
+const FICTION_HTML: &str = include_str!("./data/royal-road/fiction.html");
+const CHAPTER_HTML: &str = include_str!("./data/royal-road/chapter.html");
+const CHAPTER_MARKDOWN: &str = include_str!("./data/royal-road/chapter.md");
+
+#[test]
+fn chapter() {
+    assert_eq!(
+        Chapter {
+            name: String::from("Chapter 1: Cinders"),
+            main: String::from(CHAPTER_MARKDOWN),
+            words: 64,
+            pre: String::from("Welcome aboard! New chapters go up every Monday."),
+            post: String::from("Thanks for reading, comments are always welcome."),
+        },
+        royal_road::get_chapter(CHAPTER_HTML).unwrap(),
+    );
+}
+
+#[test]
+fn chapters() {
+    assert_eq!(
+        vec![
+            String::from("/fiction/35127/ashfall-academy/chapter/531201/chapter-1-cinders"),
+            String::from("/fiction/35127/ashfall-academy/chapter/533877/chapter-2-the-gate"),
+            String::from("/fiction/35127/ashfall-academy/chapter/536410/chapter-3-entrance"),
+        ],
+        royal_road::get_chapters(FICTION_HTML).unwrap(),
+    );
+}
+
+#[test]
+fn details() {
+    assert_eq!(
+        Details {
+            name: String::from("Ashfall Academy"),
+            summary: String::from(
+                "Every year the Academy takes one student from the ash villages.\n\nThis year, it took *two*."
+            ),
+            chapters: 3,
+            language: Language::English,
+            rating: Rating::Teen,
+            state: State::InProgress,
+            authors: vec![String::from("hollowpine")],
+            origins: vec![],
+            tags: vec![
+                (TagType::Warning, String::from("Profanity")),
+                (TagType::Warning, String::from("Gore")),
+                (TagType::General, String::from("Fantasy")),
+                (TagType::General, String::from("Magic")),
+                (TagType::General, String::from("School Life")),
+            ],
+            pairings: vec![],
+            series: None,
+            created: Utc.ymd(2020, 6, 1).and_hms(14, 0, 0),
+            updated: Utc.ymd(2020, 6, 15).and_hms(14, 30, 0),
+        },
+        royal_road::get_details(FICTION_HTML).unwrap()
+    );
+}
+
+#[test]
+fn id_from_url() {
+    assert_eq!(
+        "35127",
+        royal_road::id_from_url(
+            &"https://www.royalroad.com/fiction/35127/ashfall-academy"
+                .parse()
+                .unwrap()
+        )
+        .unwrap(),
+    );
+    assert!(
+        royal_road::id_from_url(&"https://www.royalroad.com/profile/118324".parse().unwrap())
+            .is_err()
+    );
+}
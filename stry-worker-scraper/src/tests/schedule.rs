@@ -0,0 +1,53 @@
+use {
+    crate::schedule::{self, Status},
+    chrono::{TimeZone, Utc},
+    stry_config::{Schedule, ScheduledJob},
+    stry_models::Settings,
+};
+
+fn setting(key: &str, value: &str) -> Settings {
+    Settings {
+        key: key.to_string(),
+        value: value.to_string(),
+        created: Utc.ymd(2020, 10, 1).and_hms(0, 0, 0),
+        updated: Utc.ymd(2020, 10, 1).and_hms(0, 0, 0),
+    }
+}
+
+#[test]
+fn status() {
+    let settings = vec![
+        setting("schedule.backup.last-run", "2020-10-01T04:00:00+00:00"),
+        setting("schedule.backup.next-run", "2020-10-02T04:00:00+00:00"),
+        setting("schedule.vacuum.next-run", "2020-10-04T03:30:00+00:00"),
+        setting("schedule.vacuum.unknown", "2020-10-04T03:30:00+00:00"),
+        setting("other.next-run", "2020-10-04T03:30:00+00:00"),
+    ];
+
+    assert_eq!(
+        vec![
+            Status {
+                name: "backup".to_string(),
+                last_run: Some(Utc.ymd(2020, 10, 1).and_hms(4, 0, 0)),
+                next_run: Some(Utc.ymd(2020, 10, 2).and_hms(4, 0, 0)),
+            },
+            Status {
+                name: "vacuum".to_string(),
+                last_run: None,
+                next_run: Some(Utc.ymd(2020, 10, 4).and_hms(3, 30, 0)),
+            },
+        ],
+        schedule::status(&settings)
+    );
+}
+
+#[test]
+fn invalid_cron_is_skipped() {
+    let scheduler = schedule::Scheduler::new(&[Schedule {
+        name: "vacuum".to_string(),
+        cron: "every sunday".to_string(),
+        job: ScheduledJob::Vacuum,
+    }]);
+
+    assert!(scheduler.is_empty());
+}
@@ -0,0 +1,128 @@
+use crate::{archive_of_our_own, fanfiction, script::ScriptSite, SiteScraper};
+
+const ARCHIVE_OF_OUR_OWN_DEFINITION: &str = include_str!("./definitions/archive-of-our-own.ron");
+const FANFICTION_DEFINITION: &str = include_str!("./definitions/fanfiction.ron");
+
+const ARCHIVE_OF_OUR_OWN_MULTIPLE_CHAPTERS_HTML: &str =
+    include_str!("./data/archive-of-our-own/multiple-chapters.html");
+const ARCHIVE_OF_OUR_OWN_SINGLE_CHAPTER_HTML: &str =
+    include_str!("./data/archive-of-our-own/single-chapter.html");
+
+const FANFICTION_MULTIPLE_CHAPTERS_HTML: &str =
+    include_str!("./data/fanfiction/multiple-chapters.html");
+const FANFICTION_SINGLE_CHAPTER_HTML: &str = include_str!("./data/fanfiction/single-chapter.html");
+
+// The script definitions should give the same results as the built in sites
+
+#[test]
+#[allow(non_snake_case)]
+fn archive_of_our_own__multiple_chapters() {
+    let site = ARCHIVE_OF_OUR_OWN_DEFINITION.parse::<ScriptSite>().unwrap();
+
+    assert_eq!(
+        archive_of_our_own::get_details(ARCHIVE_OF_OUR_OWN_MULTIPLE_CHAPTERS_HTML).unwrap(),
+        site.parse_details(ARCHIVE_OF_OUR_OWN_MULTIPLE_CHAPTERS_HTML)
+            .unwrap(),
+    );
+    assert_eq!(
+        archive_of_our_own::get_chapter(ARCHIVE_OF_OUR_OWN_MULTIPLE_CHAPTERS_HTML, 1).unwrap(),
+        site.parse_chapter(ARCHIVE_OF_OUR_OWN_MULTIPLE_CHAPTERS_HTML, 1)
+            .unwrap(),
+    );
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn archive_of_our_own__single_chapter() {
+    let site = ARCHIVE_OF_OUR_OWN_DEFINITION.parse::<ScriptSite>().unwrap();
+
+    assert_eq!(
+        archive_of_our_own::get_details(ARCHIVE_OF_OUR_OWN_SINGLE_CHAPTER_HTML).unwrap(),
+        site.parse_details(ARCHIVE_OF_OUR_OWN_SINGLE_CHAPTER_HTML)
+            .unwrap(),
+    );
+    assert_eq!(
+        archive_of_our_own::get_chapter(ARCHIVE_OF_OUR_OWN_SINGLE_CHAPTER_HTML, 1).unwrap(),
+        site.parse_chapter(ARCHIVE_OF_OUR_OWN_SINGLE_CHAPTER_HTML, 1)
+            .unwrap(),
+    );
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn archive_of_our_own__id_from_url() {
+    let site = ARCHIVE_OF_OUR_OWN_DEFINITION.parse::<ScriptSite>().unwrap();
+
+    assert_eq!(
+        "20797079",
+        site.id_from_url(
+            &"https://archiveofourown.org/works/20797079/chapters/49428320"
+                .parse()
+                .unwrap()
+        )
+        .unwrap(),
+    );
+    assert!(site
+        .id_from_url(&"https://archiveofourown.org/works".parse().unwrap())
+        .is_err());
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn fanfiction__multiple_chapters() {
+    let site = FANFICTION_DEFINITION.parse::<ScriptSite>().unwrap();
+
+    assert_eq!(
+        fanfiction::get_details(FANFICTION_MULTIPLE_CHAPTERS_HTML).unwrap(),
+        site.parse_details(FANFICTION_MULTIPLE_CHAPTERS_HTML)
+            .unwrap(),
+    );
+    assert_eq!(
+        fanfiction::get_chapter(FANFICTION_MULTIPLE_CHAPTERS_HTML).unwrap(),
+        site.parse_chapter(FANFICTION_MULTIPLE_CHAPTERS_HTML, 1)
+            .unwrap(),
+    );
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn fanfiction__single_chapter() {
+    let site = FANFICTION_DEFINITION.parse::<ScriptSite>().unwrap();
+
+    assert_eq!(
+        fanfiction::get_details(FANFICTION_SINGLE_CHAPTER_HTML).unwrap(),
+        site.parse_details(FANFICTION_SINGLE_CHAPTER_HTML).unwrap(),
+    );
+    assert_eq!(
+        fanfiction::get_chapter(FANFICTION_SINGLE_CHAPTER_HTML).unwrap(),
+        site.parse_chapter(FANFICTION_SINGLE_CHAPTER_HTML, 1)
+            .unwrap(),
+    );
+}
+
+#[test]
+fn runaway_script_fails() {
+    let site = FANFICTION_DEFINITION
+        .replace(
+            "fn chapters(details) {",
+            "fn chapters(details) {\n            loop {}\n",
+        )
+        .parse::<ScriptSite>()
+        .unwrap();
+
+    let err = site
+        .parse_details(FANFICTION_MULTIPLE_CHAPTERS_HTML)
+        .unwrap_err();
+
+    assert!(err.to_string().contains("over its limits"), "{}", err);
+}
+
+#[test]
+fn hosts() {
+    let site = FANFICTION_DEFINITION.parse::<ScriptSite>().unwrap();
+
+    assert_eq!(
+        vec!["fanfiction.net", "www.fanfiction.net", "m.fanfiction.net"],
+        site.hosts(),
+    );
+}
@@ -0,0 +1,92 @@
+use {
+    crate::{script::ScriptSite, SiteScraper},
+    std::sync::Arc,
+};
+
+#[test]
+fn init_from_url() {
+    let stories = vec![
+        (
+            "archive of our own",
+            "20797079",
+            "https://archiveofourown.org/works/20797079",
+        ),
+        (
+            "fanfiction",
+            "13397024",
+            "https://www.fanfiction.net/s/13397024/1/Fellow-Traveler",
+        ),
+        ("ficwad", "287300", "https://ficwad.com/story/287300"),
+        (
+            "questionable questing",
+            "13987",
+            "https://forum.questionablequesting.com/threads/the-wandering-inn-keeper.13987/",
+        ),
+        (
+            "royal road",
+            "35127",
+            "https://www.royalroad.com/fiction/35127/ashfall-academy",
+        ),
+        (
+            "spacebattles",
+            "874213",
+            "https://forums.spacebattles.com/threads/tidebreaker-original.874213/",
+        ),
+        (
+            "sufficient velocity",
+            "71420",
+            "https://forums.sufficientvelocity.com/threads/71420/threadmarks",
+        ),
+        (
+            "wattpad",
+            "219584761",
+            "https://www.wattpad.com/story/219584761-harbor-lights",
+        ),
+    ];
+
+    for (name, id, url) in stories {
+        let story = crate::init_from_url(url).unwrap();
+
+        assert_eq!(name, story.site().name());
+        assert_eq!(id, story.id());
+    }
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn init_from_url__unknown_host() {
+    assert!(crate::init_from_url("https://example.com/s/1234").is_err());
+}
+
+#[test]
+fn register() {
+    let site = include_str!("./definitions/fanfiction.ron")
+        .replace(
+            r#"hosts: ["fanfiction.net", "www.fanfiction.net", "m.fanfiction.net"]"#,
+            r#"hosts: ["fanfiction.example.com"]"#,
+        )
+        .parse::<ScriptSite>()
+        .unwrap();
+
+    crate::register(Arc::new(site)).unwrap();
+
+    let story = crate::init_from_url("https://fanfiction.example.com/s/1234/1").unwrap();
+
+    assert_eq!("fanfiction", story.site().name());
+    assert_eq!("1234", story.id());
+
+    // Built in sites are still found by their own hosts
+    assert!(crate::find("www.fanfiction.net").is_some());
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn register__builtin_host() {
+    let site = include_str!("./definitions/fanfiction.ron")
+        .parse::<ScriptSite>()
+        .unwrap();
+
+    let err = crate::register(Arc::new(site)).unwrap_err();
+
+    assert!(err.to_string().contains("fanfiction.net"), "{}", err);
+}
@@ -0,0 +1,74 @@
+use {
+    crate::{
+        models::{Chapter, Details, Language, Rating, State, TagType},
+        wattpad, Uri,
+    },
+    chrono::prelude::*,
+};
+
+const STORY_HTML: &str = include_str!("./data/wattpad/story.html");
+
+const PART_HTML: &str = include_str!("./data/wattpad/part.html");
+const PART_MARKDOWN: &str = include_str!("./data/wattpad/part.md");
+
+#[test]
+fn chapter() {
+    assert_eq!(
+        Chapter {
+            name: String::from("The Keeper"),
+            main: String::from(PART_MARKDOWN),
+            words: 91,
+            pre: String::new(),
+            post: String::new(),
+        },
+        wattpad::get_chapter(PART_HTML).unwrap(),
+    );
+}
+
+#[test]
+fn details() {
+    assert_eq!(Details {
+        name: String::from("Harbor Lights"),
+        summary: String::from("The lighthouse on Gull Point has been dark for eleven years. When Mara takes the keeper's job nobody else wants, she finds a logbook that was never supposed to be opened."),
+        chapters: 3,
+        language: Language::English,
+        rating: Rating::General,
+        state: State::Completed,
+        authors: vec![String::from("wren_writes")],
+        origins: vec![],
+        tags: vec![
+            (TagType::General, String::from("lighthouse")),
+            (TagType::General, String::from("mystery")),
+            (TagType::General, String::from("smalltown")),
+        ],
+        pairings: vec![],
+        series: None,
+        created: Utc.ymd(2020, 1, 5).and_hms(0, 0, 0),
+        updated: Utc.ymd(2020, 2, 2).and_hms(0, 0, 0),
+    }, wattpad::get_details(STORY_HTML).unwrap());
+}
+
+#[test]
+fn parts() {
+    assert_eq!(
+        vec![
+            String::from("836571201-harbor-lights-the-keeper"),
+            String::from("836571388-harbor-lights-the-logbook"),
+            String::from("836571502-harbor-lights-first-light"),
+        ],
+        wattpad::get_parts(STORY_HTML).unwrap(),
+    );
+}
+
+#[test]
+fn id_from_url() {
+    let story = "https://www.wattpad.com/story/219584761-harbor-lights"
+        .parse::<Uri>()
+        .unwrap();
+    let part = "https://www.wattpad.com/836571201-harbor-lights-the-keeper"
+        .parse::<Uri>()
+        .unwrap();
+
+    assert_eq!("219584761", wattpad::id_from_url(&story).unwrap());
+    assert!(wattpad::id_from_url(&part).is_err());
+}
@@ -0,0 +1,287 @@
+use {
+    crate::{
+        models::{Chapter, Details, Language, Rating, State, TagType},
+        xenforo,
+    },
+    chrono::prelude::*,
+};
+
+const QUESTIONABLE_QUESTING_THREADMARKS_HTML: &str =
+    include_str!("./data/questionable-questing/threadmarks.html");
+const QUESTIONABLE_QUESTING_READER_HTML: &str =
+    include_str!("./data/questionable-questing/reader.html");
+const QUESTIONABLE_QUESTING_CHAPTER_1_MARKDOWN: &str =
+    include_str!("./data/questionable-questing/chapter-1.md");
+const QUESTIONABLE_QUESTING_CHAPTER_2_MARKDOWN: &str =
+    include_str!("./data/questionable-questing/chapter-2.md");
+
+const SPACEBATTLES_THREADMARKS_HTML: &str = include_str!("./data/spacebattles/threadmarks.html");
+const SPACEBATTLES_READER_HTML: &str = include_str!("./data/spacebattles/reader.html");
+const SPACEBATTLES_CHAPTER_1_MARKDOWN: &str = include_str!("./data/spacebattles/chapter-1.md");
+const SPACEBATTLES_CHAPTER_2_MARKDOWN: &str = include_str!("./data/spacebattles/chapter-2.md");
+
+const SUFFICIENT_VELOCITY_THREADMARKS_HTML: &str =
+    include_str!("./data/sufficient-velocity/threadmarks.html");
+const SUFFICIENT_VELOCITY_READER_HTML: &str =
+    include_str!("./data/sufficient-velocity/reader.html");
+const SUFFICIENT_VELOCITY_CHAPTER_1_MARKDOWN: &str =
+    include_str!("./data/sufficient-velocity/chapter-1.md");
+const SUFFICIENT_VELOCITY_CHAPTER_2_MARKDOWN: &str =
+    include_str!("./data/sufficient-velocity/chapter-2.md");
+
+#[test]
+#[allow(non_snake_case)]
+fn questionable_questing__chapter() {
+    let name = xenforo::QUESTIONABLE_QUESTING.name;
+
+    assert_eq!(
+        Chapter {
+            name: String::from("1.01"),
+            main: String::from(QUESTIONABLE_QUESTING_CHAPTER_1_MARKDOWN),
+            words: 32,
+            pre: String::new(),
+            post: String::new(),
+        },
+        xenforo::get_chapter(QUESTIONABLE_QUESTING_READER_HTML, name, "3981220").unwrap(),
+    );
+    assert_eq!(
+        Chapter {
+            name: String::from("1.02"),
+            main: String::from(QUESTIONABLE_QUESTING_CHAPTER_2_MARKDOWN),
+            words: 23,
+            pre: String::new(),
+            post: String::new(),
+        },
+        xenforo::get_chapter(QUESTIONABLE_QUESTING_READER_HTML, name, "3990814").unwrap(),
+    );
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn questionable_questing__details() {
+    assert_eq!(
+        Details {
+            name: String::from("Lanternlight Inn"),
+            summary: String::new(),
+            chapters: 2,
+            language: Language::English,
+            rating: Rating::Mature,
+            state: State::Hiatus,
+            authors: vec![String::from("marrowfen")],
+            origins: vec![],
+            tags: vec![
+                (TagType::General, String::from("fantasy")),
+                (TagType::General, String::from("slice of life")),
+            ],
+            pairings: vec![],
+            series: None,
+            created: Utc.ymd(2019, 11, 23).and_hms(21, 5, 30),
+            updated: Utc.ymd(2019, 12, 7).and_hms(21, 0, 0),
+        },
+        xenforo::get_details(
+            QUESTIONABLE_QUESTING_THREADMARKS_HTML,
+            xenforo::QUESTIONABLE_QUESTING.name,
+            xenforo::QUESTIONABLE_QUESTING.rating.clone(),
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn questionable_questing__threadmarks() {
+    assert_eq!(
+        vec![String::from("3981220"), String::from("3990814")],
+        xenforo::get_threadmarks(
+            QUESTIONABLE_QUESTING_THREADMARKS_HTML,
+            xenforo::QUESTIONABLE_QUESTING.name,
+        )
+        .unwrap(),
+    );
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn spacebattles__chapter() {
+    let name = xenforo::SPACEBATTLES.name;
+
+    assert_eq!(
+        Chapter {
+            name: String::from("Chapter 1: Low Tide"),
+            main: String::from(SPACEBATTLES_CHAPTER_1_MARKDOWN),
+            words: 33,
+            pre: String::new(),
+            post: String::new(),
+        },
+        xenforo::get_chapter(SPACEBATTLES_READER_HTML, name, "70211534").unwrap(),
+    );
+    assert_eq!(
+        Chapter {
+            name: String::from("Chapter 2: The Bell"),
+            main: String::from(SPACEBATTLES_CHAPTER_2_MARKDOWN),
+            words: 39,
+            pre: String::new(),
+            post: String::new(),
+        },
+        xenforo::get_chapter(SPACEBATTLES_READER_HTML, name, "70298811").unwrap(),
+    );
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn spacebattles__details() {
+    assert_eq!(
+        Details {
+            name: String::from("Tidebreaker (Original)"),
+            summary: String::new(),
+            chapters: 2,
+            language: Language::English,
+            rating: Rating::Teen,
+            state: State::InProgress,
+            authors: vec![String::from("quill_and_anchor")],
+            origins: vec![],
+            tags: vec![
+                (TagType::General, String::from("original")),
+                (TagType::General, String::from("sea")),
+                (TagType::General, String::from("sailing")),
+            ],
+            pairings: vec![],
+            series: None,
+            created: Utc.ymd(2020, 8, 2).and_hms(19, 12, 5),
+            updated: Utc.ymd(2020, 8, 9).and_hms(18, 40, 0),
+        },
+        xenforo::get_details(
+            SPACEBATTLES_THREADMARKS_HTML,
+            xenforo::SPACEBATTLES.name,
+            xenforo::SPACEBATTLES.rating.clone(),
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn spacebattles__threadmarks() {
+    assert_eq!(
+        vec![String::from("70211534"), String::from("70298811")],
+        xenforo::get_threadmarks(SPACEBATTLES_THREADMARKS_HTML, xenforo::SPACEBATTLES.name)
+            .unwrap(),
+    );
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn sufficient_velocity__chapter() {
+    let name = xenforo::SUFFICIENT_VELOCITY.name;
+
+    assert_eq!(
+        Chapter {
+            name: String::from("Turn 1: Launch Window"),
+            main: String::from(SUFFICIENT_VELOCITY_CHAPTER_1_MARKDOWN),
+            words: 27,
+            pre: String::new(),
+            post: String::new(),
+        },
+        xenforo::get_chapter(SUFFICIENT_VELOCITY_READER_HTML, name, "16550127").unwrap(),
+    );
+    assert_eq!(
+        Chapter {
+            name: String::from("Turn 2: Burn"),
+            main: String::from(SUFFICIENT_VELOCITY_CHAPTER_2_MARKDOWN),
+            words: 22,
+            pre: String::new(),
+            post: String::new(),
+        },
+        xenforo::get_chapter(SUFFICIENT_VELOCITY_READER_HTML, name, "16581342").unwrap(),
+    );
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn sufficient_velocity__details() {
+    assert_eq!(
+        Details {
+            name: String::from("Orbital Mechanics (A Space Quest)"),
+            summary: String::new(),
+            chapters: 2,
+            language: Language::English,
+            rating: Rating::Teen,
+            state: State::Completed,
+            authors: vec![String::from("deltavee")],
+            origins: vec![],
+            tags: vec![
+                (TagType::General, String::from("quest")),
+                (TagType::General, String::from("science fiction")),
+            ],
+            pairings: vec![],
+            series: None,
+            created: Utc.ymd(2020, 5, 4).and_hms(12, 0, 0),
+            updated: Utc.ymd(2020, 5, 11).and_hms(12, 0, 0),
+        },
+        xenforo::get_details(
+            SUFFICIENT_VELOCITY_THREADMARKS_HTML,
+            xenforo::SUFFICIENT_VELOCITY.name,
+            xenforo::SUFFICIENT_VELOCITY.rating.clone(),
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn sufficient_velocity__threadmarks() {
+    assert_eq!(
+        vec![String::from("16550127"), String::from("16581342")],
+        xenforo::get_threadmarks(
+            SUFFICIENT_VELOCITY_THREADMARKS_HTML,
+            xenforo::SUFFICIENT_VELOCITY.name,
+        )
+        .unwrap(),
+    );
+}
+
+#[test]
+fn id_from_url() {
+    assert_eq!(
+        "874213",
+        xenforo::id_from_url(
+            &"https://forums.spacebattles.com/threads/tidebreaker-original.874213/"
+                .parse()
+                .unwrap()
+        )
+        .unwrap(),
+    );
+    assert_eq!(
+        "71420",
+        xenforo::id_from_url(
+            &"https://forums.sufficientvelocity.com/threads/71420/threadmarks"
+                .parse()
+                .unwrap()
+        )
+        .unwrap(),
+    );
+    assert!(xenforo::id_from_url(
+        &"https://forum.questionablequesting.com/members/marrowfen.9033/"
+            .parse()
+            .unwrap()
+    )
+    .is_err());
+}
+
+#[test]
+fn reader_url() {
+    assert_eq!(
+        "https://forums.spacebattles.com/threads/874213/reader/page-1",
+        xenforo::SPACEBATTLES
+            .reader_url("874213", 10)
+            .unwrap()
+            .to_string(),
+    );
+    assert_eq!(
+        "https://forums.spacebattles.com/threads/874213/reader/page-2",
+        xenforo::SPACEBATTLES
+            .reader_url("874213", 11)
+            .unwrap()
+            .to_string(),
+    );
+}
@@ -0,0 +1,263 @@
+//! Regression tests for every site's scraper, run against the saved pages in
+//! `src/tests/data`.
+//!
+//! Each fixture directory has a `fixtures.ron` giving a host of its site,
+//! which the site's scraper is found by, and listing the cases to run, the
+//! results of which are compared with the golden files next to the pages:
+//!
+//! ```text
+//! <name>.json  the story details, or the chapter without its content
+//! <name>.md    the chapter's content
+//! ```
+//!
+//! When a site changes its layout, save the new pages and run
+//! `cargo test -p stry-worker-scraper --test fixtures -- --bless` to write
+//! the new results to the golden files, any other argument only runs the
+//! fixture directories that contain it. Directories without a `fixtures.ron`
+//! are skipped.
+
+use {
+    serde::{Deserialize, Serialize},
+    std::{
+        env, fs,
+        path::{Path, PathBuf},
+        process,
+    },
+    stry_worker_scraper::{sites, SiteScraper},
+};
+
+#[derive(Debug, Deserialize)]
+struct Fixtures {
+    /// A host of the site the pages were saved from.
+    host: String,
+
+    cases: Vec<Case>,
+}
+
+#[derive(Debug, Deserialize)]
+enum Case {
+    Details {
+        name: String,
+        page: String,
+    },
+    Chapter {
+        name: String,
+        page: String,
+
+        /// The chapter number, for sites that have every chapter on one page.
+        #[serde(default = "first")]
+        chapter: u32,
+
+        /// The threadmarked post, for XenForo forums.
+        #[serde(default)]
+        post: Option<String>,
+    },
+}
+
+fn first() -> u32 {
+    1
+}
+
+impl Case {
+    fn name(&self) -> &str {
+        match self {
+            Case::Details { name, .. } | Case::Chapter { name, .. } => name,
+        }
+    }
+}
+
+/// A chapter without its content, which is kept as markdown instead.
+#[derive(Serialize)]
+struct ChapterGolden<'c> {
+    name: &'c str,
+
+    pre: &'c str,
+    post: &'c str,
+
+    words: u32,
+}
+
+/// Runs the case, returning the golden files and their expected contents.
+fn run(
+    site: &dyn SiteScraper,
+    directory: &Path,
+    case: &Case,
+) -> anyhow::Result<Vec<(PathBuf, String)>> {
+    match case {
+        Case::Details { name, page } => {
+            let html = fs::read_to_string(directory.join(page))?;
+
+            let details = site.parse_details(html)?;
+
+            Ok(vec![(
+                directory.join(format!("{}.json", name)),
+                json(&details)?,
+            )])
+        }
+        Case::Chapter {
+            name,
+            page,
+            chapter,
+            post,
+        } => {
+            let html = fs::read_to_string(directory.join(page))?;
+
+            let chapter = site.parse_chapter(html, *chapter, post.as_deref())?;
+
+            let golden = ChapterGolden {
+                name: &chapter.name,
+                pre: &chapter.pre,
+                post: &chapter.post,
+                words: chapter.words,
+            };
+
+            Ok(vec![
+                (directory.join(format!("{}.json", name)), json(&golden)?),
+                (directory.join(format!("{}.md", name)), chapter.main),
+            ])
+        }
+    }
+}
+
+fn json<T: Serialize>(value: &T) -> anyhow::Result<String> {
+    let mut json = serde_json::to_string_pretty(value)?;
+
+    json.push('\n');
+
+    Ok(json)
+}
+
+/// Compares the golden file with the result, writing the result instead when
+/// blessing.
+fn check(path: &Path, actual: &str, bless: bool) -> anyhow::Result<()> {
+    let expected = match fs::read_to_string(path) {
+        Ok(expected) => Some(expected),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+        Err(err) => return Err(err.into()),
+    };
+
+    if expected.as_deref() == Some(actual) {
+        return Ok(());
+    }
+
+    if bless {
+        fs::write(path, actual)?;
+
+        println!("    blessed {}", path.display());
+
+        return Ok(());
+    }
+
+    let expected = match expected {
+        Some(expected) => expected,
+        None => anyhow::bail!("{} does not exist, run with --bless", path.display()),
+    };
+
+    let expected = expected.split('\n').collect::<Vec<_>>();
+    let actual = actual.split('\n').collect::<Vec<_>>();
+
+    let line = (0..expected.len().max(actual.len()))
+        .find(|line| expected.get(*line) != actual.get(*line))
+        .unwrap_or(0);
+
+    let expected = expected.get(line).copied().unwrap_or("");
+    let actual = actual.get(line).copied().unwrap_or("");
+
+    anyhow::bail!(
+        "{} differs at line {}\n      expected: {:?}\n        actual: {:?}",
+        path.display(),
+        line + 1,
+        expected,
+        actual
+    )
+}
+
+fn fixture(name: &str, directory: &Path, bless: bool) -> anyhow::Result<usize> {
+    let manifest = directory.join("fixtures.ron");
+
+    let fixtures: Fixtures = ron::de::from_str(
+        &fs::read_to_string(&manifest)
+            .map_err(|err| anyhow::anyhow!("Unable to read {}: {}", manifest.display(), err))?,
+    )?;
+
+    let site = sites::find(&fixtures.host)
+        .ok_or_else(|| anyhow::anyhow!("No site found that can handle: {}", fixtures.host))?;
+
+    let mut failed = 0;
+
+    for case in &fixtures.cases {
+        let result = run(site.as_ref(), directory, case).and_then(|goldens| {
+            goldens
+                .iter()
+                .try_for_each(|(path, actual)| check(path, actual, bless))
+        });
+
+        match result {
+            Ok(()) => println!("fixture {}/{} ... ok", name, case.name()),
+            Err(err) => {
+                println!("fixture {}/{} ... FAILED\n    {}", name, case.name(), err);
+
+                failed += 1;
+            }
+        }
+    }
+
+    Ok(failed)
+}
+
+fn main() {
+    let mut bless = false;
+    let mut filters = Vec::new();
+
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--bless" => bless = true,
+            arg if arg.starts_with('-') => {}
+            arg => filters.push(arg.to_string()),
+        }
+    }
+
+    let data = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("tests")
+        .join("data");
+
+    let mut directories = fs::read_dir(&data)
+        .expect("Unable to read the fixture directory")
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()
+        .expect("Unable to read the fixture directory");
+
+    // Directories without a manifest only hold pages used by other tests
+    directories.retain(|directory| directory.join("fixtures.ron").is_file());
+    directories.sort();
+
+    let mut failed = 0;
+
+    for directory in directories {
+        let name = directory
+            .file_name()
+            .and_then(|name| name.to_str())
+            .expect("Fixture directory name is not UTF-8")
+            .to_string();
+
+        if !filters.is_empty() && !filters.iter().any(|filter| name.contains(filter.as_str())) {
+            continue;
+        }
+
+        match fixture(&name, &directory, bless) {
+            Ok(count) => failed += count,
+            Err(err) => {
+                println!("fixture {} ... FAILED\n    {}", name, err);
+
+                failed += 1;
+            }
+        }
+    }
+
+    if failed != 0 {
+        println!("\n{} fixture(s) failed", failed);
+
+        process::exit(1);
+    }
+}
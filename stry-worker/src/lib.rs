@@ -1,14 +1,26 @@
 use {
-    std::sync::Arc,
+    std::{sync::Arc, time::Duration},
     stry_backend::DataBackend,
     stry_common::LibraryDetails,
     stry_config::Config,
-    stry_evermore::{Evermore, Worker},
-    stry_worker_scraper::task,
-    tokio::sync::broadcast::Receiver,
+    stry_evermore::{Cancellation, Evermore, Handle, Worker},
+    stry_models::TaskEvent,
+    stry_worker_scraper::{
+        cache::Pruned,
+        events, http,
+        jobs::{self, Jobs},
+        script, task,
+    },
+    tokio::sync::broadcast::{Receiver, Sender},
 };
 
-pub async fn start(cfg: Arc<Config>, mut rx: Receiver<()>, backend: DataBackend) {
+pub async fn start(
+    cfg: Arc<Config>,
+    mut rx: Receiver<()>,
+    backend: DataBackend,
+    workers: Handle,
+    task_events: Sender<TaskEvent>,
+) {
     // worker::WorkerPool::new(
     //     async move { rx.recv().await.expect("Failed to listen for event") },
     //     backend,
@@ -17,13 +29,100 @@ pub async fn start(cfg: Arc<Config>, mut rx: Receiver<()>, backend: DataBackend)
     // )
     // .await;
 
-    Evermore::new(
-        async move { rx.recv().await.expect("Failed to listen for event") },
-        cfg.workers.as_count() as u8,
-        backend,
+    events::configure(task_events);
+
+    if let Err(err) = http::configure(&cfg.scraper) {
+        tracing::error!("Unable to configure the scraper's HTTP client: {}", err);
+    }
+
+    if let Some(sites) = &cfg.scraper.sites {
+        match script::load(sites) {
+            Ok(count) => tracing::info!("Loaded {} site definitions from {}", count, sites),
+            Err(err) => tracing::error!("Unable to load site definitions from {}: {}", sites, err),
+        }
+    }
+
+    let supports_jobs = backend.supports_jobs();
+
+    if supports_jobs {
+        match backend.reset_jobs().await {
+            Ok(0) => {}
+            Ok(count) => tracing::info!("Queued {} jobs left running by the last run", count),
+            Err(err) => tracing::error!("Unable to queue jobs left running: {}", err),
+        }
+    } else {
+        tracing::warn!("The backend does not support jobs, no job workers will be started");
+    }
+
+    let jobs = Jobs::builtin(&cfg.scraper).schedule(&cfg.schedule);
+
+    // Stories and background jobs get their own workers, so one failing
+    // doesn't stop and restart the other
+    let shutdown = Cancellation::new();
+
+    let scrapers = Evermore::with_handle(
+        {
+            let shutdown = shutdown.clone();
+
+            async move { shutdown.cancelled().await }
+        },
+        workers,
+        backend.clone(),
         |data: Worker<DataBackend>| Box::pin(task::task(data)),
     )
-    .await;
+    .restart(cfg.restart.clone())
+    .drain_timeout(Duration::from_secs(cfg.drain_timeout))
+    .on_failure(|failure| {
+        tracing::error!(
+            id = failure.id,
+            failures = failure.failures,
+            "Scraper worker has stopped after failing: {}",
+            failure.error
+        );
+    });
+
+    let runners = {
+        let cfg = cfg.clone();
+        let shutdown = shutdown.clone();
+
+        async move {
+            if !supports_jobs {
+                return;
+            }
+
+            Evermore::new(
+                async move { shutdown.cancelled().await },
+                cfg.workers,
+                backend,
+                move |data: Worker<DataBackend>| Box::pin(jobs::task(data, jobs.clone())),
+            )
+            .restart(cfg.restart.clone())
+            .drain_timeout(Duration::from_secs(cfg.drain_timeout))
+            .on_failure(|failure| {
+                tracing::error!(
+                    id = failure.id,
+                    failures = failure.failures,
+                    "Job worker has stopped after failing: {}",
+                    failure.error
+                );
+            })
+            .await;
+        }
+    };
+
+    let signal = async move {
+        rx.recv().await.expect("Failed to listen for event");
+
+        shutdown.cancel();
+    };
+
+    tokio::join!(signal, scrapers, runners);
+}
+
+/// Removes cached pages that have not been fetched in the given number of
+/// days, or every page if no days are given.
+pub fn prune_cache(cfg: &Config, older_than: Option<i64>) -> anyhow::Result<Pruned> {
+    http::prune_cache(&cfg.scraper, older_than.map(chrono::Duration::days))
 }
 
 pub fn library_details() -> Vec<LibraryDetails> {
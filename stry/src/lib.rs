@@ -6,6 +6,7 @@ use {
     std::sync::Arc,
     stry_backend::DataBackend,
     stry_config::Config,
+    stry_evermore::Handle,
     tokio::{runtime::Builder, sync::broadcast},
 };
 
@@ -45,6 +46,19 @@ pub fn start(cfg: Config) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Removes pages from the scraper's cache, see `stry_worker::prune_cache`.
+pub fn prune_cache(cfg: Config, older_than: Option<i64>) -> anyhow::Result<()> {
+    let pruned = stry_worker::prune_cache(&cfg, older_than)
+        .with_context(|| format!("Unable to prune cache {}", cfg.scraper.cache.directory))?;
+
+    println!(
+        "Removed {} cached URLs and {} cached pages from {}",
+        pruned.entries, pruned.bodies, cfg.scraper.cache.directory
+    );
+
+    Ok(())
+}
+
 async fn run(cfg: Arc<Config>) -> anyhow::Result<()> {
     let (tx, frontend_rx) = broadcast::channel::<()>(2);
     let download_rx = tx.subscribe();
@@ -65,12 +79,26 @@ async fn run(cfg: Arc<Config>) -> anyhow::Result<()> {
         .await
         .context("Unable to create backend instance")?;
 
+    // Shared so the dashboard can show and change the workers
+    let workers = Handle::new(cfg.workers);
+
+    // Task progress sent by the workers, streamed to the dashboard
+    let (task_events, _) = broadcast::channel(64);
+
     let download_handle = tokio::spawn(stry_worker::start(
         cfg.clone(),
         download_rx,
         backend.clone(),
+        workers.clone(),
+        task_events.clone(),
+    ));
+    let frontend_handle = tokio::spawn(stry_frontend::start(
+        cfg.clone(),
+        frontend_rx,
+        backend,
+        workers,
+        task_events,
     ));
-    let frontend_handle = tokio::spawn(stry_frontend::start(cfg.clone(), frontend_rx, backend));
 
     download_handle
         .await
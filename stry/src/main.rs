@@ -3,7 +3,7 @@ static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
 use {
     anyhow::Context,
-    clap::{App, Arg},
+    clap::{App, Arg, SubCommand},
     stry_config::Config,
     stry_generated_version::GIT_VERSION,
 };
@@ -18,9 +18,26 @@ fn main() -> anyhow::Result<()> {
         String::from("stry.ron")
     };
 
+    let prune_cache = match matches.subcommand_matches("prune-cache") {
+        Some(matches) => Some(
+            matches
+                .value_of("older-than")
+                .map(str::parse)
+                .transpose()
+                .context("Cache age must be a number of days")?,
+        ),
+        None => None,
+    };
+
     let cfg =
         Config::new_from_sources(path, matches).context("Failure to create config instance")?;
 
+    if let Some(older_than) = prune_cache {
+        stry::prune_cache(cfg, older_than)?;
+
+        return Ok(());
+    }
+
     stry::start(cfg)?;
 
     Ok(())
@@ -42,6 +59,7 @@ fn app<'b>(version: &'b str) -> App<'static, 'b> {
         .arg(value("backend-type", "t", "The type of the backend database", "TYPE")
             .possible_values(&["postgres", "sqlite"]))
         .arg(value("backend-username", "u", "Username for remote backend database user", "USERNAME"))
+        .arg(value("scraper-sites", "S", "Directory of site definitions for the scraper", "DIRECTORY"))
         .arg(value("server-ip", "i", "IP that the server will listen for requests on", "IP"))
         .arg(value("server-port", "p", "Port used by the server", "PORT"))
         .arg(value("tracing-directory", "o", "Directory to write tracing files to", "DIRECTORY"))
@@ -55,6 +73,9 @@ fn app<'b>(version: &'b str) -> App<'static, 'b> {
             .possible_values(&["both", "file", "stdout"]))
         .arg(flag("tracing-thread-ids", "D", "Logging output contains the ID of its source thread"))
         .arg(flag("tracing-thread-names", "n", "Logging output contains the name of its source thread"))
+        .subcommand(SubCommand::with_name("prune-cache")
+            .about("Removes pages from the scraper's cache")
+            .arg(value("older-than", "o", "Only remove pages that have not been fetched in this many days", "DAYS")))
         .version_short("v")
 }
 